@@ -0,0 +1,477 @@
+//! Anomaly detection: isolation forest, local outlier factor and robust covariance
+
+use rand::{rngs::StdRng, seq::index::sample, Rng};
+use smartcore::{
+    algorithm::neighbour::{
+        cover_tree::CoverTree, linear_search::LinearKNNSearch, KNNAlgorithmName,
+    },
+    linalg::{cholesky::CholeskyDecomposableMatrix, naive::dense_matrix::DenseMatrix, BaseMatrix},
+    math::distance::{
+        euclidian::Euclidian, hamming::Hamming, mahalanobis::Mahalanobis, manhattan::Manhattan,
+        minkowski::Minkowski, Distance as SmartcoreDistance, Distances,
+    },
+};
+
+use crate::settings::AnomalySettings;
+use crate::Distance;
+
+/// The largest number of concentration steps taken from each start of robust covariance
+const MAX_CONCENTRATION_STEPS: usize = 30;
+
+/// Added to the local reachability distances so that duplicate rows have a finite density
+const REACHABILITY_OFFSET: f32 = 1e-10;
+
+/// A fitted anomaly detector, which scores rows with higher scores for more anomalous rows
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum AnomalyDetector {
+    /// Isolation forest, scoring rows by their mean path length through the trees
+    IsolationForest {
+        /// The isolation trees
+        trees: Vec<IsolationNode>,
+        /// The number of rows each tree was grown from
+        samples: usize,
+    },
+    /// Local outlier factor, scoring rows by the density of their training neighbors over their
+    /// own
+    LocalOutlierFactor {
+        /// The training rows
+        x: DenseMatrix<f32>,
+        /// The number of nearest neighbors
+        k: usize,
+        /// The search algorithm used to find the nearest neighbors
+        algorithm: KNNAlgorithmName,
+        /// The distance metric used to find the nearest neighbors
+        distance: Distance,
+        /// The distance from each training row to its k-th nearest neighbor
+        k_distances: Vec<f32>,
+        /// The local reachability density of each training row
+        densities: Vec<f32>,
+    },
+    /// Robust covariance, scoring rows by their Mahalanobis distance from the robust mean
+    RobustCovariance {
+        /// The mean of the rows the estimate was fit to
+        location: Vec<f32>,
+        /// The Mahalanobis distance under the covariance of the rows the estimate was fit to
+        distance: Mahalanobis<f32, DenseMatrix<f32>>,
+    },
+}
+
+impl AnomalyDetector {
+    /// Grow an isolation forest, returning the model and the score of each row
+    pub fn isolation_forest(
+        x: &DenseMatrix<f32>,
+        settings: &AnomalySettings,
+        rng: &mut StdRng,
+    ) -> (Self, Vec<f32>) {
+        let rows = rows_of(x);
+        let samples = settings
+            .isolation_forest_settings
+            .max_samples
+            .clamp(1, rows.len().max(1));
+        let height_limit = usize::BITS - (samples - 1).leading_zeros();
+        let trees = (0..settings.isolation_forest_settings.n_trees)
+            .map(|_| {
+                let indices = sample(rng, rows.len(), samples).into_vec();
+                IsolationNode::grow(&rows, &indices, 0, height_limit as usize, rng)
+            })
+            .collect();
+        let model = Self::IsolationForest { trees, samples };
+        let scores = model.scores(x);
+        (model, scores)
+    }
+
+    /// Fit the local outlier factor, returning the model and the score of each row, where each
+    /// row is left out of its own neighbors
+    pub fn local_outlier_factor(
+        x: &DenseMatrix<f32>,
+        settings: &AnomalySettings,
+    ) -> (Self, Vec<f32>) {
+        let parameters = &settings.local_outlier_factor_settings;
+        let rows = rows_of(x);
+        let k = parameters.k.clamp(1, rows.len().saturating_sub(1).max(1));
+        let search = NeighborSearch::new(x, &parameters.algorithm, &parameters.distance);
+        let neighbors: Vec<Vec<(usize, f32)>> = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let mut found = search.find(row, (k + 1).min(rows.len()));
+                match found.iter().position(|&(other, _)| other == index) {
+                    Some(position) => {
+                        found.remove(position);
+                    }
+                    None => {
+                        found.pop();
+                    }
+                }
+                found
+            })
+            .collect();
+        let k_distances: Vec<f32> = neighbors
+            .iter()
+            .map(|found| found.last().map_or(0.0, |&(_, distance)| distance))
+            .collect();
+        let densities: Vec<f32> = neighbors
+            .iter()
+            .map(|found| reachability_density(found, &k_distances))
+            .collect();
+        let scores = neighbors
+            .iter()
+            .zip(&densities)
+            .map(|(found, &density)| outlier_factor(found, density, &densities))
+            .collect();
+        (
+            Self::LocalOutlierFactor {
+                x: x.clone(),
+                k,
+                algorithm: parameters.algorithm.clone(),
+                distance: parameters.distance.clone(),
+                k_distances,
+                densities,
+            },
+            scores,
+        )
+    }
+
+    /// Fit robust covariance with the minimum covariance determinant estimator (Rousseeuw and
+    /// Van Driessen, 1999), returning the model and the score of each row. Each random start is
+    /// refined by concentration steps, which refit to the rows closest under the current estimate
+    /// until they stop changing, and the start with the smallest covariance determinant is kept.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn robust_covariance(
+        x: &DenseMatrix<f32>,
+        settings: &AnomalySettings,
+        rng: &mut StdRng,
+    ) -> (Self, Vec<f32>) {
+        let parameters = &settings.robust_covariance_settings;
+        let (n, p) = x.shape();
+        let support = parameters
+            .support_fraction
+            .map_or_else(
+                || (n + p).div_ceil(2),
+                |fraction| (fraction * n as f32).ceil() as usize,
+            )
+            .max(p + 1)
+            .min(n);
+        let rows = rows_of(x);
+
+        let mut best: Option<(f32, Vec<usize>)> = None;
+        for _ in 0..parameters.n_starts.max(1) {
+            let mut subset = sample(rng, n, support).into_vec();
+            subset.sort_unstable();
+            for _ in 0..MAX_CONCENTRATION_STEPS {
+                let (location, distance) = estimate(x, &subset);
+                let distances: Vec<f32> = rows
+                    .iter()
+                    .map(|row| distance.distance(row, &location))
+                    .collect();
+                let mut closest: Vec<usize> = (0..n).collect();
+                closest.sort_by(|&a, &b| distances[a].total_cmp(&distances[b]));
+                closest.truncate(support);
+                closest.sort_unstable();
+                if closest == subset {
+                    break;
+                }
+                subset = closest;
+            }
+            let determinant = log_determinant(&covariance(x, &subset));
+            if best
+                .as_ref()
+                .is_none_or(|(best_determinant, _)| determinant < *best_determinant)
+            {
+                best = Some((determinant, subset));
+            }
+        }
+
+        let (location, distance) = estimate(x, &best.map(|(_, subset)| subset).unwrap_or_default());
+        let model = Self::RobustCovariance { location, distance };
+        let scores = model.scores(x);
+        (model, scores)
+    }
+
+    /// Score each row, with higher scores for more anomalous rows
+    #[allow(clippy::cast_precision_loss)]
+    pub fn scores(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        let rows = rows_of(x);
+        match self {
+            Self::IsolationForest { trees, samples } => {
+                let normalization = average_path_length(*samples).max(f32::EPSILON);
+                rows.iter()
+                    .map(|row| {
+                        let mean_path = trees.iter().map(|tree| tree.path_length(row)).sum::<f32>()
+                            / trees.len().max(1) as f32;
+                        (-mean_path / normalization).exp2()
+                    })
+                    .collect()
+            }
+            Self::LocalOutlierFactor {
+                x: x_train,
+                k,
+                algorithm,
+                distance,
+                k_distances,
+                densities,
+            } => {
+                let search = NeighborSearch::new(x_train, algorithm, distance);
+                rows.iter()
+                    .map(|row| {
+                        let found = search.find(row, *k);
+                        let density = reachability_density(&found, k_distances);
+                        outlier_factor(&found, density, densities)
+                    })
+                    .collect()
+            }
+            Self::RobustCovariance { location, distance } => rows
+                .iter()
+                .map(|row| distance.distance(row, location))
+                .collect(),
+        }
+    }
+}
+
+/// A node of an isolation tree
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum IsolationNode {
+    /// A random split of a random feature
+    Split {
+        /// The feature split on
+        feature: usize,
+        /// Rows with the feature at or below this value go left
+        value: f32,
+        /// The rows at or below the split
+        left: Box<Self>,
+        /// The rows above the split
+        right: Box<Self>,
+    },
+    /// A leaf, with the number of training rows that reached it
+    Leaf {
+        /// The number of training rows that reached the leaf
+        size: usize,
+    },
+}
+
+impl IsolationNode {
+    /// Grow a tree by splitting the rows at random until they are isolated or the height limit
+    /// is reached
+    fn grow(
+        rows: &[Vec<f32>],
+        indices: &[usize],
+        depth: usize,
+        height_limit: usize,
+        rng: &mut StdRng,
+    ) -> Self {
+        if indices.len() <= 1 || depth >= height_limit {
+            return Self::Leaf {
+                size: indices.len(),
+            };
+        }
+
+        // Only features that vary among the rows can separate them
+        let ranges: Vec<(usize, f32, f32)> = (0..rows[indices[0]].len())
+            .filter_map(|feature| {
+                let (min, max) = indices.iter().fold(
+                    (f32::INFINITY, f32::NEG_INFINITY),
+                    |(min, max), &index| {
+                        (min.min(rows[index][feature]), max.max(rows[index][feature]))
+                    },
+                );
+                (max > min).then_some((feature, min, max))
+            })
+            .collect();
+        if ranges.is_empty() {
+            return Self::Leaf {
+                size: indices.len(),
+            };
+        }
+
+        let (feature, min, max) = ranges[rng.gen_range(0..ranges.len())];
+        let value = rng.gen_range(min..max);
+        let (left, right): (Vec<usize>, Vec<usize>) = indices
+            .iter()
+            .partition(|&&index| rows[index][feature] <= value);
+        Self::Split {
+            feature,
+            value,
+            left: Box::new(Self::grow(rows, &left, depth + 1, height_limit, rng)),
+            right: Box::new(Self::grow(rows, &right, depth + 1, height_limit, rng)),
+        }
+    }
+
+    /// The depth at which a row leaves the tree, plus the average depth it would take to isolate
+    /// it among the other rows of its leaf
+    #[allow(clippy::cast_precision_loss)]
+    fn path_length(&self, row: &[f32]) -> f32 {
+        let mut node = self;
+        let mut depth = 0;
+        loop {
+            match node {
+                Self::Split {
+                    feature,
+                    value,
+                    left,
+                    right,
+                } => {
+                    node = if row[*feature] <= *value { left } else { right };
+                    depth += 1;
+                }
+                Self::Leaf { size } => return depth as f32 + average_path_length(*size),
+            }
+        }
+    }
+}
+
+/// The average path length of an unsuccessful search in a binary search tree of n rows, which
+/// normalizes the path lengths of isolation trees
+#[allow(clippy::cast_precision_loss)]
+fn average_path_length(n: usize) -> f32 {
+    match n {
+        0 | 1 => 0.0,
+        2 => 1.0,
+        _ => {
+            let n = n as f32;
+            let harmonic = (n - 1.0).ln() + 0.577_215_7;
+            2.0f32.mul_add(harmonic, -2.0 * (n - 1.0) / n)
+        }
+    }
+}
+
+/// The rows of a matrix as vectors
+fn rows_of(x: &DenseMatrix<f32>) -> Vec<Vec<f32>> {
+    (0..x.shape().0).map(|row| x.get_row_as_vec(row)).collect()
+}
+
+/// The local reachability density of a row from its neighbors and their k-distances
+#[allow(clippy::cast_precision_loss)]
+fn reachability_density(neighbors: &[(usize, f32)], k_distances: &[f32]) -> f32 {
+    let mean_reachability = neighbors
+        .iter()
+        .map(|&(neighbor, distance)| distance.max(k_distances[neighbor]))
+        .sum::<f32>()
+        / neighbors.len().max(1) as f32;
+    1.0 / (mean_reachability + REACHABILITY_OFFSET)
+}
+
+/// The mean density of a row's neighbors over its own density
+#[allow(clippy::cast_precision_loss)]
+fn outlier_factor(neighbors: &[(usize, f32)], density: f32, densities: &[f32]) -> f32 {
+    neighbors
+        .iter()
+        .map(|&(neighbor, _)| densities[neighbor])
+        .sum::<f32>()
+        / neighbors.len().max(1) as f32
+        / density
+}
+
+/// The covariance of some rows, with a small ridge on the diagonal so that it can be inverted
+#[allow(clippy::cast_precision_loss)]
+fn covariance(x: &DenseMatrix<f32>, subset: &[usize]) -> DenseMatrix<f32> {
+    let mut covariance = x.take(subset, 0).cov();
+    let p = covariance.shape().0;
+    let trace: f32 = (0..p).map(|i| covariance.get(i, i)).sum();
+    let ridge = 1e-5f32.mul_add(trace / p.max(1) as f32, f32::EPSILON);
+    for i in 0..p {
+        covariance.add_element_mut(i, i, ridge);
+    }
+    covariance
+}
+
+/// The mean and Mahalanobis distance of some rows
+fn estimate(
+    x: &DenseMatrix<f32>,
+    subset: &[usize],
+) -> (Vec<f32>, Mahalanobis<f32, DenseMatrix<f32>>) {
+    (
+        x.take(subset, 0).column_mean(),
+        Mahalanobis::new_from_covariance(&covariance(x, subset)),
+    )
+}
+
+/// The log determinant of a covariance matrix, or infinity if it is not positive definite
+fn log_determinant(covariance: &DenseMatrix<f32>) -> f32 {
+    covariance.cholesky().map_or(f32::INFINITY, |cholesky| {
+        let lower = cholesky.L();
+        (0..lower.shape().0)
+            .map(|i| 2.0 * lower.get(i, i).ln())
+            .sum()
+    })
+}
+
+/// The crate's distance metrics as a smartcore distance, for the nearest neighbor searches
+#[derive(Clone)]
+enum NeighborDistance {
+    /// Euclidean distance
+    Euclidean(Euclidian),
+    /// Manhattan distance
+    Manhattan(Manhattan),
+    /// Minkowski distance
+    Minkowski(Minkowski),
+    /// Mahalanobis distance under the covariance of the training rows
+    Mahalanobis(Mahalanobis<f32, DenseMatrix<f32>>),
+    /// Hamming distance
+    Hamming(Hamming),
+}
+
+impl NeighborDistance {
+    /// Build the distance metric for some training rows
+    fn new(x: &DenseMatrix<f32>, distance: &Distance) -> Self {
+        match distance {
+            Distance::Euclidean => Self::Euclidean(Distances::euclidian()),
+            Distance::Manhattan => Self::Manhattan(Distances::manhattan()),
+            Distance::Minkowski(p) => Self::Minkowski(Distances::minkowski(*p)),
+            Distance::Mahalanobis => Self::Mahalanobis(Distances::mahalanobis(x)),
+            Distance::Hamming => Self::Hamming(Distances::hamming()),
+        }
+    }
+}
+
+impl SmartcoreDistance<Vec<f32>, f32> for NeighborDistance {
+    fn distance(&self, a: &Vec<f32>, b: &Vec<f32>) -> f32 {
+        match self {
+            Self::Euclidean(distance) => distance.distance(a, b),
+            Self::Manhattan(distance) => distance.distance(a, b),
+            Self::Minkowski(distance) => distance.distance(a, b),
+            Self::Mahalanobis(distance) => distance.distance(a, b),
+            Self::Hamming(distance) => SmartcoreDistance::<Vec<f32>, f32>::distance(distance, a, b),
+        }
+    }
+}
+
+/// The nearest neighbor search of the KNN wrappers, over the training rows
+enum NeighborSearch {
+    /// Search every row
+    LinearSearch(LinearKNNSearch<Vec<f32>, f32, NeighborDistance>),
+    /// Search a cover tree
+    CoverTree(CoverTree<Vec<f32>, f32, NeighborDistance>),
+}
+
+impl NeighborSearch {
+    /// Index the training rows
+    fn new(x: &DenseMatrix<f32>, algorithm: &KNNAlgorithmName, distance: &Distance) -> Self {
+        let distance = NeighborDistance::new(x, distance);
+        match algorithm {
+            KNNAlgorithmName::LinearSearch => {
+                Self::LinearSearch(LinearKNNSearch::new(rows_of(x), distance).unwrap())
+            }
+            KNNAlgorithmName::CoverTree => {
+                Self::CoverTree(CoverTree::new(rows_of(x), distance).unwrap())
+            }
+        }
+    }
+
+    /// The k nearest training rows and their distances, from nearest to farthest
+    fn find(&self, row: &Vec<f32>, k: usize) -> Vec<(usize, f32)> {
+        let mut found: Vec<(usize, f32)> = match self {
+            Self::LinearSearch(search) => search.find(row, k),
+            Self::CoverTree(search) => search.find(row, k),
+        }
+        .unwrap()
+        .into_iter()
+        .map(|(index, distance, _)| (index, distance))
+        .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found
+    }
+}
@@ -0,0 +1,212 @@
+//! Bagging meta-estimator
+
+use rand::{seq::index::sample, Rng};
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+use std::time::{Duration, Instant};
+
+use super::{cross_validate, CrossValidation};
+use crate::settings::FinalModel;
+use crate::{Algorithm, Settings};
+
+/// The Bagging meta-estimator, which wraps any other algorithm.
+///
+/// Unlike the other wrappers, its parameters are part of the algorithm
+/// (`Algorithm::Bagging`) rather than of the settings, so that ensembles around different base
+/// algorithms can be compared in one run.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#bagging-meta-estimator)
+/// for a more in-depth description of the algorithm.
+pub struct BaggingWrapper {}
+
+impl BaggingWrapper {
+    /// Perform cross-validation and train the final model, like `ModelWrapper::cv_model`
+    ///
+    /// # Errors
+    ///
+    /// If the base algorithm cannot be fit to one of the bootstrap samples
+    #[allow(clippy::ptr_arg)]
+    pub fn cv_model(
+        bagging: &Algorithm,
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm, Duration, Vec<u8>), Failed> {
+        let start = Instant::now();
+        let results = cross_validate(
+            |x, y, bagging| {
+                BaggedEnsemble::fit(x, y, bagging, settings)
+                    .map(|ensemble| BoundEnsemble { ensemble, settings })
+            },
+            x,
+            y,
+            bagging,
+            settings.get_kfolds(x, y),
+            settings.get_metric(),
+        )?;
+        let end = Instant::now();
+        Ok((
+            results,
+            bagging.clone(),
+            end.duration_since(start),
+            match settings.final_model_approach {
+                FinalModel::None => vec![],
+                _ => Self::train(bagging, x, y, settings)?,
+            },
+        ))
+    }
+
+    /// Train a model
+    ///
+    /// # Errors
+    ///
+    /// If the base algorithm cannot be fit to one of the bootstrap samples
+    #[allow(clippy::ptr_arg)]
+    pub fn train(
+        bagging: &Algorithm,
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&BaggedEnsemble::fit(x, y, bagging, settings)?).unwrap())
+    }
+
+    /// Perform a prediction
+    #[allow(clippy::ptr_arg)]
+    pub fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, settings: &Settings) -> Vec<f32> {
+        let model: BaggedEnsemble = bincode::deserialize(final_model).unwrap();
+        model.predict(x, settings)
+    }
+}
+
+/// A single model of the ensemble and the features it was trained on
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BaggedMember {
+    /// The columns of the data used by this model
+    features: Vec<usize>,
+    /// The serialized base model
+    model: Vec<u8>,
+}
+
+/// A fitted bagging ensemble
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BaggedEnsemble {
+    /// The algorithm of every member
+    base: Algorithm,
+    /// The fitted members
+    members: Vec<BaggedMember>,
+}
+
+impl BaggedEnsemble {
+    /// Fit the base algorithm to bootstrap samples of the rows and random subsets of the features
+    #[allow(
+        clippy::ptr_arg,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        bagging: &Algorithm,
+        settings: &Settings,
+    ) -> Result<Self, Failed> {
+        let Algorithm::Bagging {
+            base,
+            n_estimators,
+            max_samples,
+            max_features,
+        } = bagging
+        else {
+            return Err(Failed::fit(
+                "Only a bagging algorithm can fit a bagging ensemble",
+            ));
+        };
+        if matches!(**base, Algorithm::Bagging { .. }) {
+            return Err(Failed::fit(
+                "Bagging cannot use itself as the base algorithm",
+            ));
+        }
+        let (n_rows, n_features) = x.shape();
+        let n_samples = ((n_rows as f32) * max_samples).ceil() as usize;
+        let n_columns = ((n_features as f32) * max_features)
+            .ceil()
+            .clamp(1.0, n_features as f32) as usize;
+        let mut rng = settings.rng("bagging", y);
+        let trainer = base.get_trainer();
+
+        let members = (0..*n_estimators)
+            .map(|_| {
+                let rows: Vec<usize> = (0..n_samples).map(|_| rng.gen_range(0..n_rows)).collect();
+                let mut features = sample(&mut rng, n_features, n_columns).into_vec();
+                features.sort_unstable();
+                let x_sample = x.take(&rows, 0).take(&features, 1);
+                let y_sample: Vec<f32> = rows.iter().map(|&row| y[row]).collect();
+                Ok(BaggedMember {
+                    features,
+                    model: trainer(&x_sample, &y_sample, settings)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Failed>>()?;
+
+        Ok(Self {
+            base: (**base).clone(),
+            members,
+        })
+    }
+
+    /// Average (regression) or take a majority vote of (classification) the members' predictions
+    #[allow(clippy::cast_precision_loss)]
+    fn predict(&self, x: &DenseMatrix<f32>, settings: &Settings) -> Vec<f32> {
+        let predictor = self.base.get_predictor();
+        let predictions: Vec<Vec<f32>> = self
+            .members
+            .iter()
+            .map(|member| predictor(&x.take(&member.features, 1), &member.model, settings))
+            .collect();
+        let (n, _) = x.shape();
+        (0..n)
+            .map(|row| {
+                let mut values: Vec<f32> = predictions.iter().map(|p| p[row]).collect();
+                if settings.is_classification() {
+                    most_common(&mut values)
+                } else {
+                    values.iter().sum::<f32>() / values.len() as f32
+                }
+            })
+            .collect()
+    }
+}
+
+/// A fitted ensemble together with the settings its members need to predict, for cross-validation
+struct BoundEnsemble<'a> {
+    /// The fitted ensemble
+    ensemble: BaggedEnsemble,
+    /// The settings used to train the members
+    settings: &'a Settings,
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for BoundEnsemble<'_> {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        Ok(self.ensemble.predict(x, self.settings))
+    }
+}
+
+/// The most common value, with ties going to the smallest value
+fn most_common(values: &mut [f32]) -> f32 {
+    values.sort_by(f32::total_cmp);
+    let mut best = (0, f32::NAN);
+    let mut start = 0;
+    for end in 1..=values.len() {
+        if end == values.len() || values[end].total_cmp(&values[start]).is_ne() {
+            if end - start > best.0 {
+                best = (end - start, values[start]);
+            }
+            start = end;
+        }
+    }
+    best.1
+}
@@ -1,13 +1,14 @@
 //! Categorical Naive Bayes Classifier.
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix,
     naive_bayes::categorical::CategoricalNB,
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Categorical Naive Bayes Classifier.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/naive_bayes.html#categorical-naive-bayes)
@@ -19,31 +20,28 @@ impl super::ModelWrapper for CategoricalNaiveBayesClassifierWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
-                CategoricalNB::fit,
+                settings.rebalanced(CategoricalNB::fit),
                 x,
                 y,
                 settings.categorical_nb_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::CategoricalNaiveBayes,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &CategoricalNB::fit(
-                x,
-                y,
-                settings.categorical_nb_settings.as_ref().unwrap().clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&CategoricalNB::fit(
+            x,
+            y,
+            settings.categorical_nb_settings.as_ref().unwrap().clone(),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
@@ -0,0 +1,237 @@
+//! Clustering: k-means and DBSCAN from smartcore, and agglomerative hierarchical clustering
+
+use smartcore::{
+    algorithm::neighbour::KNNAlgorithmName,
+    cluster::{
+        dbscan::{DBSCANParameters, DBSCAN},
+        kmeans::{KMeans, KMeansParameters},
+    },
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    math::distance::euclidian::Euclidian,
+};
+
+use crate::settings::{ClusteringSettings, Linkage};
+use crate::utils::{euclidean_distance, squared_distance};
+
+/// A fitted clustering, which assigns new rows to its clusters
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Clusterer {
+    /// K-means, assigning rows to the nearest centroid
+    KMeans {
+        /// The fitted model
+        model: KMeans<f32>,
+    },
+    /// DBSCAN, assigning rows to the most common cluster within the radius, or to noise
+    DensityBased {
+        /// The fitted model
+        model: DBSCAN<f32, Euclidian>,
+    },
+    /// Hierarchical clustering, assigning rows to the cluster of the nearest training row, which
+    /// suits every linkage, including single linkage with its long, thin clusters
+    Hierarchical {
+        /// The training data
+        x: DenseMatrix<f32>,
+        /// The cluster of each training row
+        clusters: Vec<f32>,
+    },
+}
+
+impl Clusterer {
+    /// Fit k-means with k clusters, returning the model and the cluster of each row
+    pub fn kmeans(
+        x: &DenseMatrix<f32>,
+        k: usize,
+        settings: &ClusteringSettings,
+    ) -> (Self, Vec<f32>) {
+        let model = KMeans::fit(
+            x,
+            KMeansParameters::default()
+                .with_k(k)
+                .with_max_iter(settings.kmeans_settings.max_iter),
+        )
+        .unwrap();
+        let clusters = model.predict(x).unwrap();
+        (Self::KMeans { model }, clusters)
+    }
+
+    /// Fit DBSCAN, returning the model and the cluster of each row, with -1 for noise
+    pub fn dbscan(x: &DenseMatrix<f32>, settings: &ClusteringSettings) -> (Self, Vec<f32>) {
+        let model = DBSCAN::fit(
+            x,
+            DBSCANParameters::default()
+                .with_eps(settings.dbscan_settings.eps)
+                .with_min_samples(settings.dbscan_settings.min_samples)
+                .with_algorithm(KNNAlgorithmName::CoverTree),
+        )
+        .unwrap();
+        let clusters = model.predict(x).unwrap();
+        (Self::DensityBased { model }, clusters)
+    }
+
+    /// Keep a cut of a dendrogram to assign new rows to its clusters
+    pub fn hierarchical(x: &DenseMatrix<f32>, clusters: &[f32]) -> Self {
+        Self::Hierarchical {
+            x: x.clone(),
+            clusters: clusters.to_vec(),
+        }
+    }
+
+    /// Assign each row to a cluster, with -1 for noise
+    pub fn predict(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        match self {
+            Self::KMeans { model } => model.predict(x).unwrap(),
+            Self::DensityBased { model } => model.predict(x).unwrap(),
+            Self::Hierarchical {
+                x: x_train,
+                clusters,
+            } => {
+                let training_rows: Vec<Vec<f32>> = (0..x_train.shape().0)
+                    .map(|row| x_train.get_row_as_vec(row))
+                    .collect();
+                (0..x.shape().0)
+                    .map(|row| {
+                        let row = x.get_row_as_vec(row);
+                        let (nearest, _) = training_rows.iter().enumerate().fold(
+                            (0, f32::INFINITY),
+                            |(nearest, best), (other, training_row)| {
+                                let distance = squared_distance(&row, training_row);
+                                if distance < best {
+                                    (other, distance)
+                                } else {
+                                    (nearest, best)
+                                }
+                            },
+                        );
+                        clusters[nearest]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The full merge history of agglomerative hierarchical clustering, which can be cut into any
+/// number of clusters
+pub struct Dendrogram {
+    /// The number of rows clustered
+    rows: usize,
+    /// The pairs of clusters merged, each named by one of its rows, from the closest pair up
+    merges: Vec<(usize, usize)>,
+}
+
+impl Dendrogram {
+    /// Build the dendrogram with the nearest-neighbor chain algorithm, which takes quadratic time
+    /// and memory for the reducible linkages used here (Murtagh, 1983)
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fit(x: &DenseMatrix<f32>, linkage: Linkage) -> Self {
+        let n = x.shape().0;
+        let data: Vec<Vec<f32>> = (0..n).map(|row| x.get_row_as_vec(row)).collect();
+
+        // Ward's method updates squared distances, the others plain distances
+        let ward = matches!(linkage, Linkage::Ward);
+        let mut distance = vec![0.0_f32; n * n];
+        for i in 0..n {
+            for j in i + 1..n {
+                let d = if ward {
+                    squared_distance(&data[i], &data[j])
+                } else {
+                    euclidean_distance(&data[i], &data[j])
+                };
+                distance[i * n + j] = d;
+                distance[j * n + i] = d;
+            }
+        }
+
+        let mut size = vec![1.0_f32; n];
+        let mut active = vec![true; n];
+        let mut chain: Vec<usize> = vec![];
+        let mut merges: Vec<(f32, usize, usize)> = vec![];
+        while merges.len() + 1 < n {
+            if chain.is_empty() {
+                chain.push(active.iter().position(|&is_active| is_active).unwrap());
+            }
+            let a = chain[chain.len() - 1];
+            let previous = chain.len().checked_sub(2).map(|index| chain[index]);
+
+            // Find the nearest cluster, preferring the previous link of the chain on ties
+            let (mut nearest, mut best) = previous.map_or((a, f32::INFINITY), |previous| {
+                (previous, distance[a * n + previous])
+            });
+            for b in (0..n).filter(|&b| active[b] && b != a) {
+                if distance[a * n + b] < best {
+                    (nearest, best) = (b, distance[a * n + b]);
+                }
+            }
+
+            if Some(nearest) != previous {
+                chain.push(nearest);
+                continue;
+            }
+
+            // The last two links are reciprocal nearest neighbors, so merge them into `a`
+            let b = nearest;
+            chain.truncate(chain.len() - 2);
+            for k in (0..n).filter(|&k| active[k] && k != a && k != b) {
+                let (to_a, to_b) = (distance[a * n + k], distance[b * n + k]);
+                let (size_a, size_b, size_k) = (size[a], size[b], size[k]);
+                let updated = match linkage {
+                    Linkage::Single => to_a.min(to_b),
+                    Linkage::Complete => to_a.max(to_b),
+                    Linkage::Average => size_a.mul_add(to_a, size_b * to_b) / (size_a + size_b),
+                    Linkage::Ward => {
+                        size_k.mul_add(
+                            -best,
+                            (size_a + size_k).mul_add(to_a, (size_b + size_k) * to_b),
+                        ) / (size_a + size_b + size_k)
+                    }
+                };
+                distance[a * n + k] = updated;
+                distance[k * n + a] = updated;
+            }
+            size[a] += size[b];
+            active[b] = false;
+            merges.push((best, a, b));
+        }
+
+        // The chain finds merges out of order, but the linkages never shrink as clusters grow
+        merges.sort_by(|first, second| first.0.total_cmp(&second.0));
+        Self {
+            rows: n,
+            merges: merges.into_iter().map(|(_, a, b)| (a, b)).collect(),
+        }
+    }
+
+    /// Cut the dendrogram into k clusters, returning the cluster of each row
+    pub fn cut(&self, k: usize) -> Vec<f32> {
+        let mut parent: Vec<usize> = (0..self.rows).collect();
+        let find = |parent: &mut Vec<usize>, mut row: usize| {
+            while parent[row] != row {
+                parent[row] = parent[parent[row]];
+                row = parent[row];
+            }
+            row
+        };
+        for &(a, b) in &self.merges[..self.rows.saturating_sub(k).min(self.merges.len())] {
+            let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+            parent[root_b] = root_a;
+        }
+
+        // Number the clusters in order of their first row
+        let mut roots: Vec<usize> = vec![];
+        (0..self.rows)
+            .map(|row| {
+                let root = find(&mut parent, row);
+                let cluster = roots
+                    .iter()
+                    .position(|&other| other == root)
+                    .unwrap_or_else(|| {
+                        roots.push(root);
+                        roots.len() - 1
+                    });
+                #[allow(clippy::cast_precision_loss)]
+                let cluster = cluster as f32;
+                cluster
+            })
+            .collect()
+    }
+}
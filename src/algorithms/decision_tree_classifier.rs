@@ -1,13 +1,14 @@
 //! Decision Tree Classifier.
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix,
     tree::decision_tree_classifier::DecisionTreeClassifier,
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Decision Tree Classifier.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/tree.html#classification)
@@ -19,10 +20,10 @@ impl super::ModelWrapper for DecisionTreeClassifierWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
-                DecisionTreeClassifier::fit,
+                settings.rebalanced(DecisionTreeClassifier::fit),
                 x,
                 y,
                 settings
@@ -30,28 +31,25 @@ impl super::ModelWrapper for DecisionTreeClassifierWrapper {
                     .as_ref()
                     .unwrap()
                     .clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::DecisionTreeClassifier,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &DecisionTreeClassifier::fit(
-                x,
-                y,
-                settings
-                    .decision_tree_classifier_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&DecisionTreeClassifier::fit(
+            x,
+            y,
+            settings
+                .decision_tree_classifier_settings
+                .as_ref()
+                .unwrap()
+                .clone(),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
@@ -1,13 +1,14 @@
 //! Decision Tree Regressor.
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix,
     tree::decision_tree_regressor::DecisionTreeRegressor,
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Decision Tree Regressor.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/tree.html#regression)
@@ -19,8 +20,8 @@ impl super::ModelWrapper for DecisionTreeRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
                 DecisionTreeRegressor::fit,
                 x,
@@ -30,28 +31,24 @@ impl super::ModelWrapper for DecisionTreeRegressorWrapper {
                     .as_ref()
                     .unwrap()
                     .clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::DecisionTreeRegressor,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &DecisionTreeRegressor::fit(
-                x,
-                y,
-                settings
-                    .decision_tree_regressor_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&DecisionTreeRegressor::fit(
+            x,
+            y,
+            settings
+                .decision_tree_regressor_settings
+                .as_ref()
+                .unwrap()
+                .clone(),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
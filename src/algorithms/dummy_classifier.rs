@@ -0,0 +1,152 @@
+//! Dummy Classifier
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+
+use crate::settings::DummyClassifierStrategy;
+
+use super::{cross_validate, CrossValidation};
+use crate::utils::class_indices;
+use crate::{Algorithm, Settings};
+
+/// The Dummy Classifier, a baseline that predicts classes without looking at the features.
+///
+/// Unlike the other classifiers, the training data is not rebalanced, so the baseline reflects
+/// the class frequencies actually observed.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/model_evaluation.html#dummy-estimators)
+/// for a more in-depth description of the algorithm.
+pub struct DummyClassifierWrapper {}
+
+impl DummyClassifierWrapper {
+    /// Predict the probability of each class, in ascending order of class label, which is the
+    /// same for every row, for calibration
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_scores(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Vec<Vec<f32>> {
+        let model: DummyClassifier = bincode::deserialize(final_model).unwrap();
+        vec![model.probabilities; x.shape().0]
+    }
+}
+
+impl super::ModelWrapper for DummyClassifierWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                |_, y, strategy| DummyClassifier::fit(y, strategy, settings),
+                x,
+                y,
+                settings
+                    .dummy_classifier_settings
+                    .as_ref()
+                    .unwrap()
+                    .strategy,
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::DummyClassifier,
+        ))
+    }
+
+    fn train(_x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let strategy = settings
+            .dummy_classifier_settings
+            .as_ref()
+            .unwrap()
+            .strategy;
+        Ok(bincode::serialize(&DummyClassifier::fit(y, strategy, settings)?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: DummyClassifier = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
+
+/// A fitted dummy classifier
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DummyClassifier {
+    /// How the classes are predicted
+    strategy: DummyClassifierStrategy,
+    /// The class labels in ascending order
+    classes: Vec<f32>,
+    /// The probability of each class, from the class frequencies or the priors
+    probabilities: Vec<f32>,
+    /// The seed for stratified predictions
+    seed: Option<u64>,
+}
+
+impl DummyClassifier {
+    /// Compute the class probabilities from the training targets
+    #[allow(clippy::cast_precision_loss)]
+    fn fit(
+        y: &[f32],
+        strategy: DummyClassifierStrategy,
+        settings: &Settings,
+    ) -> Result<Self, Failed> {
+        if y.is_empty() {
+            return Err(Failed::fit("Cannot fit a dummy classifier without targets"));
+        }
+        let members = class_indices(y);
+        let frequencies = members
+            .iter()
+            .map(|class| class.len() as f32 / y.len() as f32)
+            .collect();
+        let probabilities = match strategy {
+            DummyClassifierStrategy::Prior => settings.class_priors(y).unwrap_or(frequencies),
+            DummyClassifierStrategy::MostFrequent | DummyClassifierStrategy::Stratified => {
+                frequencies
+            }
+        };
+        Ok(Self {
+            strategy,
+            classes: members.iter().map(|class| y[class[0]]).collect(),
+            probabilities,
+            seed: settings.seed,
+        })
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for DummyClassifier {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        let (n, _) = x.shape();
+        Ok(match self.strategy {
+            DummyClassifierStrategy::MostFrequent | DummyClassifierStrategy::Prior => {
+                // Ties go to the smallest class label
+                let best = (0..self.classes.len())
+                    .rev()
+                    .max_by(|&a, &b| self.probabilities[a].total_cmp(&self.probabilities[b]))
+                    .unwrap_or(0);
+                vec![self.classes[best]; n]
+            }
+            DummyClassifierStrategy::Stratified => {
+                let mut rng = self
+                    .seed
+                    .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+                (0..n)
+                    .map(|_| {
+                        let mut draw: f32 = rng.gen();
+                        for (class, probability) in self.classes.iter().zip(&self.probabilities) {
+                            if draw < *probability {
+                                return *class;
+                            }
+                            draw -= probability;
+                        }
+                        self.classes[self.classes.len() - 1]
+                    })
+                    .collect()
+            }
+        })
+    }
+}
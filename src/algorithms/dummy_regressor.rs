@@ -0,0 +1,100 @@
+//! Dummy Regressor
+
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+
+use crate::settings::DummyRegressorStrategy;
+
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Dummy Regressor, a baseline that predicts a constant computed from the training targets.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/model_evaluation.html#dummy-estimators)
+/// for a more in-depth description of the algorithm.
+pub struct DummyRegressorWrapper {}
+
+impl super::ModelWrapper for DummyRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                DummyRegressor::fit,
+                x,
+                y,
+                settings.dummy_regressor_settings.as_ref().unwrap().strategy,
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::DummyRegressor,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let strategy = settings.dummy_regressor_settings.as_ref().unwrap().strategy;
+        Ok(bincode::serialize(&DummyRegressor::fit(x, y, strategy)?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: DummyRegressor = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
+
+/// A fitted dummy regressor
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DummyRegressor {
+    /// The value predicted for every row
+    constant: f32,
+}
+
+impl DummyRegressor {
+    /// Compute the constant prediction from the training targets
+    #[allow(
+        clippy::ptr_arg,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn fit(
+        _x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        strategy: DummyRegressorStrategy,
+    ) -> Result<Self, Failed> {
+        if y.is_empty() {
+            return Err(Failed::fit("Cannot fit a dummy regressor without targets"));
+        }
+        let quantile = match strategy {
+            DummyRegressorStrategy::Mean => {
+                return Ok(Self {
+                    constant: y.iter().sum::<f32>() / y.len() as f32,
+                })
+            }
+            DummyRegressorStrategy::Median => 0.5,
+            DummyRegressorStrategy::Quantile(quantile) => quantile.clamp(0.0, 1.0),
+        };
+
+        // Interpolate linearly between the two nearest order statistics
+        let mut sorted = y.clone();
+        sorted.sort_by(f32::total_cmp);
+        let position = quantile * (sorted.len() - 1) as f32;
+        let (below, above) = (position.floor() as usize, position.ceil() as usize);
+        let weight = position - position.floor();
+        Ok(Self {
+            constant: sorted[below] + weight * (sorted[above] - sorted[below]),
+        })
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for DummyRegressor {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        let (n, _) = x.shape();
+        Ok(vec![self.constant; n])
+    }
+}
@@ -1,12 +1,13 @@
 //! Elastic Net Regressor.
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, linear::elastic_net::ElasticNet,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix, linear::elastic_net::ElasticNet,
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Elastic Net Regressor.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/linear_model.html#elastic-net)
@@ -18,31 +19,27 @@ impl super::ModelWrapper for ElasticNetRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
                 ElasticNet::fit,
                 x,
                 y,
                 settings.elastic_net_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::ElasticNet,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &ElasticNet::fit(
-                x,
-                y,
-                settings.elastic_net_settings.as_ref().unwrap().clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&ElasticNet::fit(
+            x,
+            y,
+            settings.elastic_net_settings.as_ref().unwrap().clone(),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
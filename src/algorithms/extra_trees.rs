@@ -0,0 +1,319 @@
+//! Extremely randomized trees, shared by the extra trees regressor and classifier
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+
+use crate::utils::class_indices;
+
+/// Everything needed to fit an extra trees ensemble
+#[derive(Clone)]
+pub struct ForestParameters {
+    /// Whether to fit a classifier (Gini impurity, majority vote) or a regressor (variance, mean)
+    pub classification: bool,
+    /// The number of trees in the ensemble
+    pub n_trees: u16,
+    /// The maximum depth of each tree
+    pub max_depth: Option<u16>,
+    /// The minimum number of rows in each leaf
+    pub min_samples_leaf: usize,
+    /// The minimum number of rows needed to split a node
+    pub min_samples_split: usize,
+    /// The number of features considered at each split
+    pub m: Option<usize>,
+    /// The seed for the random features and thresholds
+    pub seed: Option<u64>,
+}
+
+/// A node of a fitted tree
+#[derive(serde::Serialize, serde::Deserialize)]
+enum Node {
+    /// A terminal node holding the prediction
+    Leaf(f32),
+    /// An internal node sending rows with `feature <= threshold` left and the rest right
+    Split {
+        /// The column compared against the threshold
+        feature: usize,
+        /// The value separating the two children
+        threshold: f32,
+        /// The index of the left child
+        left: usize,
+        /// The index of the right child
+        right: usize,
+    },
+}
+
+/// A fitted extra trees ensemble
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ExtraTrees {
+    /// The class labels in ascending order, empty for regression
+    classes: Vec<f32>,
+    /// The nodes of each tree, with the root first
+    trees: Vec<Vec<Node>>,
+}
+
+/// The state needed while growing a single tree
+struct TreeBuilder<'a> {
+    /// The training features
+    x: &'a DenseMatrix<f32>,
+    /// The targets: values for regression, or class indices for classification
+    y: &'a [f32],
+    /// The number of classes, or zero for regression
+    n_classes: usize,
+    /// The parameters of the ensemble
+    parameters: &'a ForestParameters,
+    /// The number of features considered at each split
+    m: usize,
+    /// The source of random features and thresholds for this tree
+    rng: StdRng,
+    /// The nodes grown so far
+    nodes: Vec<Node>,
+}
+
+impl ExtraTrees {
+    /// Fit an extra trees ensemble to the data.
+    #[allow(clippy::ptr_arg, clippy::needless_pass_by_value)]
+    pub fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        parameters: ForestParameters,
+    ) -> Result<Self, Failed> {
+        let (n_rows, n_features) = x.shape();
+        if n_rows == 0 {
+            return Err(Failed::fit("Cannot fit extra trees to an empty data set"));
+        }
+
+        // Classes are fit as indices so that leaves can count them
+        let (classes, targets) = if parameters.classification {
+            let members = class_indices(y);
+            let mut targets = vec![0.0; n_rows];
+            #[allow(clippy::cast_precision_loss)]
+            for (class, rows) in members.iter().enumerate() {
+                for &row in rows {
+                    targets[row] = class as f32;
+                }
+            }
+            (members.iter().map(|rows| y[rows[0]]).collect(), targets)
+        } else {
+            (vec![], y.clone())
+        };
+
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let m = parameters
+            .m
+            .unwrap_or_else(|| {
+                if parameters.classification {
+                    (n_features as f32).sqrt() as usize
+                } else {
+                    n_features / 3
+                }
+            })
+            .clamp(1, n_features.max(1));
+
+        let mut seeds = parameters
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+        let trees = (0..parameters.n_trees)
+            .map(|_| {
+                let mut builder = TreeBuilder {
+                    x,
+                    y: &targets,
+                    n_classes: classes.len(),
+                    parameters: &parameters,
+                    m,
+                    rng: StdRng::seed_from_u64(seeds.gen()),
+                    nodes: vec![],
+                };
+                builder.grow(&(0..n_rows).collect::<Vec<_>>(), 0);
+                builder.nodes
+            })
+            .collect();
+
+        Ok(Self { classes, trees })
+    }
+}
+
+impl ExtraTrees {
+    /// The fraction of the trees voting for each class, in ascending order of class label, for
+    /// each row
+    #[allow(clippy::cast_precision_loss)]
+    pub fn predict_scores(&self, x: &DenseMatrix<f32>) -> Vec<Vec<f32>> {
+        let (n, _) = x.shape();
+        (0..n)
+            .map(|row| {
+                self.votes(x, row)
+                    .into_iter()
+                    .map(|votes| votes as f32 / self.trees.len() as f32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The prediction of each tree for a row
+    fn tree_predictions<'a>(
+        &'a self,
+        x: &'a DenseMatrix<f32>,
+        row: usize,
+    ) -> impl Iterator<Item = f32> + 'a {
+        self.trees.iter().map(move |nodes| {
+            let mut node = 0;
+            loop {
+                match nodes[node] {
+                    Node::Leaf(value) => return value,
+                    Node::Split {
+                        feature,
+                        threshold,
+                        left,
+                        right,
+                    } => {
+                        node = if x.get(row, feature) <= threshold {
+                            left
+                        } else {
+                            right
+                        };
+                    }
+                }
+            }
+        })
+    }
+
+    /// The number of trees voting for each class for a row
+    fn votes(&self, x: &DenseMatrix<f32>, row: usize) -> Vec<usize> {
+        let mut votes = vec![0_usize; self.classes.len()];
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        for class in self.tree_predictions(x, row) {
+            votes[class as usize] += 1;
+        }
+        votes
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for ExtraTrees {
+    #[allow(clippy::cast_precision_loss)]
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        let (n, _) = x.shape();
+        Ok((0..n)
+            .map(|row| {
+                if self.classes.is_empty() {
+                    self.tree_predictions(x, row).sum::<f32>() / self.trees.len() as f32
+                } else {
+                    let votes = self.votes(x, row);
+                    let best = (0..votes.len())
+                        .max_by_key(|&class| (votes[class], std::cmp::Reverse(class)))
+                        .unwrap_or(0);
+                    self.classes[best]
+                }
+            })
+            .collect())
+    }
+}
+
+impl TreeBuilder<'_> {
+    /// Grow the subtree for the given rows and return the index of its root node
+    fn grow(&mut self, rows: &[usize], depth: u16) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(Node::Leaf(self.leaf_value(rows)));
+
+        let can_split = rows.len() >= self.parameters.min_samples_split.max(2)
+            && self.parameters.max_depth.is_none_or(|max| depth < max)
+            && self.impurity(rows) > f32::EPSILON;
+        if !can_split {
+            return index;
+        }
+
+        if let Some((feature, threshold)) = self.random_split(rows) {
+            let (left_rows, right_rows): (Vec<usize>, Vec<usize>) = rows
+                .iter()
+                .partition(|&&row| self.x.get(row, feature) <= threshold);
+            let left = self.grow(&left_rows, depth + 1);
+            let right = self.grow(&right_rows, depth + 1);
+            self.nodes[index] = Node::Split {
+                feature,
+                threshold,
+                left,
+                right,
+            };
+        }
+        index
+    }
+
+    /// Draw one random threshold for each of `m` random features and keep the split that
+    /// reduces impurity the most, or `None` if no split leaves enough rows on both sides
+    fn random_split(&mut self, rows: &[usize]) -> Option<(usize, f32)> {
+        let (_, n_features) = self.x.shape();
+        let mut features: Vec<usize> = (0..n_features).collect();
+        features.shuffle(&mut self.rng);
+
+        let mut best: Option<(f32, usize, f32)> = None;
+        for &feature in features.iter().take(self.m) {
+            let (low, high) =
+                rows.iter()
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &row| {
+                        let value = self.x.get(row, feature);
+                        (lo.min(value), hi.max(value))
+                    });
+            if high - low <= f32::EPSILON {
+                continue;
+            }
+            let threshold = self.rng.gen_range(low..high);
+            let (left, right): (Vec<usize>, Vec<usize>) = rows
+                .iter()
+                .partition(|&&row| self.x.get(row, feature) <= threshold);
+            if left.len() < self.parameters.min_samples_leaf
+                || right.len() < self.parameters.min_samples_leaf
+            {
+                continue;
+            }
+            let score = self.impurity(&left) + self.impurity(&right);
+            if best.is_none_or(|(best_score, _, _)| score < best_score) {
+                best = Some((score, feature, threshold));
+            }
+        }
+        best.map(|(_, feature, threshold)| (feature, threshold))
+    }
+
+    /// The total impurity of the rows: the sum of squared deviations for regression, or the
+    /// Gini impurity scaled by the row count for classification
+    #[allow(clippy::cast_precision_loss)]
+    fn impurity(&self, rows: &[usize]) -> f32 {
+        let n = rows.len() as f32;
+        if self.n_classes > 0 {
+            let counts = self.class_counts(rows);
+            n - counts.iter().map(|&c| (c * c) as f32).sum::<f32>() / n
+        } else {
+            let sum: f32 = rows.iter().map(|&row| self.y[row]).sum();
+            let sum_of_squares: f32 = rows.iter().map(|&row| self.y[row] * self.y[row]).sum();
+            sum_of_squares - sum * sum / n
+        }
+    }
+
+    /// The prediction for a leaf holding the rows: their mean, or their most common class index
+    #[allow(clippy::cast_precision_loss)]
+    fn leaf_value(&self, rows: &[usize]) -> f32 {
+        if self.n_classes > 0 {
+            let counts = self.class_counts(rows);
+            (0..counts.len())
+                .max_by_key(|&class| (counts[class], std::cmp::Reverse(class)))
+                .unwrap_or(0) as f32
+        } else {
+            rows.iter().map(|&row| self.y[row]).sum::<f32>() / rows.len().max(1) as f32
+        }
+    }
+
+    /// The number of rows in each class
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn class_counts(&self, rows: &[usize]) -> Vec<usize> {
+        let mut counts = vec![0; self.n_classes];
+        for &row in rows {
+            counts[self.y[row] as usize] += 1;
+        }
+        counts
+    }
+}
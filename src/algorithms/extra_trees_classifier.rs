@@ -0,0 +1,71 @@
+//! Extra Trees Classifier
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::extra_trees::{ExtraTrees, ForestParameters};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Extra Trees (extremely randomized trees) Classifier.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#extremely-randomized-trees)
+/// for a more in-depth description of the algorithm.
+pub struct ExtraTreesClassifierWrapper {}
+
+impl ExtraTreesClassifierWrapper {
+    /// Get the forest parameters, seeded from the settings if a seed has been set.
+    const fn parameters(settings: &Settings) -> ForestParameters {
+        let parameters = settings.extra_trees_classifier_settings.as_ref().unwrap();
+        ForestParameters {
+            classification: true,
+            n_trees: parameters.n_trees,
+            max_depth: parameters.max_depth,
+            min_samples_leaf: parameters.min_samples_leaf,
+            min_samples_split: parameters.min_samples_split,
+            m: parameters.m,
+            seed: settings.seed,
+        }
+    }
+
+    /// Predict the fraction of the trees voting for each class, in ascending order of class
+    /// label, for calibration
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_scores(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Vec<Vec<f32>> {
+        let model: ExtraTrees = bincode::deserialize(final_model).unwrap();
+        model.predict_scores(x)
+    }
+}
+
+impl super::ModelWrapper for ExtraTreesClassifierWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                settings.rebalanced(ExtraTrees::fit),
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::ExtraTreesClassifier,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&ExtraTrees::fit(x, y, Self::parameters(settings))?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: ExtraTrees = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
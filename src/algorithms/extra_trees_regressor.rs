@@ -0,0 +1,58 @@
+//! Extra Trees Regressor
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::extra_trees::{ExtraTrees, ForestParameters};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Extra Trees (extremely randomized trees) Regressor.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#extremely-randomized-trees)
+/// for a more in-depth description of the algorithm.
+pub struct ExtraTreesRegressorWrapper {}
+
+impl ExtraTreesRegressorWrapper {
+    /// Get the forest parameters, seeded from the settings if a seed has been set.
+    const fn parameters(settings: &Settings) -> ForestParameters {
+        let parameters = settings.extra_trees_regressor_settings.as_ref().unwrap();
+        ForestParameters {
+            classification: false,
+            n_trees: parameters.n_trees,
+            max_depth: parameters.max_depth,
+            min_samples_leaf: parameters.min_samples_leaf,
+            min_samples_split: parameters.min_samples_split,
+            m: parameters.m,
+            seed: settings.seed,
+        }
+    }
+}
+
+impl super::ModelWrapper for ExtraTreesRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                ExtraTrees::fit,
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::ExtraTreesRegressor,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&ExtraTrees::fit(x, y, Self::parameters(settings))?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: ExtraTrees = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
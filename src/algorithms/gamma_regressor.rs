@@ -0,0 +1,60 @@
+//! Gamma Regressor
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::generalized_linear::{GeneralizedLinearModel, LinearModelParameters, Objective};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Gamma Regressor.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/linear_model.html#generalized-linear-models)
+/// for a more in-depth description of the algorithm.
+pub struct GammaRegressorWrapper {}
+
+impl GammaRegressorWrapper {
+    /// Get the linear model parameters.
+    const fn parameters(settings: &Settings) -> LinearModelParameters {
+        let parameters = settings.gamma_settings.as_ref().unwrap();
+        LinearModelParameters {
+            objective: Objective::Tweedie { power: 2.0 },
+            alpha: parameters.alpha,
+            max_iter: parameters.max_iter,
+            tol: parameters.tol,
+        }
+    }
+}
+
+impl super::ModelWrapper for GammaRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                GeneralizedLinearModel::fit,
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::Gamma,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&GeneralizedLinearModel::fit(
+            x,
+            y,
+            Self::parameters(settings),
+        )?)
+        .unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GeneralizedLinearModel = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
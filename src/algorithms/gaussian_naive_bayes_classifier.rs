@@ -1,49 +1,106 @@
 //! Gaussian Naive Bayes Classifier
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
-    naive_bayes::gaussian::GaussianNB,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    naive_bayes::gaussian::{GaussianNB, GaussianNBParameters},
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Gaussian Naive Bayes Classifier.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/naive_bayes.html#gaussian-naive-bayes)
 /// for a more in-depth description of the algorithm.
 pub struct GaussianNaiveBayesClassifierWrapper {}
 
+impl GaussianNaiveBayesClassifierWrapper {
+    /// Fill in the priors implied by the class weights, unless priors were set explicitly.
+    fn with_class_priors(
+        mut parameters: GaussianNBParameters<f32>,
+        y: &[f32],
+        settings: &Settings,
+    ) -> GaussianNBParameters<f32> {
+        if parameters.priors.is_none() {
+            parameters.priors = settings.class_priors(y);
+        }
+        parameters
+    }
+
+    /// Predict the joint log likelihood of each class, its log prior plus the log likelihood of
+    /// the row, in ascending order of class label, for calibration
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_scores(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Vec<Vec<f32>> {
+        let model: GaussianNB<f32, DenseMatrix<f32>> = bincode::deserialize(final_model).unwrap();
+        let (n, _) = x.shape();
+        (0..n)
+            .map(|row| {
+                let row = x.get_row_as_vec(row);
+                model
+                    .class_priors()
+                    .iter()
+                    .zip(model.theta())
+                    .zip(model.var())
+                    .map(|((prior, means), variances)| {
+                        prior.ln()
+                            + row
+                                .iter()
+                                .zip(means)
+                                .zip(variances)
+                                .map(|((value, mean), variance)| {
+                                    let variance = variance.max(f32::EPSILON);
+                                    -(value - mean).powi(2) / (2.0 * variance)
+                                        - (2.0 * std::f32::consts::PI * variance).ln() / 2.0
+                                })
+                                .sum::<f32>()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
 impl super::ModelWrapper for GaussianNaiveBayesClassifierWrapper {
     fn cv(
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
-                GaussianNB::fit,
+                |x, y, parameters| {
+                    let (x, y) = &settings.resample(x, y, &mut settings.rng("rebalancing", y));
+                    GaussianNB::fit(x, y, Self::with_class_priors(parameters, y, settings))
+                },
                 x,
                 y,
                 settings.gaussian_nb_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::GaussianNaiveBayes,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &GaussianNB::fit(
-                x,
-                y,
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        // The class weights become priors rather than duplicated rows
+        let (x, y) = &settings.resample(x, y, &mut settings.rng("rebalancing", y));
+        Ok(bincode::serialize(&GaussianNB::fit(
+            x,
+            y,
+            Self::with_class_priors(
                 settings.gaussian_nb_settings.as_ref().unwrap().clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+                y,
+                settings,
+            ),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
@@ -0,0 +1,520 @@
+//! Gaussian Process Regressor
+
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+
+use crate::settings::{GaussianProcessKernel, GaussianProcessRegressorParameters};
+
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The hyperparameters are kept between `exp(-LOG_BOUND)` and `exp(LOG_BOUND)`
+const LOG_BOUND: f64 = 11.5;
+
+/// The step size of the Adam optimizer, in log space
+const LEARNING_RATE: f64 = 0.1;
+
+/// Stop once no gradient of the mean log marginal likelihood is larger than this
+const TOLERANCE: f64 = 1e-5;
+
+/// Added to the diagonal of the covariance matrix to keep it numerically positive definite
+const JITTER: f64 = 1e-8;
+
+/// The Gaussian Process Regressor.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/gaussian_process.html#gaussian-process-regression-gpr)
+/// for a more in-depth description of the algorithm.
+pub struct GaussianProcessRegressorWrapper {}
+
+impl GaussianProcessRegressorWrapper {
+    /// Predict the mean and standard deviation of the predictive distribution
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_with_std(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> (Vec<f32>, Vec<f32>) {
+        let model: GaussianProcess = bincode::deserialize(final_model).unwrap();
+        model.predict_with_std(x)
+    }
+}
+
+impl super::ModelWrapper for GaussianProcessRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                GaussianProcess::fit,
+                x,
+                y,
+                settings
+                    .gaussian_process_regressor_settings
+                    .clone()
+                    .unwrap(),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::GaussianProcessRegressor,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let parameters = settings
+            .gaussian_process_regressor_settings
+            .clone()
+            .unwrap();
+        Ok(bincode::serialize(&GaussianProcess::fit(x, y, parameters)?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GaussianProcess = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
+
+/// The kernel hyperparameters and noise variance
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct Hyperparameters {
+    /// The variance of the signal
+    signal_variance: f64,
+    /// The variance of the observation noise
+    noise: f64,
+    /// The length scale of the stationary kernels
+    length_scale: f64,
+}
+
+impl Hyperparameters {
+    /// The hyperparameters in log space, leaving out the length scale for the linear kernel
+    fn to_log(self, kernel: GaussianProcessKernel) -> Vec<f64> {
+        let mut theta = vec![self.signal_variance.ln(), self.noise.ln()];
+        if !matches!(kernel, GaussianProcessKernel::Linear) {
+            theta.push(self.length_scale.ln());
+        }
+        theta
+    }
+
+    /// The hyperparameters from log space
+    fn from_log(theta: &[f64]) -> Self {
+        Self {
+            signal_variance: theta[0].exp(),
+            noise: theta[1].exp(),
+            length_scale: theta.get(2).map_or(1.0, |value| value.exp()),
+        }
+    }
+}
+
+/// The factorized covariance matrix of the training data, with the log marginal likelihood of the
+/// targets and its gradient with respect to the log hyperparameters
+struct Evidence {
+    /// The lower Cholesky factor of the covariance matrix, row-major
+    cholesky: Vec<f64>,
+    /// The covariance matrix times the weights, which gives the targets
+    weights: Vec<f64>,
+    /// The log marginal likelihood
+    log_likelihood: f64,
+    /// The gradient of the log marginal likelihood
+    gradient: Vec<f64>,
+}
+
+/// A fitted Gaussian process
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GaussianProcess {
+    /// The covariance function
+    kernel: GaussianProcessKernel,
+    /// The fitted hyperparameters
+    hyperparameters: Hyperparameters,
+    /// The mean of each feature, for standardization
+    x_means: Vec<f64>,
+    /// The standard deviation of each feature, for standardization
+    x_scales: Vec<f64>,
+    /// The mean of the targets
+    y_mean: f64,
+    /// The standard deviation of the targets
+    y_scale: f64,
+    /// The standardized training inputs
+    x: Vec<Vec<f64>>,
+    /// The weight of each training row in the predictive mean
+    weights: Vec<f64>,
+    /// The lower Cholesky factor of the training covariance matrix, row-major
+    cholesky: Vec<f64>,
+}
+
+impl GaussianProcess {
+    /// Fit a Gaussian process, tuning the hyperparameters by maximizing the log marginal
+    /// likelihood with Adam in log space.
+    #[allow(
+        clippy::ptr_arg,
+        clippy::needless_pass_by_value,
+        clippy::cast_precision_loss,
+        clippy::many_single_char_names
+    )]
+    fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        parameters: GaussianProcessRegressorParameters,
+    ) -> Result<Self, Failed> {
+        let (n, n_features) = x.shape();
+        if n == 0 {
+            return Err(Failed::fit("Cannot fit a Gaussian process to no data"));
+        }
+        if let GaussianProcessKernel::Matern { nu } = parameters.kernel {
+            if ![0.5, 1.5, 2.5].contains(&nu) {
+                return Err(Failed::fit(&format!(
+                    "Matérn kernels need nu of 0.5, 1.5 or 2.5, not {nu}"
+                )));
+            }
+        }
+
+        // Standardize the inputs and targets
+        let mut x_means = vec![0.0; n_features];
+        let mut x_scales = vec![1.0; n_features];
+        for column in 0..n_features {
+            let values: Vec<f64> = (0..n).map(|row| f64::from(x.get(row, column))).collect();
+            let (mean, scale) = mean_and_scale(&values);
+            x_means[column] = mean;
+            x_scales[column] = scale;
+        }
+        let inputs: Vec<Vec<f64>> = (0..n)
+            .map(|row| {
+                (0..n_features)
+                    .map(|column| {
+                        (f64::from(x.get(row, column)) - x_means[column]) / x_scales[column]
+                    })
+                    .collect()
+            })
+            .collect();
+        let targets: Vec<f64> = y.iter().map(|&value| f64::from(value)).collect();
+        let (y_mean, y_scale) = mean_and_scale(&targets);
+        let targets: Vec<f64> = targets
+            .iter()
+            .map(|value| (value - y_mean) / y_scale)
+            .collect();
+
+        // The distances (or dot products) between rows do not change during the optimization
+        let mut base = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..=i {
+                let value = base_value(parameters.kernel, &inputs[i], &inputs[j]);
+                base[i * n + j] = value;
+                base[j * n + i] = value;
+            }
+        }
+
+        // Maximize the log marginal likelihood, keeping the best hyperparameters seen
+        let initial = Hyperparameters {
+            signal_variance: f64::from(parameters.signal_variance),
+            noise: f64::from(parameters.noise),
+            length_scale: f64::from(parameters.length_scale),
+        };
+        let mut theta: Vec<f64> = initial
+            .to_log(parameters.kernel)
+            .iter()
+            .map(|value| value.clamp(-LOG_BOUND, LOG_BOUND))
+            .collect();
+        let mut best = (f64::NEG_INFINITY, theta.clone());
+        let mut first_moment = vec![0.0; theta.len()];
+        let mut second_moment = vec![0.0; theta.len()];
+        let (beta_1, beta_2): (f64, f64) = (0.9, 0.999);
+        for step in (1..).take(parameters.max_iter) {
+            let Ok(evidence) = evidence(parameters.kernel, &base, &targets, &theta, true) else {
+                break;
+            };
+            if evidence.log_likelihood > best.0 {
+                best = (evidence.log_likelihood, theta.clone());
+            }
+            if evidence
+                .gradient
+                .iter()
+                .all(|gradient| (gradient / n as f64).abs() < TOLERANCE)
+            {
+                break;
+            }
+            for (i, gradient) in evidence.gradient.iter().enumerate() {
+                let gradient = gradient / n as f64;
+                first_moment[i] = beta_1.mul_add(first_moment[i], (1.0 - beta_1) * gradient);
+                second_moment[i] =
+                    beta_2.mul_add(second_moment[i], (1.0 - beta_2) * gradient * gradient);
+                let m = first_moment[i] / (1.0 - beta_1.powi(step));
+                let v = second_moment[i] / (1.0 - beta_2.powi(step));
+                theta[i] = LEARNING_RATE
+                    .mul_add(m / (v.sqrt() + 1e-8), theta[i])
+                    .clamp(-LOG_BOUND, LOG_BOUND);
+            }
+        }
+        let theta = if best.0.is_finite() { best.1 } else { theta };
+        let evidence = evidence(parameters.kernel, &base, &targets, &theta, false)?;
+
+        Ok(Self {
+            kernel: parameters.kernel,
+            hyperparameters: Hyperparameters::from_log(&theta),
+            x_means,
+            x_scales,
+            y_mean,
+            y_scale,
+            x: inputs,
+            weights: evidence.weights,
+            cholesky: evidence.cholesky,
+        })
+    }
+
+    /// Predict the mean and standard deviation of the predictive distribution, including the
+    /// observation noise.
+    fn predict_with_std(&self, x: &DenseMatrix<f32>) -> (Vec<f32>, Vec<f32>) {
+        let (rows, n_features) = x.shape();
+        let n = self.x.len();
+        let Hyperparameters {
+            signal_variance,
+            noise,
+            length_scale,
+        } = self.hyperparameters;
+        (0..rows)
+            .map(|row| {
+                let input: Vec<f64> = (0..n_features)
+                    .map(|column| {
+                        (f64::from(x.get(row, column)) - self.x_means[column])
+                            / self.x_scales[column]
+                    })
+                    .collect();
+                let covariances: Vec<f64> = self
+                    .x
+                    .iter()
+                    .map(|other| {
+                        let base = base_value(self.kernel, &input, other);
+                        signal_variance * correlation(self.kernel, base, length_scale).0
+                    })
+                    .collect();
+                let mean = dot(&covariances, &self.weights);
+
+                // The variance explained by the training data comes from solving L v = k
+                let mut v = vec![0.0; n];
+                for i in 0..n {
+                    let known = dot(&self.cholesky[i * n..i * n + i], &v[..i]);
+                    v[i] = (covariances[i] - known) / self.cholesky[i * n + i];
+                }
+                let prior = signal_variance
+                    * correlation(
+                        self.kernel,
+                        base_value(self.kernel, &input, &input),
+                        length_scale,
+                    )
+                    .0;
+                let variance = (prior + noise - dot(&v, &v)).max(0.0);
+
+                #[allow(clippy::cast_possible_truncation)]
+                (
+                    mean.mul_add(self.y_scale, self.y_mean) as f32,
+                    (variance.sqrt() * self.y_scale) as f32,
+                )
+            })
+            .unzip()
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for GaussianProcess {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        Ok(self.predict_with_std(x).0)
+    }
+}
+
+/// The mean and standard deviation of some values, with a standard deviation of one for constants
+#[allow(clippy::cast_precision_loss)]
+fn mean_and_scale(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    let scale = if variance.sqrt() > 1e-12 {
+        variance.sqrt()
+    } else {
+        1.0
+    };
+    (mean, scale)
+}
+
+/// The quantity the kernel depends on: the squared distance between two rows for the stationary
+/// kernels, or their dot product for the linear kernel
+fn base_value(kernel: GaussianProcessKernel, a: &[f64], b: &[f64]) -> f64 {
+    match kernel {
+        GaussianProcessKernel::Linear => dot(a, b),
+        _ => a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum(),
+    }
+}
+
+/// The kernel without its signal variance, and its derivative with respect to the log length scale
+fn correlation(kernel: GaussianProcessKernel, base: f64, length_scale: f64) -> (f64, f64) {
+    match kernel {
+        GaussianProcessKernel::RBF => {
+            let scaled = base / (length_scale * length_scale);
+            let value = (-0.5 * scaled).exp();
+            (value, value * scaled)
+        }
+        GaussianProcessKernel::Matern { nu } => {
+            let d = (2.0 * f64::from(nu)).sqrt() * base.sqrt() / length_scale;
+            let decay = (-d).exp();
+            if nu < 1.0 {
+                (decay, d * decay)
+            } else if nu < 2.0 {
+                ((1.0 + d) * decay, d * d * decay)
+            } else {
+                (
+                    (1.0 + d + d * d / 3.0) * decay,
+                    d * d * (1.0 + d) / 3.0 * decay,
+                )
+            }
+        }
+        GaussianProcessKernel::Linear => (base, 0.0),
+    }
+}
+
+/// Factorize the covariance matrix for the given log hyperparameters and compute the log marginal
+/// likelihood of the targets, along with its gradient if asked.
+#[allow(clippy::cast_precision_loss)]
+fn evidence(
+    kernel: GaussianProcessKernel,
+    base: &[f64],
+    targets: &[f64],
+    theta: &[f64],
+    with_gradient: bool,
+) -> Result<Evidence, Failed> {
+    let n = targets.len();
+    let Hyperparameters {
+        signal_variance,
+        noise,
+        length_scale,
+    } = Hyperparameters::from_log(theta);
+    let mut covariance = vec![0.0; n * n];
+    let mut length_derivative = vec![0.0; if theta.len() > 2 { n * n } else { 0 }];
+    for i in 0..n {
+        for j in 0..=i {
+            let (value, derivative) = correlation(kernel, base[i * n + j], length_scale);
+            covariance[i * n + j] = signal_variance * value;
+            covariance[j * n + i] = signal_variance * value;
+            if !length_derivative.is_empty() {
+                length_derivative[i * n + j] = signal_variance * derivative;
+                length_derivative[j * n + i] = signal_variance * derivative;
+            }
+        }
+    }
+    let mut cholesky = covariance.clone();
+    for i in 0..n {
+        cholesky[i * n + i] += noise + JITTER;
+    }
+    cholesky_in_place(&mut cholesky, n)?;
+
+    // Solve K w = y by forward and back substitution
+    let mut weights = targets.to_vec();
+    for i in 0..n {
+        let known = dot(&cholesky[i * n..i * n + i], &weights[..i]);
+        weights[i] = (weights[i] - known) / cholesky[i * n + i];
+    }
+    for i in (0..n).rev() {
+        let known: f64 = (i + 1..n).map(|j| cholesky[j * n + i] * weights[j]).sum();
+        weights[i] = (weights[i] - known) / cholesky[i * n + i];
+    }
+    let log_determinant: f64 = (0..n).map(|i| cholesky[i * n + i].ln()).sum::<f64>() * 2.0;
+    let log_likelihood = (n as f64).mul_add(
+        -0.5 * (2.0 * std::f64::consts::PI).ln(),
+        -0.5 * (dot(targets, &weights) + log_determinant),
+    );
+
+    // The gradient is half of the sum of (w w' - K^-1) times the derivative of K
+    let mut gradient = vec![];
+    if with_gradient {
+        let inverse = inverse_from_cholesky(&cholesky, n);
+        let mut signal_gradient = 0.0;
+        let mut noise_gradient = 0.0;
+        let mut length_gradient = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                let outer = weights[i].mul_add(weights[j], -inverse[i * n + j]);
+                signal_gradient += outer * covariance[i * n + j];
+                if !length_derivative.is_empty() {
+                    length_gradient += outer * length_derivative[i * n + j];
+                }
+            }
+            noise_gradient += weights[i].mul_add(weights[i], -inverse[i * n + i]) * noise;
+        }
+        gradient = vec![0.5 * signal_gradient, 0.5 * noise_gradient];
+        if theta.len() > 2 {
+            gradient.push(0.5 * length_gradient);
+        }
+    }
+
+    Ok(Evidence {
+        cholesky,
+        weights,
+        log_likelihood,
+        gradient,
+    })
+}
+
+/// Replace the lower triangle of a symmetric positive definite row-major matrix with its Cholesky
+/// factor, zeroing the upper triangle
+fn cholesky_in_place(matrix: &mut [f64], n: usize) -> Result<(), Failed> {
+    for i in 0..n {
+        for j in 0..=i {
+            let sum = dot(&matrix[i * n..i * n + j], &matrix[j * n..j * n + j]);
+            if i == j {
+                let pivot = matrix[i * n + i] - sum;
+                if pivot <= 0.0 || !pivot.is_finite() {
+                    return Err(Failed::fit(
+                        "The covariance matrix is not positive definite",
+                    ));
+                }
+                matrix[i * n + i] = pivot.sqrt();
+            } else {
+                matrix[i * n + j] = (matrix[i * n + j] - sum) / matrix[j * n + j];
+            }
+        }
+        for j in i + 1..n {
+            matrix[i * n + j] = 0.0;
+        }
+    }
+    Ok(())
+}
+
+/// Invert a matrix from its lower Cholesky factor, as the product of the inverse factors
+fn inverse_from_cholesky(cholesky: &[f64], n: usize) -> Vec<f64> {
+    // Invert the lower triangular factor, one column at a time, storing the result transposed so
+    // that each column is contiguous
+    let mut transposed = vec![0.0; n * n];
+    for column in 0..n {
+        let inverse_column = &mut transposed[column * n..(column + 1) * n];
+        inverse_column[column] = 1.0 / cholesky[column * n + column];
+        for i in column + 1..n {
+            let known = dot(
+                &cholesky[i * n + column..i * n + i],
+                &inverse_column[column..i],
+            );
+            inverse_column[i] = -known / cholesky[i * n + i];
+        }
+    }
+
+    // K^-1 = L^-T L^-1, whose (i, j) entry is the dot product of columns i and j of L^-1
+    let mut inverse = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            let value = dot(
+                &transposed[i * n + i..(i + 1) * n],
+                &transposed[j * n + i..(j + 1) * n],
+            );
+            inverse[i * n + j] = value;
+            inverse[j * n + i] = value;
+        }
+    }
+    inverse
+}
+
+/// The dot product of two vectors
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
@@ -0,0 +1,281 @@
+//! Linear models fit by iteratively reweighted least squares, shared by the robust and
+//! generalized linear regressors
+
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+
+use crate::utils::supports_tweedie_targets;
+
+/// The loss minimized by a linear model
+#[derive(Clone, Copy)]
+pub enum Objective {
+    /// Huber loss, quadratic for residuals within `epsilon` robust standard deviations and linear
+    /// beyond
+    Huber {
+        /// The number of robust standard deviations where the loss turns linear
+        epsilon: f32,
+    },
+    /// Pinball loss, whose minimizer is the given quantile
+    Quantile {
+        /// The quantile to predict
+        quantile: f32,
+    },
+    /// Tweedie deviance with a log link: Poisson for power 1 and Gamma for power 2
+    Tweedie {
+        /// The power of the variance function
+        power: f32,
+    },
+}
+
+/// Everything needed to fit a linear model
+#[derive(Clone)]
+pub struct LinearModelParameters {
+    /// The loss to minimize
+    pub objective: Objective,
+    /// The strength of the L2 penalty on the (standardized) coefficients
+    pub alpha: f32,
+    /// The maximum number of reweighting iterations
+    pub max_iter: usize,
+    /// Stop once no coefficient changes by more than this (relative) amount
+    pub tol: f32,
+}
+
+/// A fitted linear model
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct GeneralizedLinearModel {
+    /// Whether predictions are the exponential of the linear predictor
+    log_link: bool,
+    /// The intercept of the linear predictor
+    intercept: f32,
+    /// The coefficient of each feature in the linear predictor
+    coefficients: Vec<f32>,
+}
+
+impl GeneralizedLinearModel {
+    /// Fit a linear model to the data.
+    #[allow(
+        clippy::ptr_arg,
+        clippy::needless_pass_by_value,
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss
+    )]
+    pub fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        parameters: LinearModelParameters,
+    ) -> Result<Self, Failed> {
+        let (n_rows, n_features) = x.shape();
+        if n_rows == 0 {
+            return Err(Failed::fit("Cannot fit a linear model to no data"));
+        }
+        if let Objective::Tweedie { power } = parameters.objective {
+            if !supports_tweedie_targets(power, y) {
+                return Err(Failed::fit(&format!(
+                    "The targets are out of range for a Tweedie power of {power}"
+                )));
+            }
+        }
+        let n = n_rows as f64;
+        let y: Vec<f64> = y.iter().map(|&value| f64::from(value)).collect();
+
+        // Standardize the features, with a leading column of ones for the intercept
+        let mut means = vec![0.0; n_features];
+        let mut scales = vec![1.0; n_features];
+        for column in 0..n_features {
+            let values: Vec<f64> = (0..n_rows)
+                .map(|row| f64::from(x.get(row, column)))
+                .collect();
+            means[column] = values.iter().sum::<f64>() / n;
+            let variance = values
+                .iter()
+                .map(|value| (value - means[column]).powi(2))
+                .sum::<f64>()
+                / n;
+            if variance.sqrt() > 1e-12 {
+                scales[column] = variance.sqrt();
+            }
+        }
+        let design: Vec<Vec<f64>> = (0..n_rows)
+            .map(|row| {
+                std::iter::once(1.0)
+                    .chain((0..n_features).map(|column| {
+                        (f64::from(x.get(row, column)) - means[column]) / scales[column]
+                    }))
+                    .collect()
+            })
+            .collect();
+        let penalty = n * f64::from(parameters.alpha);
+
+        // Start from least squares, or from the mean for a log link
+        let mut beta = match parameters.objective {
+            Objective::Huber { .. } | Objective::Quantile { .. } => {
+                weighted_ridge(&design, &y, &vec![1.0; n_rows], penalty)?
+            }
+            Objective::Tweedie { .. } => {
+                let mut beta = vec![0.0; n_features + 1];
+                beta[0] = (y.iter().sum::<f64>() / n).max(1e-12).ln();
+                beta
+            }
+        };
+
+        for _ in 0..parameters.max_iter {
+            let eta: Vec<f64> = design.iter().map(|row| dot(row, &beta)).collect();
+            let (weights, response) = working_weights(parameters.objective, &y, &eta);
+            let next = weighted_ridge(&design, &response, &weights, penalty)?;
+            let largest = beta.iter().fold(0.0_f64, |max, value| max.max(value.abs()));
+            let change = next
+                .iter()
+                .zip(&beta)
+                .fold(0.0_f64, |max, (a, b)| max.max((a - b).abs()));
+            beta = next;
+            if change <= f64::from(parameters.tol) * (1.0 + largest) {
+                break;
+            }
+        }
+
+        // Undo the standardization
+        let coefficients: Vec<f64> = (0..n_features)
+            .map(|column| beta[column + 1] / scales[column])
+            .collect();
+        let intercept = beta[0] - dot(&coefficients, &means);
+        Ok(Self {
+            log_link: matches!(parameters.objective, Objective::Tweedie { .. }),
+            intercept: intercept as f32,
+            coefficients: coefficients.iter().map(|&value| value as f32).collect(),
+        })
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for GeneralizedLinearModel {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        let (n, _) = x.shape();
+        Ok((0..n)
+            .map(|row| {
+                let eta = self
+                    .coefficients
+                    .iter()
+                    .enumerate()
+                    .fold(self.intercept, |sum, (column, coefficient)| {
+                        coefficient.mul_add(x.get(row, column), sum)
+                    });
+                if self.log_link {
+                    eta.exp()
+                } else {
+                    eta
+                }
+            })
+            .collect())
+    }
+}
+
+/// The weight and working response of each row for the next least squares fit
+fn working_weights(objective: Objective, y: &[f64], eta: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    match objective {
+        Objective::Huber { epsilon } => {
+            let residuals: Vec<f64> = y.iter().zip(eta).map(|(y, eta)| y - eta).collect();
+            // The median absolute deviation is a robust estimate of the standard deviation
+            let mut deviations: Vec<f64> = residuals.iter().map(|r| r.abs()).collect();
+            deviations.sort_by(f64::total_cmp);
+            let scale = (deviations[deviations.len() / 2] / 0.6745).max(1e-12);
+            let threshold = f64::from(epsilon) * scale;
+            let weights = residuals
+                .iter()
+                .map(|r| {
+                    if r.abs() <= threshold {
+                        1.0
+                    } else {
+                        threshold / r.abs()
+                    }
+                })
+                .collect();
+            (weights, y.to_vec())
+        }
+        Objective::Quantile { quantile } => {
+            let quantile = f64::from(quantile);
+            let weights = y
+                .iter()
+                .zip(eta)
+                .map(|(y, eta)| {
+                    let residual = y - eta;
+                    let side = if residual >= 0.0 {
+                        quantile
+                    } else {
+                        1.0 - quantile
+                    };
+                    side / residual.abs().max(1e-6)
+                })
+                .collect();
+            (weights, y.to_vec())
+        }
+        Objective::Tweedie { power } => {
+            let power = f64::from(power);
+            eta.iter()
+                .zip(y)
+                .map(|(&eta, y)| {
+                    let eta = eta.clamp(-30.0, 30.0);
+                    let mu = eta.exp();
+                    (mu.powf(2.0 - power), eta + (y - mu) / mu)
+                })
+                .unzip()
+        }
+    }
+}
+
+/// Solve the weighted ridge regression normal equations, leaving the intercept unpenalized
+fn weighted_ridge(
+    design: &[Vec<f64>],
+    response: &[f64],
+    weights: &[f64],
+    penalty: f64,
+) -> Result<Vec<f64>, Failed> {
+    let size = design[0].len();
+    let mut system = vec![vec![0.0; size + 1]; size];
+    for ((row, &target), &weight) in design.iter().zip(response).zip(weights) {
+        for (equation, &value) in system.iter_mut().zip(row) {
+            let scaled = weight * value;
+            for (cell, &other) in equation.iter_mut().zip(row) {
+                *cell += scaled * other;
+            }
+            equation[size] += scaled * target;
+        }
+    }
+    for (i, equation) in system.iter_mut().enumerate() {
+        // A tiny ridge keeps collinear features solvable
+        equation[i] += if i == 0 { 1e-10 } else { penalty + 1e-10 };
+    }
+
+    // Gaussian elimination with partial pivoting
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| system[a][column].abs().total_cmp(&system[b][column].abs()))
+            .unwrap_or(column);
+        system.swap(column, pivot);
+        let divisor = system[column][column];
+        if divisor.abs() < 1e-300 || !divisor.is_finite() {
+            return Err(Failed::fit(
+                "The weighted least squares problem is singular",
+            ));
+        }
+        let (solved, remaining) = system.split_at_mut(column + 1);
+        for equation in remaining {
+            let factor = equation[column] / divisor;
+            for (cell, &value) in equation[column..].iter_mut().zip(&solved[column][column..]) {
+                *cell -= factor * value;
+            }
+        }
+    }
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let known = dot(&system[row][row + 1..size], &solution[row + 1..]);
+        solution[row] = (system[row][size] - known) / system[row][row];
+    }
+    Ok(solution)
+}
+
+/// The dot product of two vectors
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
@@ -0,0 +1,441 @@
+//! Gradient-boosted trees, shared by the gradient boosting regressor and classifier
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    tree::decision_tree_regressor::{DecisionTreeRegressor, DecisionTreeRegressorParameters},
+};
+
+use crate::utils::class_indices;
+
+/// The loss function minimized by gradient boosting
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Loss {
+    /// Squared error, for regression
+    SquaredError,
+    /// Absolute error, for regression
+    AbsoluteError,
+    /// Logistic loss for two classes, or softmax cross-entropy for more
+    Deviance,
+}
+
+/// Everything needed to fit a gradient boosting model
+#[derive(Clone)]
+pub struct BoostingParameters {
+    /// The loss function to minimize
+    pub loss: Loss,
+    /// The maximum number of boosting iterations
+    pub n_estimators: usize,
+    /// The shrinkage applied to each tree
+    pub learning_rate: f32,
+    /// The maximum depth of each tree
+    pub max_depth: u16,
+    /// The minimum number of rows in each leaf
+    pub min_samples_leaf: usize,
+    /// The fraction of rows used to fit each tree
+    pub subsample: f32,
+    /// The fraction of rows set aside to decide when to stop early
+    pub validation_fraction: f32,
+    /// Stop once the validation loss has not improved for this many iterations
+    pub early_stopping_rounds: Option<usize>,
+    /// The seed for subsampling and the validation split
+    pub seed: Option<u64>,
+}
+
+/// A fitted gradient boosting model
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct GradientBoosting {
+    /// The loss function the model was fit with
+    loss: Loss,
+    /// The class labels in ascending order, empty for regression
+    classes: Vec<f32>,
+    /// The initial raw prediction for each output
+    init: Vec<f32>,
+    /// The shrinkage applied to each tree
+    learning_rate: f32,
+    /// The trees fit in each iteration, one per output
+    trees: Vec<Vec<DecisionTreeRegressor<f32>>>,
+}
+
+impl GradientBoosting {
+    /// Fit a gradient boosting model to the data.
+    #[allow(clippy::ptr_arg, clippy::needless_pass_by_value)]
+    pub fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        parameters: BoostingParameters,
+    ) -> Result<Self, Failed> {
+        let mut rng = parameters
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+        // One target vector per output: the labels for regression, or class indicators
+        let (classes, targets) = match parameters.loss {
+            Loss::SquaredError | Loss::AbsoluteError => (vec![], vec![y.clone()]),
+            Loss::Deviance => {
+                let members = class_indices(y);
+                let classes: Vec<f32> = members.iter().map(|class| y[class[0]]).collect();
+                let indicators: Vec<Vec<f32>> = members
+                    .iter()
+                    .map(|class| {
+                        let mut indicator = vec![0.0; y.len()];
+                        for &idx in class {
+                            indicator[idx] = 1.0;
+                        }
+                        indicator
+                    })
+                    .collect();
+                match classes.len() {
+                    // A single class needs no trees, since it is always predicted
+                    1 => {
+                        return Ok(Self {
+                            loss: parameters.loss,
+                            classes,
+                            init: vec![],
+                            learning_rate: parameters.learning_rate,
+                            trees: vec![],
+                        })
+                    }
+                    2 => (classes, vec![indicators[1].clone()]),
+                    _ => (classes, indicators),
+                }
+            }
+        };
+
+        // Set aside rows for early stopping
+        let mut rows: Vec<usize> = (0..y.len()).collect();
+        let validation_rows = if parameters.early_stopping_rounds.is_some() {
+            rows.shuffle(&mut rng);
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            let n_validation = ((y.len() as f32) * parameters.validation_fraction) as usize;
+            rows.split_off(y.len() - n_validation)
+        } else {
+            vec![]
+        };
+        let x_train = x.take(&rows, 0);
+        let x_validation = x.take(&validation_rows, 0);
+        let take = |target: &Vec<f32>, idx: &[usize]| -> Vec<f32> {
+            idx.iter().map(|&i| target[i]).collect()
+        };
+        let train_targets: Vec<Vec<f32>> = targets.iter().map(|t| take(t, &rows)).collect();
+        let validation_targets: Vec<Vec<f32>> =
+            targets.iter().map(|t| take(t, &validation_rows)).collect();
+
+        let init = initial_prediction(parameters.loss, &train_targets);
+        let mut train_raw: Vec<Vec<f32>> = init.iter().map(|&v| vec![v; rows.len()]).collect();
+        let mut validation_raw: Vec<Vec<f32>> = init
+            .iter()
+            .map(|&v| vec![v; validation_rows.len()])
+            .collect();
+
+        let tree_parameters = DecisionTreeRegressorParameters::default()
+            .with_max_depth(parameters.max_depth)
+            .with_min_samples_leaf(parameters.min_samples_leaf);
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let n_subsample = ((rows.len() as f32) * parameters.subsample).ceil() as usize;
+        let mut trees = vec![];
+        let mut best_loss = f32::INFINITY;
+        let mut best_iteration = 0;
+        for iteration in 0..parameters.n_estimators {
+            let gradients = negative_gradients(parameters.loss, &train_targets, &train_raw);
+            let mut sample: Vec<usize> = (0..rows.len()).collect();
+            if n_subsample < rows.len() {
+                sample = sample
+                    .choose_multiple(&mut rng, n_subsample)
+                    .copied()
+                    .collect();
+                sample.sort_unstable();
+            }
+            let x_sample = x_train.take(&sample, 0);
+
+            let mut iteration_trees = vec![];
+            for (output, gradient) in gradients.iter().enumerate() {
+                let mut tree = DecisionTreeRegressor::fit(
+                    &x_sample,
+                    &take(gradient, &sample),
+                    tree_parameters.clone(),
+                )?;
+                if matches!(parameters.loss, Loss::AbsoluteError) {
+                    // The signs of the residuals only shape the tree, so each leaf moves to the
+                    // median residual of its rows instead, as least absolute deviation boosting does
+                    let residuals: Vec<f32> = sample
+                        .iter()
+                        .map(|&i| train_targets[output][i] - train_raw[output][i])
+                        .collect();
+                    tree = with_leaf_medians(&tree, &x_sample, &residuals)?;
+                }
+                for (raw, update) in train_raw[output].iter_mut().zip(tree.predict(&x_train)?) {
+                    *raw += parameters.learning_rate * update;
+                }
+                if !validation_rows.is_empty() {
+                    for (raw, update) in validation_raw[output]
+                        .iter_mut()
+                        .zip(tree.predict(&x_validation)?)
+                    {
+                        *raw += parameters.learning_rate * update;
+                    }
+                }
+                iteration_trees.push(tree);
+            }
+            trees.push(iteration_trees);
+
+            // Stop once the validation loss stops improving
+            if let Some(rounds) = parameters.early_stopping_rounds {
+                if validation_rows.is_empty() {
+                    continue;
+                }
+                let validation_loss =
+                    loss_value(parameters.loss, &validation_targets, &validation_raw);
+                if validation_loss < best_loss {
+                    best_loss = validation_loss;
+                    best_iteration = iteration + 1;
+                } else if iteration + 1 - best_iteration >= rounds {
+                    trees.truncate(best_iteration);
+                    break;
+                }
+            }
+        }
+
+        Ok(Self {
+            loss: parameters.loss,
+            classes,
+            init,
+            learning_rate: parameters.learning_rate,
+            trees,
+        })
+    }
+
+    /// The raw score of each class, in ascending order of class label, for each row: the logit of
+    /// each class, with the first class at zero when there are two or only one.
+    pub fn predict_scores(&self, x: &DenseMatrix<f32>) -> Result<Vec<Vec<f32>>, Failed> {
+        if self.classes.len() == 1 {
+            return Ok(vec![vec![0.0]; x.shape().0]);
+        }
+        let raw = self.raw_predictions(x)?;
+        Ok((0..raw[0].len())
+            .map(|row| {
+                if raw.len() == 1 {
+                    vec![0.0, raw[0][row]]
+                } else {
+                    raw.iter().map(|output| output[row]).collect()
+                }
+            })
+            .collect())
+    }
+
+    /// Get the raw (untransformed) predictions for each output.
+    fn raw_predictions(&self, x: &DenseMatrix<f32>) -> Result<Vec<Vec<f32>>, Failed> {
+        let (n, _) = x.shape();
+        let mut raw: Vec<Vec<f32>> = self.init.iter().map(|&v| vec![v; n]).collect();
+        for iteration_trees in &self.trees {
+            for (output, tree) in iteration_trees.iter().enumerate() {
+                for (value, update) in raw[output].iter_mut().zip(tree.predict(x)?) {
+                    *value += self.learning_rate * update;
+                }
+            }
+        }
+        Ok(raw)
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for GradientBoosting {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        if self.classes.len() == 1 {
+            return Ok(vec![self.classes[0]; x.shape().0]);
+        }
+        let raw = self.raw_predictions(x)?;
+        Ok(match self.loss {
+            Loss::SquaredError | Loss::AbsoluteError => raw[0].clone(),
+            Loss::Deviance if raw.len() == 1 => raw[0]
+                .iter()
+                .map(|&value| self.classes[usize::from(value > 0.0)])
+                .collect(),
+            Loss::Deviance => (0..raw[0].len())
+                .map(|i| {
+                    let best = (0..raw.len())
+                        .max_by(|&a, &b| raw[a][i].total_cmp(&raw[b][i]))
+                        .unwrap_or(0);
+                    self.classes[best]
+                })
+                .collect(),
+        })
+    }
+}
+
+/// The constant prediction that minimizes the loss before any trees are fit
+#[allow(clippy::cast_precision_loss)]
+fn initial_prediction(loss: Loss, targets: &[Vec<f32>]) -> Vec<f32> {
+    targets
+        .iter()
+        .map(|target| {
+            let mean = target.iter().sum::<f32>() / target.len().max(1) as f32;
+            match loss {
+                Loss::SquaredError => mean,
+                Loss::AbsoluteError => median(&mut target.clone()),
+                Loss::Deviance if targets.len() == 1 => {
+                    let p = mean.clamp(1e-6, 1.0 - 1e-6);
+                    (p / (1.0 - p)).ln()
+                }
+                Loss::Deviance => mean.max(1e-6).ln(),
+            }
+        })
+        .collect()
+}
+
+/// The median of the values, or zero if there are none
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(f32::total_cmp);
+    values.get(values.len() / 2).copied().unwrap_or(0.0)
+}
+
+/// The layout of a smartcore regression tree, whose nodes are private, for rewriting leaf values
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TreeLayout {
+    /// The nodes, with the root first
+    nodes: Vec<NodeLayout>,
+    /// The parameters the tree was fit with
+    parameters: DecisionTreeRegressorParameters,
+    /// The depth of the tree
+    depth: u16,
+}
+
+/// The layout of a node in a smartcore regression tree
+#[derive(serde::Serialize, serde::Deserialize)]
+struct NodeLayout {
+    /// The position of the node in the tree
+    index: usize,
+    /// The value predicted for rows that stop at the node
+    output: f32,
+    /// The feature the node splits on
+    split_feature: usize,
+    /// The threshold of the split, if any
+    split_value: Option<f32>,
+    /// The gain of the split, if any
+    split_score: Option<f32>,
+    /// The child for rows at or below the threshold
+    true_child: Option<usize>,
+    /// The child for rows above the threshold
+    false_child: Option<usize>,
+}
+
+/// Convert between a tree and its layout, which share the same serialized form
+fn relayout<S: serde::Serialize, D: serde::de::DeserializeOwned>(source: &S) -> Result<D, Failed> {
+    bincode::serialize(source)
+        .and_then(|bytes| bincode::deserialize(&bytes))
+        .map_err(|error| Failed::fit(&format!("Cannot rewrite the leaves of a tree: {error}")))
+}
+
+/// Set the value of each leaf of the tree to the median residual of the rows that fall in it
+fn with_leaf_medians(
+    tree: &DecisionTreeRegressor<f32>,
+    x: &DenseMatrix<f32>,
+    residuals: &[f32],
+) -> Result<DecisionTreeRegressor<f32>, Failed> {
+    let mut layout: TreeLayout = relayout(tree)?;
+    let leaves: Vec<usize> = (0..layout.nodes.len())
+        .filter(|&node| {
+            layout.nodes[node].true_child.is_none() && layout.nodes[node].false_child.is_none()
+        })
+        .collect();
+
+    // Number the leaves so the tree predicts which leaf each row falls in
+    for (number, &leaf) in leaves.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let number = number as f32;
+        layout.nodes[leaf].output = number;
+    }
+    let numbered: DecisionTreeRegressor<f32> = relayout(&layout)?;
+    let mut members = vec![vec![]; leaves.len()];
+    for (number, &residual) in numbered.predict(x)?.iter().zip(residuals) {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        members[*number as usize].push(residual);
+    }
+
+    for (&leaf, mut leaf_residuals) in leaves.iter().zip(members) {
+        layout.nodes[leaf].output = median(&mut leaf_residuals);
+    }
+    relayout(&layout)
+}
+
+/// The negative gradient of the loss for each output and row, which the next trees are fit to
+fn negative_gradients(loss: Loss, targets: &[Vec<f32>], raw: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let probabilities = probabilities(loss, raw);
+    targets
+        .iter()
+        .zip(raw)
+        .zip(&probabilities)
+        .map(|((target, raw), probability)| {
+            target
+                .iter()
+                .zip(raw)
+                .zip(probability)
+                .map(|((&t, &f), &p)| match loss {
+                    Loss::SquaredError => t - f,
+                    Loss::AbsoluteError => (t - f).signum(),
+                    Loss::Deviance => t - p,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The mean loss over all rows
+#[allow(clippy::cast_precision_loss)]
+fn loss_value(loss: Loss, targets: &[Vec<f32>], raw: &[Vec<f32>]) -> f32 {
+    let probabilities = probabilities(loss, raw);
+    let n = targets[0].len().max(1) as f32;
+    let total: f32 = targets
+        .iter()
+        .zip(raw)
+        .zip(&probabilities)
+        .map(|((target, raw), probability)| {
+            target
+                .iter()
+                .zip(raw)
+                .zip(probability)
+                .map(|((&t, &f), &p)| match loss {
+                    Loss::SquaredError => (t - f) * (t - f),
+                    Loss::AbsoluteError => (t - f).abs(),
+                    Loss::Deviance if targets.len() == 1 => {
+                        let p = p.clamp(1e-7, 1.0 - 1e-7);
+                        -t.mul_add(p.ln(), (1.0 - t) * (1.0 - p).ln())
+                    }
+                    Loss::Deviance => -t * p.max(1e-7).ln(),
+                })
+                .sum::<f32>()
+        })
+        .sum();
+    total / n
+}
+
+/// Class probabilities implied by the raw predictions (sigmoid for one output, softmax for more).
+/// For regression losses these are unused and simply copy the raw predictions.
+fn probabilities(loss: Loss, raw: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    match loss {
+        Loss::SquaredError | Loss::AbsoluteError => raw.to_vec(),
+        Loss::Deviance if raw.len() == 1 => {
+            vec![raw[0].iter().map(|&f| 1.0 / (1.0 + (-f).exp())).collect()]
+        }
+        Loss::Deviance => {
+            let mut probabilities = raw.to_vec();
+            for i in 0..raw[0].len() {
+                let max = raw.iter().map(|r| r[i]).fold(f32::NEG_INFINITY, f32::max);
+                let total: f32 = raw.iter().map(|r| (r[i] - max).exp()).sum();
+                for (output, r) in raw.iter().enumerate() {
+                    probabilities[output][i] = (r[i] - max).exp() / total;
+                }
+            }
+            probabilities
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Gradient Boosting Classifier
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::gradient_boosting::{BoostingParameters, GradientBoosting, Loss};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Gradient Boosting Classifier.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#gradient-tree-boosting)
+/// for a more in-depth description of the algorithm.
+pub struct GradientBoostingClassifierWrapper {}
+
+impl GradientBoostingClassifierWrapper {
+    /// Get the boosting parameters, seeded from the settings if a seed has been set.
+    const fn parameters(settings: &Settings) -> BoostingParameters {
+        let parameters = settings
+            .gradient_boosting_classifier_settings
+            .as_ref()
+            .unwrap();
+        BoostingParameters {
+            loss: Loss::Deviance,
+            n_estimators: parameters.n_estimators,
+            learning_rate: parameters.learning_rate,
+            max_depth: parameters.max_depth,
+            min_samples_leaf: parameters.min_samples_leaf,
+            subsample: parameters.subsample,
+            validation_fraction: parameters.validation_fraction,
+            early_stopping_rounds: parameters.early_stopping_rounds,
+            seed: settings.seed,
+        }
+    }
+
+    /// Predict the logit of each class, in ascending order of class label, for calibration
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_scores(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Vec<Vec<f32>> {
+        let model: GradientBoosting = bincode::deserialize(final_model).unwrap();
+        model.predict_scores(x).unwrap()
+    }
+}
+
+impl super::ModelWrapper for GradientBoostingClassifierWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                settings.rebalanced(GradientBoosting::fit),
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::GradientBoostingClassifier,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&GradientBoosting::fit(x, y, Self::parameters(settings))?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GradientBoosting = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
@@ -0,0 +1,66 @@
+//! Gradient Boosting Regressor
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::gradient_boosting::{BoostingParameters, GradientBoosting, Loss};
+use super::{cross_validate, CrossValidation};
+use crate::{settings::GradientBoostingLoss, Algorithm, Settings};
+
+/// The Gradient Boosting Regressor.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#gradient-tree-boosting)
+/// for a more in-depth description of the algorithm.
+pub struct GradientBoostingRegressorWrapper {}
+
+impl GradientBoostingRegressorWrapper {
+    /// Get the boosting parameters, seeded from the settings if a seed has been set.
+    const fn parameters(settings: &Settings) -> BoostingParameters {
+        let parameters = settings
+            .gradient_boosting_regressor_settings
+            .as_ref()
+            .unwrap();
+        BoostingParameters {
+            loss: match parameters.loss {
+                GradientBoostingLoss::SquaredError => Loss::SquaredError,
+                GradientBoostingLoss::AbsoluteError => Loss::AbsoluteError,
+            },
+            n_estimators: parameters.n_estimators,
+            learning_rate: parameters.learning_rate,
+            max_depth: parameters.max_depth,
+            min_samples_leaf: parameters.min_samples_leaf,
+            subsample: parameters.subsample,
+            validation_fraction: parameters.validation_fraction,
+            early_stopping_rounds: parameters.early_stopping_rounds,
+            seed: settings.seed,
+        }
+    }
+}
+
+impl super::ModelWrapper for GradientBoostingRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                GradientBoosting::fit,
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::GradientBoostingRegressor,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&GradientBoosting::fit(x, y, Self::parameters(settings))?).unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GradientBoosting = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
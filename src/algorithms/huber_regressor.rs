@@ -0,0 +1,62 @@
+//! Huber Regressor
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::generalized_linear::{GeneralizedLinearModel, LinearModelParameters, Objective};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Huber Regressor.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/linear_model.html#huber-regression)
+/// for a more in-depth description of the algorithm.
+pub struct HuberRegressorWrapper {}
+
+impl HuberRegressorWrapper {
+    /// Get the linear model parameters.
+    const fn parameters(settings: &Settings) -> LinearModelParameters {
+        let parameters = settings.huber_settings.as_ref().unwrap();
+        LinearModelParameters {
+            objective: Objective::Huber {
+                epsilon: parameters.epsilon,
+            },
+            alpha: parameters.alpha,
+            max_iter: parameters.max_iter,
+            tol: parameters.tol,
+        }
+    }
+}
+
+impl super::ModelWrapper for HuberRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                GeneralizedLinearModel::fit,
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::Huber,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&GeneralizedLinearModel::fit(
+            x,
+            y,
+            Self::parameters(settings),
+        )?)
+        .unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: GeneralizedLinearModel = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
@@ -1,12 +1,12 @@
 //! KNN Classifier
 
 use smartcore::{
+    error::Failed,
     linalg::naive::dense_matrix::DenseMatrix,
     math::distance::{
         euclidian::Euclidian, hamming::Hamming, mahalanobis::Mahalanobis, manhattan::Manhattan,
         minkowski::Minkowski, Distances,
     },
-    model_selection::{cross_validate, CrossValidationResult},
     neighbors::knn_classifier::{
         KNNClassifier, KNNClassifierParameters as SmartcoreKNNClassifierParameters,
     },
@@ -14,6 +14,8 @@ use smartcore::{
 
 use crate::{Algorithm, Distance, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The KNN Classifier.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/neighbors.html#classification)
@@ -25,10 +27,10 @@ impl super::ModelWrapper for KNNClassifierWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
         let cv = match settings.knn_classifier_settings.as_ref().unwrap().distance {
             Distance::Euclidean => cross_validate(
-                KNNClassifier::fit,
+                settings.rebalanced(KNNClassifier::fit),
                 x,
                 y,
                 SmartcoreKNNClassifierParameters::default()
@@ -50,12 +52,11 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::euclidian()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Manhattan => cross_validate(
-                KNNClassifier::fit,
+                settings.rebalanced(KNNClassifier::fit),
                 x,
                 y,
                 SmartcoreKNNClassifierParameters::default()
@@ -77,12 +78,11 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::manhattan()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Minkowski(p) => cross_validate(
-                KNNClassifier::fit,
+                settings.rebalanced(KNNClassifier::fit),
                 x,
                 y,
                 SmartcoreKNNClassifierParameters::default()
@@ -104,12 +104,11 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::minkowski(p)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Mahalanobis => cross_validate(
-                KNNClassifier::fit,
+                settings.rebalanced(KNNClassifier::fit),
                 x,
                 y,
                 SmartcoreKNNClassifierParameters::default()
@@ -131,12 +130,11 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::mahalanobis(x)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Hamming => cross_validate(
-                KNNClassifier::fit,
+                settings.rebalanced(KNNClassifier::fit),
                 x,
                 y,
                 SmartcoreKNNClassifierParameters::default()
@@ -158,16 +156,16 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::hamming()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
         };
 
-        (cv, Algorithm::KNNClassifier)
+        Ok((cv, Algorithm::KNNClassifier))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
         match settings.knn_classifier_settings.as_ref().unwrap().distance {
             Distance::Euclidean => {
                 let params = SmartcoreKNNClassifierParameters::default()
@@ -189,7 +187,7 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::euclidian());
-                bincode::serialize(&KNNClassifier::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNClassifier::fit(x, y, params)?).unwrap())
             }
             Distance::Manhattan => {
                 let params = SmartcoreKNNClassifierParameters::default()
@@ -211,7 +209,7 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::manhattan());
-                bincode::serialize(&KNNClassifier::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNClassifier::fit(x, y, params)?).unwrap())
             }
             Distance::Minkowski(p) => {
                 let params = SmartcoreKNNClassifierParameters::default()
@@ -233,7 +231,7 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::minkowski(p));
-                bincode::serialize(&KNNClassifier::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNClassifier::fit(x, y, params)?).unwrap())
             }
             Distance::Mahalanobis => {
                 let params = SmartcoreKNNClassifierParameters::default()
@@ -255,7 +253,7 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::mahalanobis(x));
-                bincode::serialize(&KNNClassifier::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNClassifier::fit(x, y, params)?).unwrap())
             }
             Distance::Hamming => {
                 let params = SmartcoreKNNClassifierParameters::default()
@@ -277,7 +275,7 @@ impl super::ModelWrapper for KNNClassifierWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::hamming());
-                bincode::serialize(&KNNClassifier::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNClassifier::fit(x, y, params)?).unwrap())
             }
         }
     }
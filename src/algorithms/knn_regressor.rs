@@ -1,13 +1,12 @@
 //! KNN Regressor
 
 use smartcore::{
+    error::Failed,
     linalg::naive::dense_matrix::DenseMatrix,
     math::distance::{
         euclidian::Euclidian, hamming::Hamming, mahalanobis::Mahalanobis, manhattan::Manhattan,
         minkowski::Minkowski, Distances,
     },
-    model_selection::cross_validate,
-    model_selection::CrossValidationResult,
     neighbors::knn_regressor::{
         KNNRegressor, KNNRegressorParameters as SmartcoreKNNRegressorParameters,
     },
@@ -15,6 +14,8 @@ use smartcore::{
 
 use crate::{Algorithm, Distance, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The KNN Regressor.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/neighbors.html#regression)
@@ -26,7 +27,7 @@ impl super::ModelWrapper for KNNRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
         let cv = match settings.knn_regressor_settings.as_ref().unwrap().distance {
             Distance::Euclidean => cross_validate(
                 KNNRegressor::fit,
@@ -51,10 +52,9 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::euclidian()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Manhattan => cross_validate(
                 KNNRegressor::fit,
                 x,
@@ -78,10 +78,9 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::manhattan()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Minkowski(p) => cross_validate(
                 KNNRegressor::fit,
                 x,
@@ -105,10 +104,9 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::minkowski(p)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Mahalanobis => cross_validate(
                 KNNRegressor::fit,
                 x,
@@ -132,10 +130,9 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::mahalanobis(x)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Distance::Hamming => cross_validate(
                 KNNRegressor::fit,
                 x,
@@ -159,16 +156,15 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::hamming()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
         };
 
-        (cv, Algorithm::KNNRegressor)
+        Ok((cv, Algorithm::KNNRegressor))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
         match settings.knn_regressor_settings.as_ref().unwrap().distance {
             Distance::Euclidean => {
                 let params = SmartcoreKNNRegressorParameters::default()
@@ -191,7 +187,7 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                     )
                     .with_distance(Distances::euclidian());
 
-                bincode::serialize(&KNNRegressor::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNRegressor::fit(x, y, params)?).unwrap())
             }
             Distance::Manhattan => {
                 let params = SmartcoreKNNRegressorParameters::default()
@@ -214,7 +210,7 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                     )
                     .with_distance(Distances::manhattan());
 
-                bincode::serialize(&KNNRegressor::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNRegressor::fit(x, y, params)?).unwrap())
             }
             Distance::Minkowski(p) => {
                 let params = SmartcoreKNNRegressorParameters::default()
@@ -238,7 +234,7 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                     )
                     .with_distance(Distances::minkowski(p));
 
-                bincode::serialize(&KNNRegressor::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNRegressor::fit(x, y, params)?).unwrap())
             }
             Distance::Mahalanobis => {
                 let params = SmartcoreKNNRegressorParameters::default()
@@ -261,7 +257,7 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                             .clone(),
                     )
                     .with_distance(Distances::mahalanobis(x));
-                bincode::serialize(&KNNRegressor::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNRegressor::fit(x, y, params)?).unwrap())
             }
             Distance::Hamming => {
                 let params = SmartcoreKNNRegressorParameters::default()
@@ -285,7 +281,7 @@ impl super::ModelWrapper for KNNRegressorWrapper {
                     )
                     .with_distance(Distances::hamming());
 
-                bincode::serialize(&KNNRegressor::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&KNNRegressor::fit(x, y, params)?).unwrap())
             }
         }
     }
@@ -1,12 +1,11 @@
 //! LASSO regression algorithm.
 
-use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, linear::lasso::Lasso,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
-};
+use smartcore::{error::Failed, linalg::naive::dense_matrix::DenseMatrix, linear::lasso::Lasso};
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The LASSO regression algorithm.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/linear_model.html#lasso)
@@ -18,8 +17,8 @@ impl super::ModelWrapper for LassoRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
                 Lasso::fit,
                 x,
@@ -29,28 +28,24 @@ impl super::ModelWrapper for LassoRegressorWrapper {
                     .as_ref()
                     .expect("No settings provided for the LASSO regression algorithm.")
                     .clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .expect("Error during cross-validation."),
+            )?,
             Algorithm::Lasso,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &Lasso::fit(
-                x,
-                y,
-                settings
-                    .lasso_settings
-                    .as_ref()
-                    .expect("No settings provided for the LASSO regression algorithm.")
-                    .clone(),
-            )
-            .expect("Error during training."),
-        )
-        .expect("Cannot serialize trained model.")
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&Lasso::fit(
+            x,
+            y,
+            settings
+                .lasso_settings
+                .as_ref()
+                .expect("No settings provided for the LASSO regression algorithm.")
+                .clone(),
+        )?)
+        .expect("Cannot serialize trained model."))
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
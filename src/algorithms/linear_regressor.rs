@@ -1,13 +1,14 @@
 //! Linear regression algorithm.
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix,
     linear::linear_regression::LinearRegression,
-    model_selection::{cross_validate, CrossValidationResult},
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Linear regression algorithm.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/linear_model.html#ordinary-least-squares)
@@ -19,8 +20,8 @@ impl super::ModelWrapper for LinearRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
                 LinearRegression::fit,
                 x,
@@ -30,28 +31,24 @@ impl super::ModelWrapper for LinearRegressorWrapper {
                     .as_ref()
                     .expect("No settings provided for the linear regression algorithm.")
                     .clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .expect("Error during cross-validation."),
+            )?,
             Algorithm::Linear,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &LinearRegression::fit(
-                x,
-                y,
-                settings
-                    .linear_settings
-                    .as_ref()
-                    .expect("No settings provided for the linear regression algorithm.")
-                    .clone(),
-            )
-            .expect("Error during training."),
-        )
-        .expect("Cannot serialize trained model.")
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&LinearRegression::fit(
+            x,
+            y,
+            settings
+                .linear_settings
+                .as_ref()
+                .expect("No settings provided for the linear regression algorithm.")
+                .clone(),
+        )?)
+        .expect("Cannot serialize trained model."))
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
@@ -1,9 +1,12 @@
 //! Logistic Regression
 
 use crate::{Algorithm, Settings};
+
+use super::{cross_validate, CrossValidation};
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, linear::logistic_regression::LogisticRegression,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    linear::logistic_regression::LogisticRegression,
 };
 
 /// The Logistic Regression algorithm.
@@ -12,32 +15,60 @@ use smartcore::{
 /// for a more in-depth description of the algorithm.
 pub struct LogisticRegressionWrapper {}
 
+impl LogisticRegressionWrapper {
+    /// Predict the logit of each class, in ascending order of class label, for calibration. With
+    /// two classes the first class is at zero.
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_scores(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Vec<Vec<f32>> {
+        let model: LogisticRegression<f32, DenseMatrix<f32>> =
+            bincode::deserialize(final_model).unwrap();
+        let logits = x.matmul(&model.coefficients().transpose());
+        let (n, outputs) = logits.shape();
+        (0..n)
+            .map(|row| {
+                let logits = (0..outputs)
+                    .map(|output| logits.get(row, output) + model.intercept().get(output, 0));
+                if outputs == 1 {
+                    std::iter::once(0.0).chain(logits).collect()
+                } else {
+                    logits.collect()
+                }
+            })
+            .collect()
+    }
+}
+
 impl super::ModelWrapper for LogisticRegressionWrapper {
     fn cv(
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
-                LogisticRegression::fit,
+                settings.rebalanced(LogisticRegression::fit),
                 x,
                 y,
                 settings.logistic_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::LogisticRegression,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &LogisticRegression::fit(x, y, settings.logistic_settings.as_ref().unwrap().clone())
-                .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&LogisticRegression::fit(
+            x,
+            y,
+            settings.logistic_settings.as_ref().unwrap().clone(),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
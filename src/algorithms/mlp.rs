@@ -0,0 +1,514 @@
+//! Multilayer perceptrons, shared by the MLP regressor and classifier
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use smartcore::{
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+};
+
+use crate::settings::{Activation, Optimizer};
+use crate::utils::class_indices;
+
+/// Without early stopping, training stops once the training loss has not improved for this many
+/// epochs
+const NO_IMPROVEMENT_EPOCHS: usize = 10;
+
+/// The smallest decrease in loss that counts as an improvement
+const TOLERANCE: f32 = 1e-4;
+
+/// Everything needed to fit a multilayer perceptron
+#[derive(Clone)]
+pub struct NetworkParameters {
+    /// Whether to fit a classifier (softmax, cross-entropy) or a regressor (identity, squared error)
+    pub classification: bool,
+    /// The number of neurons in each hidden layer
+    pub hidden_layer_sizes: Vec<usize>,
+    /// The activation function of the hidden layers
+    pub activation: Activation,
+    /// The optimizer used to update the weights
+    pub optimizer: Optimizer,
+    /// The step size of the optimizer
+    pub learning_rate: f32,
+    /// The strength of the L2 penalty on the weights
+    pub alpha: f32,
+    /// The number of rows in each minibatch
+    pub batch_size: usize,
+    /// The maximum number of passes over the training data
+    pub max_epochs: usize,
+    /// The fraction of rows set aside to decide when to stop early
+    pub validation_fraction: f32,
+    /// Stop once the validation loss has not improved for this many epochs
+    pub early_stopping_rounds: Option<usize>,
+    /// The seed for the initial weights, the minibatches and the validation split
+    pub seed: Option<u64>,
+}
+
+/// A fully connected layer
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct Layer {
+    /// The number of inputs to the layer
+    n_inputs: usize,
+    /// The weights, as one row of `n_inputs` values for each output
+    weights: Vec<f32>,
+    /// The bias of each output
+    biases: Vec<f32>,
+}
+
+/// The first and second moments of the gradients of one layer, as kept by the optimizer
+#[derive(Clone)]
+struct Moments {
+    /// Momentum (SGD) or the first moment estimate (Adam) of the weights and the biases
+    first: (Vec<f32>, Vec<f32>),
+    /// The second moment estimate (Adam) of the weights and the biases
+    second: (Vec<f32>, Vec<f32>),
+}
+
+/// A fitted multilayer perceptron
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MultilayerPerceptron {
+    /// The activation function of the hidden layers
+    activation: Activation,
+    /// The class labels in ascending order, empty for regression
+    classes: Vec<f32>,
+    /// The mean of each feature, used to standardize the inputs
+    feature_means: Vec<f32>,
+    /// The standard deviation of each feature, used to standardize the inputs
+    feature_scales: Vec<f32>,
+    /// The mean of the target, used to standardize regression targets
+    target_mean: f32,
+    /// The standard deviation of the target, used to standardize regression targets
+    target_scale: f32,
+    /// The hidden layers followed by the output layer
+    layers: Vec<Layer>,
+}
+
+impl MultilayerPerceptron {
+    /// Fit a multilayer perceptron to the data.
+    #[allow(
+        clippy::ptr_arg,
+        clippy::needless_pass_by_value,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        parameters: NetworkParameters,
+    ) -> Result<Self, Failed> {
+        let (n_rows, n_features) = x.shape();
+        if n_rows == 0 {
+            return Err(Failed::fit("Cannot fit a multilayer perceptron to no data"));
+        }
+        let mut rng = parameters
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+        // Standardize the features, and the targets for regression
+        let (feature_means, feature_scales): (Vec<f32>, Vec<f32>) = (0..n_features)
+            .map(|column| {
+                let values: Vec<f32> = (0..n_rows).map(|row| x.get(row, column)).collect();
+                mean_and_scale(&values)
+            })
+            .unzip();
+        let (classes, targets, target_mean, target_scale) = if parameters.classification {
+            let members = class_indices(y);
+            let mut targets = vec![0.0; n_rows];
+            for (class, rows) in members.iter().enumerate() {
+                for &row in rows {
+                    targets[row] = class as f32;
+                }
+            }
+            let classes: Vec<f32> = members.iter().map(|rows| y[rows[0]]).collect();
+            (classes, targets, 0.0, 1.0)
+        } else {
+            let (mean, scale) = mean_and_scale(y);
+            let targets = y.iter().map(|value| (value - mean) / scale).collect();
+            (vec![], targets, mean, scale)
+        };
+
+        // Glorot-initialized layers, ending with one output per class or a single value
+        let n_outputs = if parameters.classification {
+            classes.len()
+        } else {
+            1
+        };
+        let sizes: Vec<usize> = std::iter::once(n_features)
+            .chain(parameters.hidden_layer_sizes.iter().copied())
+            .chain(std::iter::once(n_outputs))
+            .collect();
+        let layers = sizes
+            .windows(2)
+            .map(|pair| {
+                let limit = (6.0 / (pair[0] + pair[1]) as f32).sqrt();
+                Layer {
+                    n_inputs: pair[0],
+                    weights: (0..pair[0] * pair[1])
+                        .map(|_| rng.gen_range(-limit..limit))
+                        .collect(),
+                    biases: (0..pair[1]).map(|_| rng.gen_range(-limit..limit)).collect(),
+                }
+            })
+            .collect();
+
+        let mut network = Self {
+            activation: parameters.activation,
+            classes,
+            feature_means,
+            feature_scales,
+            target_mean,
+            target_scale,
+            layers,
+        };
+        let inputs: Vec<Vec<f32>> = (0..n_rows)
+            .map(|row| network.standardize(&x.get_row_as_vec(row)))
+            .collect();
+
+        // Set aside rows for early stopping
+        let mut rows: Vec<usize> = (0..n_rows).collect();
+        let validation_rows = if parameters.early_stopping_rounds.is_some() {
+            rows.shuffle(&mut rng);
+            let n_validation = ((n_rows as f32) * parameters.validation_fraction) as usize;
+            rows.split_off(n_rows - n_validation.min(n_rows - 1))
+        } else {
+            vec![]
+        };
+        network.train(
+            &inputs,
+            &targets,
+            &rows,
+            &validation_rows,
+            &parameters,
+            &mut rng,
+        );
+        Ok(network)
+    }
+
+    /// Run minibatch gradient descent until the loss stops improving or the epochs run out
+    #[allow(clippy::cast_precision_loss)]
+    fn train(
+        &mut self,
+        inputs: &[Vec<f32>],
+        targets: &[f32],
+        rows: &[usize],
+        validation_rows: &[usize],
+        parameters: &NetworkParameters,
+        rng: &mut StdRng,
+    ) {
+        let mut rows = rows.to_vec();
+        let zeros = |layer: &Layer| {
+            (
+                vec![0.0; layer.weights.len()],
+                vec![0.0; layer.biases.len()],
+            )
+        };
+        let mut moments: Vec<Moments> = self
+            .layers
+            .iter()
+            .map(|layer| Moments {
+                first: zeros(layer),
+                second: zeros(layer),
+            })
+            .collect();
+        let patience = parameters
+            .early_stopping_rounds
+            .unwrap_or(NO_IMPROVEMENT_EPOCHS);
+        let mut best_loss = f32::INFINITY;
+        let mut best_layers = self.layers.clone();
+        let mut epochs_without_improvement = 0;
+        let mut step = 0;
+
+        for _ in 0..parameters.max_epochs {
+            rows.shuffle(rng);
+            let mut training_loss = 0.0;
+            for batch in rows.chunks(parameters.batch_size.max(1)) {
+                step += 1;
+                let mut gradients: Vec<(Vec<f32>, Vec<f32>)> =
+                    self.layers.iter().map(zeros).collect();
+                for &row in batch {
+                    training_loss += self.backpropagate(&inputs[row], targets[row], &mut gradients);
+                }
+                let n = batch.len() as f32;
+                for ((layer, (weights, biases)), moments) in
+                    self.layers.iter_mut().zip(&mut gradients).zip(&mut moments)
+                {
+                    for (gradient, weight) in weights.iter_mut().zip(&layer.weights) {
+                        *gradient = parameters.alpha.mul_add(*weight, *gradient / n);
+                    }
+                    for gradient in biases.iter_mut() {
+                        *gradient /= n;
+                    }
+                    update(
+                        parameters,
+                        step,
+                        &mut layer.weights,
+                        weights,
+                        (&mut moments.first.0, &mut moments.second.0),
+                    );
+                    update(
+                        parameters,
+                        step,
+                        &mut layer.biases,
+                        biases,
+                        (&mut moments.first.1, &mut moments.second.1),
+                    );
+                }
+            }
+
+            // Judge progress on the validation rows if there are any, otherwise on the training loss
+            let loss = if validation_rows.is_empty() {
+                training_loss / rows.len() as f32
+            } else {
+                validation_rows
+                    .iter()
+                    .map(|&row| self.loss(&self.forward(&inputs[row]), targets[row]))
+                    .sum::<f32>()
+                    / validation_rows.len() as f32
+            };
+            if !loss.is_finite() {
+                break;
+            }
+            if loss < best_loss - TOLERANCE {
+                best_loss = loss;
+                best_layers.clone_from(&self.layers);
+                epochs_without_improvement = 0;
+            } else {
+                epochs_without_improvement += 1;
+                if epochs_without_improvement >= patience {
+                    break;
+                }
+            }
+        }
+
+        // Keep the weights with the best validation loss
+        if !validation_rows.is_empty() && best_loss.is_finite() {
+            self.layers = best_layers;
+        }
+    }
+
+    /// Add the gradients of the loss for a single row, returning its loss
+    fn backpropagate(
+        &self,
+        input: &[f32],
+        target: f32,
+        gradients: &mut [(Vec<f32>, Vec<f32>)],
+    ) -> f32 {
+        let activations = self.forward_all(input);
+        let output = &activations[activations.len() - 1];
+        let loss = self.loss(output, target);
+
+        // Softmax with cross-entropy and identity with squared error share the same output delta
+        let mut delta = output.clone();
+        if self.classes.is_empty() {
+            delta[0] -= target;
+        } else {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let class = target as usize;
+            delta[class] -= 1.0;
+        }
+
+        for (index, layer) in self.layers.iter().enumerate().rev() {
+            let input = &activations[index];
+            let (weights, biases) = &mut gradients[index];
+            for (output, &error) in delta.iter().enumerate() {
+                let row = output * layer.n_inputs;
+                for (gradient, &value) in weights[row..row + layer.n_inputs].iter_mut().zip(input) {
+                    *gradient += error * value;
+                }
+                biases[output] += error;
+            }
+            if index > 0 {
+                let mut previous = vec![0.0; layer.n_inputs];
+                for (output, &error) in delta.iter().enumerate() {
+                    let row = output * layer.n_inputs;
+                    for (sum, &weight) in previous
+                        .iter_mut()
+                        .zip(&layer.weights[row..row + layer.n_inputs])
+                    {
+                        *sum += weight * error;
+                    }
+                }
+                for (value, &activation) in previous.iter_mut().zip(input) {
+                    *value *= derivative(self.activation, activation);
+                }
+                delta = previous;
+            }
+        }
+        loss
+    }
+
+    /// The loss for a single row: half the squared error, or the cross-entropy
+    fn loss(&self, output: &[f32], target: f32) -> f32 {
+        if self.classes.is_empty() {
+            0.5 * (output[0] - target).powi(2)
+        } else {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let class = target as usize;
+            -output[class].max(1e-7).ln()
+        }
+    }
+
+    /// The output of the network for a single (standardized) row
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.forward_all(input).pop().unwrap_or_default()
+    }
+
+    /// The input followed by the output of every layer for a single (standardized) row
+    fn forward_all(&self, input: &[f32]) -> Vec<Vec<f32>> {
+        let mut activations = vec![input.to_vec()];
+        for (index, layer) in self.layers.iter().enumerate() {
+            let input = &activations[index];
+            let mut output: Vec<f32> = layer
+                .biases
+                .iter()
+                .enumerate()
+                .map(|(output, bias)| {
+                    let row = output * layer.n_inputs;
+                    bias + layer.weights[row..row + layer.n_inputs]
+                        .iter()
+                        .zip(input)
+                        .map(|(weight, value)| weight * value)
+                        .sum::<f32>()
+                })
+                .collect();
+            if index + 1 < self.layers.len() {
+                for value in &mut output {
+                    *value = activate(self.activation, *value);
+                }
+            } else if !self.classes.is_empty() {
+                softmax(&mut output);
+            }
+            activations.push(output);
+        }
+        activations
+    }
+
+    /// Standardize a row of features
+    fn standardize(&self, row: &[f32]) -> Vec<f32> {
+        row.iter()
+            .zip(&self.feature_means)
+            .zip(&self.feature_scales)
+            .map(|((value, mean), scale)| (value - mean) / scale)
+            .collect()
+    }
+}
+
+impl MultilayerPerceptron {
+    /// The log of the predicted probability of each class, in ascending order of class label, for
+    /// each row
+    pub fn predict_scores(&self, x: &DenseMatrix<f32>) -> Vec<Vec<f32>> {
+        let (n, _) = x.shape();
+        (0..n)
+            .map(|row| {
+                self.forward(&self.standardize(&x.get_row_as_vec(row)))
+                    .into_iter()
+                    .map(|probability| probability.max(1e-7).ln())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Predictor<DenseMatrix<f32>, Vec<f32>> for MultilayerPerceptron {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        let (n, _) = x.shape();
+        Ok((0..n)
+            .map(|row| {
+                let output = self.forward(&self.standardize(&x.get_row_as_vec(row)));
+                if self.classes.is_empty() {
+                    output[0].mul_add(self.target_scale, self.target_mean)
+                } else {
+                    let best = (0..output.len())
+                        .max_by(|&a, &b| output[a].total_cmp(&output[b]))
+                        .unwrap_or(0);
+                    self.classes[best]
+                }
+            })
+            .collect())
+    }
+}
+
+/// Take one optimizer step for a set of parameters given their gradients and moments
+fn update(
+    parameters: &NetworkParameters,
+    step: i32,
+    values: &mut [f32],
+    gradients: &[f32],
+    (first, second): (&mut [f32], &mut [f32]),
+) {
+    let learning_rate = parameters.learning_rate;
+    match parameters.optimizer {
+        Optimizer::SGD { momentum } => {
+            for ((value, &gradient), velocity) in values.iter_mut().zip(gradients).zip(first) {
+                *velocity = momentum.mul_add(*velocity, -learning_rate * gradient);
+                *value += *velocity;
+            }
+        }
+        Optimizer::Adam { beta_1, beta_2 } => {
+            let first_correction = 1.0 - beta_1.powi(step);
+            let second_correction = 1.0 - beta_2.powi(step);
+            for (((value, &gradient), m), v) in
+                values.iter_mut().zip(gradients).zip(first).zip(second)
+            {
+                *m = beta_1.mul_add(*m, (1.0 - beta_1) * gradient);
+                *v = beta_2.mul_add(*v, (1.0 - beta_2) * gradient * gradient);
+                *value -= learning_rate * (*m / first_correction)
+                    / ((*v / second_correction).sqrt() + 1e-8);
+            }
+        }
+    }
+}
+
+/// Apply an activation function
+fn activate(activation: Activation, value: f32) -> f32 {
+    match activation {
+        Activation::Identity => value,
+        Activation::Logistic => 1.0 / (1.0 + (-value).exp()),
+        Activation::Tanh => value.tanh(),
+        Activation::ReLU => value.max(0.0),
+    }
+}
+
+/// The derivative of an activation function, in terms of its output
+fn derivative(activation: Activation, output: f32) -> f32 {
+    match activation {
+        Activation::Identity => 1.0,
+        Activation::Logistic => output * (1.0 - output),
+        Activation::Tanh => output.mul_add(-output, 1.0),
+        Activation::ReLU => {
+            if output > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// Turn raw outputs into probabilities
+fn softmax(values: &mut [f32]) {
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mut total = 0.0;
+    for value in values.iter_mut() {
+        *value = (*value - max).exp();
+        total += *value;
+    }
+    for value in values.iter_mut() {
+        *value /= total;
+    }
+}
+
+/// The mean and standard deviation of some values, with a constant scaled by one
+#[allow(clippy::cast_precision_loss)]
+fn mean_and_scale(values: &[f32]) -> (f32, f32) {
+    let n = values.len().max(1) as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f32>()
+        / n;
+    let scale = variance.sqrt();
+    (mean, if scale > f32::EPSILON { scale } else { 1.0 })
+}
@@ -0,0 +1,80 @@
+//! Multilayer Perceptron Classifier
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::mlp::{MultilayerPerceptron, NetworkParameters};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Multilayer Perceptron Classifier.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/neural_networks_supervised.html#classification)
+/// for a more in-depth description of the algorithm.
+pub struct MLPClassifierWrapper {}
+
+impl MLPClassifierWrapper {
+    /// Get the network parameters, seeded from the settings if a seed has been set.
+    fn parameters(settings: &Settings) -> NetworkParameters {
+        let parameters = settings.mlp_classifier_settings.as_ref().unwrap();
+        NetworkParameters {
+            classification: true,
+            hidden_layer_sizes: parameters.hidden_layer_sizes.clone(),
+            activation: parameters.activation,
+            optimizer: parameters.optimizer,
+            learning_rate: parameters.learning_rate,
+            alpha: parameters.alpha,
+            batch_size: parameters.batch_size,
+            max_epochs: parameters.max_epochs,
+            validation_fraction: parameters.validation_fraction,
+            early_stopping_rounds: parameters.early_stopping_rounds,
+            seed: settings.seed,
+        }
+    }
+
+    /// Predict the log probability of each class, in ascending order of class label, for
+    /// calibration
+    #[allow(clippy::ptr_arg)]
+    pub fn predict_scores(
+        x: &DenseMatrix<f32>,
+        final_model: &Vec<u8>,
+        _settings: &Settings,
+    ) -> Vec<Vec<f32>> {
+        let model: MultilayerPerceptron = bincode::deserialize(final_model).unwrap();
+        model.predict_scores(x)
+    }
+}
+
+impl super::ModelWrapper for MLPClassifierWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                settings.rebalanced(MultilayerPerceptron::fit),
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::MLPClassifier,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&MultilayerPerceptron::fit(
+            x,
+            y,
+            Self::parameters(settings),
+        )?)
+        .unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: MultilayerPerceptron = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
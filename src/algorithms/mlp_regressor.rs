@@ -0,0 +1,67 @@
+//! Multilayer Perceptron Regressor
+
+use smartcore::{api::Predictor, error::Failed, linalg::naive::dense_matrix::DenseMatrix};
+
+use super::mlp::{MultilayerPerceptron, NetworkParameters};
+use super::{cross_validate, CrossValidation};
+use crate::{Algorithm, Settings};
+
+/// The Multilayer Perceptron Regressor.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/neural_networks_supervised.html#regression)
+/// for a more in-depth description of the algorithm.
+pub struct MLPRegressorWrapper {}
+
+impl MLPRegressorWrapper {
+    /// Get the network parameters, seeded from the settings if a seed has been set.
+    fn parameters(settings: &Settings) -> NetworkParameters {
+        let parameters = settings.mlp_regressor_settings.as_ref().unwrap();
+        NetworkParameters {
+            classification: false,
+            hidden_layer_sizes: parameters.hidden_layer_sizes.clone(),
+            activation: parameters.activation,
+            optimizer: parameters.optimizer,
+            learning_rate: parameters.learning_rate,
+            alpha: parameters.alpha,
+            batch_size: parameters.batch_size,
+            max_epochs: parameters.max_epochs,
+            validation_fraction: parameters.validation_fraction,
+            early_stopping_rounds: parameters.early_stopping_rounds,
+            seed: settings.seed,
+        }
+    }
+}
+
+impl super::ModelWrapper for MLPRegressorWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                MultilayerPerceptron::fit,
+                x,
+                y,
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::MLPRegressor,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&MultilayerPerceptron::fit(
+            x,
+            y,
+            Self::parameters(settings),
+        )?)
+        .unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: MultilayerPerceptron = bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
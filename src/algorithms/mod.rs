@@ -12,7 +12,12 @@
 //!   - Decision Tree Classifier
 //!   - Gaussian Naive Bayes Classifier
 //!   - Categorical Naive Bayes Classifier
+//!   - Bernoulli Naive Bayes Classifier
+//!   - Multinomial Naive Bayes Classifier
 //!   - Support Vector Classifier
+//!   - Gradient Boosting Classifier
+//!   - Extra Trees Classifier
+//!   - Multilayer Perceptron Classifier
 //!
 //! * Regression algorithms:
 //!   - Linear Regression
@@ -23,6 +28,32 @@
 //!   - Random Forest Regressor
 //!   - Decision Tree Regressor
 //!   - Support Vector Regressor
+//!   - Gradient Boosting Regressor
+//!   - Extra Trees Regressor
+//!   - Multilayer Perceptron Regressor
+//!   - Huber Regressor
+//!   - Quantile Regressor
+//!   - Poisson, Gamma and Tweedie Regressors
+//!   - Gaussian Process Regressor
+//!
+//! * Meta-estimators:
+//!   - Bagging, around any of the above
+//!   - Multi-output regression, jointly or around any of the above
+//!   - Multi-label classification, around any of the classifiers above
+//!
+//! * Clustering algorithms:
+//!   - K-Means
+//!   - DBSCAN
+//!   - Agglomerative Hierarchical Clustering
+//!
+//! * Anomaly detection algorithms:
+//!   - Isolation Forest
+//!   - Local Outlier Factor
+//!   - Robust Covariance
+//!
+//! * Baselines, which ignore the features:
+//!   - Dummy Classifier
+//!   - Dummy Regressor
 
 mod linear_regressor;
 pub use linear_regressor::LinearRegressorWrapper;
@@ -63,22 +94,152 @@ pub use gaussian_naive_bayes_classifier::GaussianNaiveBayesClassifierWrapper;
 mod categorical_naive_bayes_classifier;
 pub use categorical_naive_bayes_classifier::CategoricalNaiveBayesClassifierWrapper;
 
+mod bernoulli_naive_bayes_classifier;
+pub use bernoulli_naive_bayes_classifier::BernoulliNaiveBayesClassifierWrapper;
+
+mod multinomial_naive_bayes_classifier;
+pub use multinomial_naive_bayes_classifier::MultinomialNaiveBayesClassifierWrapper;
+
 mod support_vector_classifier;
 pub use support_vector_classifier::SupportVectorClassifierWrapper;
 
 mod support_vector_regressor;
 pub use support_vector_regressor::SupportVectorRegressorWrapper;
 
+mod gradient_boosting;
+
+mod gradient_boosting_regressor;
+pub use gradient_boosting_regressor::GradientBoostingRegressorWrapper;
+
+mod gradient_boosting_classifier;
+pub use gradient_boosting_classifier::GradientBoostingClassifierWrapper;
+
+mod extra_trees;
+
+mod extra_trees_regressor;
+pub use extra_trees_regressor::ExtraTreesRegressorWrapper;
+
+mod extra_trees_classifier;
+pub use extra_trees_classifier::ExtraTreesClassifierWrapper;
+
+mod bagging;
+pub use bagging::BaggingWrapper;
+
+mod dummy_regressor;
+pub use dummy_regressor::DummyRegressorWrapper;
+
+mod dummy_classifier;
+pub use dummy_classifier::DummyClassifierWrapper;
+
+mod mlp;
+
+mod mlp_regressor;
+pub use mlp_regressor::MLPRegressorWrapper;
+
+mod mlp_classifier;
+pub use mlp_classifier::MLPClassifierWrapper;
+
+mod generalized_linear;
+
+mod huber_regressor;
+pub use huber_regressor::HuberRegressorWrapper;
+
+mod quantile_regressor;
+pub use quantile_regressor::QuantileRegressorWrapper;
+
+mod poisson_regressor;
+pub use poisson_regressor::PoissonRegressorWrapper;
+
+mod gamma_regressor;
+pub use gamma_regressor::GammaRegressorWrapper;
+
+mod tweedie_regressor;
+pub use tweedie_regressor::TweedieRegressorWrapper;
+
+mod gaussian_process_regressor;
+pub use gaussian_process_regressor::GaussianProcessRegressorWrapper;
+
+mod multi_output;
+pub use multi_output::MultiOutputRegressor;
+
+mod multi_label;
+pub use multi_label::MultiLabelClassifier;
+
+mod clustering;
+pub use clustering::{Clusterer, Dendrogram};
+
+mod anomaly;
+pub use anomaly::AnomalyDetector;
+
 use crate::{Algorithm, Settings};
+use smartcore::api::Predictor;
+use smartcore::error::Failed;
 use smartcore::linalg::naive::dense_matrix::DenseMatrix;
-use smartcore::model_selection::CrossValidationResult;
+use smartcore::linalg::BaseMatrix;
+use smartcore::model_selection::{BaseKFold, CrossValidationResult};
 
 use crate::settings::FinalModel;
 use std::time::{Duration, Instant};
 
+/// The outcome of cross-validating a model
+pub struct CrossValidation {
+    /// The training and testing scores of each fold
+    pub scores: CrossValidationResult<f32>,
+    /// The prediction for each row from the model that held it out, or NaN for rows that are
+    /// never in a testing fold
+    pub predictions: Vec<f32>,
+}
+
+/// Cross-validate an estimator like `smartcore::model_selection::cross_validate`, but also keep
+/// the out-of-fold predictions so that ensembles can be built without refitting every model.
+#[allow(clippy::ptr_arg, clippy::needless_pass_by_value)]
+pub fn cross_validate<H, E, K, F>(
+    fit_estimator: F,
+    x: &DenseMatrix<f32>,
+    y: &Vec<f32>,
+    parameters: H,
+    cv: K,
+    score: fn(&Vec<f32>, &Vec<f32>) -> f32,
+) -> Result<CrossValidation, Failed>
+where
+    H: Clone,
+    E: Predictor<DenseMatrix<f32>, Vec<f32>>,
+    K: BaseKFold,
+    F: Fn(&DenseMatrix<f32>, &Vec<f32>, H) -> Result<E, Failed>,
+{
+    let k = cv.n_splits();
+    let mut test_score = Vec::with_capacity(k);
+    let mut train_score = Vec::with_capacity(k);
+    let mut predictions = vec![f32::NAN; y.len()];
+
+    for (train_idx, test_idx) in cv.split(x) {
+        let train_x = x.take(&train_idx, 0);
+        let train_y: Vec<f32> = train_idx.iter().map(|&idx| y[idx]).collect();
+        let test_x = x.take(&test_idx, 0);
+        let test_y: Vec<f32> = test_idx.iter().map(|&idx| y[idx]).collect();
+
+        let estimator = fit_estimator(&train_x, &train_y, parameters.clone())?;
+
+        train_score.push(score(&train_y, &estimator.predict(&train_x)?));
+        let test_predictions = estimator.predict(&test_x)?;
+        test_score.push(score(&test_y, &test_predictions));
+        for (&idx, prediction) in test_idx.iter().zip(test_predictions) {
+            predictions[idx] = prediction;
+        }
+    }
+
+    Ok(CrossValidation {
+        scores: CrossValidationResult {
+            test_score,
+            train_score,
+        },
+        predictions,
+    })
+}
+
 /// Trait for wrapping models
 pub trait ModelWrapper {
-    /// Perform cross-validation and return the results
+    /// Perform cross-validation and train the final model
     ///
     /// # Arguments
     ///
@@ -88,40 +249,52 @@ pub trait ModelWrapper {
     ///
     /// # Returns
     ///
-    /// * `CrossValidationResult<f32>` - The cross-validation results
+    /// * `CrossValidation` - The cross-validation scores and out-of-fold predictions
     /// * `Algorithm` - The algorithm used
     /// * `Duration` - The time taken to perform the cross-validation
     /// * `Vec<u8>` - The final model
+    ///
+    /// # Errors
+    ///
+    /// If the model cannot be fit to one of the folds or to the whole data
     fn cv_model(
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm, Duration, Vec<u8>) {
+    ) -> Result<(CrossValidation, Algorithm, Duration, Vec<u8>), Failed> {
         let start = Instant::now();
-        let results = Self::cv(x, y, settings);
+        let results = Self::cv(x, y, settings)?;
         let end = Instant::now();
-        (
+        Ok((
             results.0,
             results.1,
             end.duration_since(start),
             match settings.final_model_approach {
                 FinalModel::None => vec![],
-                _ => Self::train(x, y, settings),
+                _ => Self::train(x, y, settings)?,
             },
-        )
+        ))
     }
 
     /// Perform cross-validation
+    ///
+    /// # Errors
+    ///
+    /// If the model cannot be fit to one of the folds
     #[allow(clippy::ptr_arg)]
     fn cv(
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm);
+    ) -> Result<(CrossValidation, Algorithm), Failed>;
 
     /// Train a model
+    ///
+    /// # Errors
+    ///
+    /// If the model cannot be fit to the data
     #[allow(clippy::ptr_arg)]
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8>;
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed>;
 
     /// Perform a prediction
     #[allow(clippy::ptr_arg)]
@@ -0,0 +1,112 @@
+//! Multi-label classification: binary relevance and classifier chains around any classifier
+
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use super::multi_output::chained_features;
+use crate::settings::{Algorithm, MultiLabel};
+use crate::Settings;
+
+/// Classifiers fitted to several binary labels
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum MultiLabelClassifier {
+    /// Independent classifiers of each label
+    BinaryRelevance {
+        /// The algorithm of every classifier
+        algorithm: Algorithm,
+        /// The classifier of each label
+        models: Vec<LabelModel>,
+    },
+    /// A classifier chain, in which the classifier of each label also sees the labels before it
+    Chain {
+        /// The algorithm of every classifier
+        algorithm: Algorithm,
+        /// The classifier of each label
+        models: Vec<LabelModel>,
+    },
+}
+
+/// The classifier of a single label
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum LabelModel {
+    /// A label that took a single value in the training data, which is always predicted
+    Constant {
+        /// The value of the label
+        value: f32,
+    },
+    /// A serialized classifier
+    Fitted {
+        /// The serialized model
+        model: Vec<u8>,
+    },
+}
+
+impl MultiLabelClassifier {
+    /// Fit the algorithm to every label with the multi-label strategy in the settings
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to fit
+    /// * `x` - The input data
+    /// * `y` - The label-indicator matrix, one column of zeros and ones per label
+    /// * `settings` - The settings for the model
+    pub fn fit(
+        algorithm: Algorithm,
+        x: &DenseMatrix<f32>,
+        y: &DenseMatrix<f32>,
+        settings: &Settings,
+    ) -> Self {
+        let x = &settings.without_group_column(x.clone());
+        let chain = matches!(settings.multi_label, MultiLabel::Chain);
+        let models = (0..y.shape().1)
+            .map(|label| {
+                let labels = y.get_col_as_vec(label);
+                if labels
+                    .iter()
+                    .all(|&value| (value - labels[0]).abs() < f32::EPSILON)
+                {
+                    return LabelModel::Constant { value: labels[0] };
+                }
+                let features = if chain {
+                    chained_features(x, y, label)
+                } else {
+                    x.clone()
+                };
+                LabelModel::Fitted {
+                    model: algorithm.get_trainer()(&features, &labels, settings)
+                        .expect("Error during training."),
+                }
+            })
+            .collect();
+        if chain {
+            Self::Chain { algorithm, models }
+        } else {
+            Self::BinaryRelevance { algorithm, models }
+        }
+    }
+
+    /// Predict every label, returning a label-indicator matrix with one column per label
+    pub fn predict(&self, x: &DenseMatrix<f32>, settings: &Settings) -> DenseMatrix<f32> {
+        let x = &settings.without_group_column(x.clone());
+        let (Self::BinaryRelevance { algorithm, models } | Self::Chain { algorithm, models }) =
+            self;
+        let predictor = algorithm.get_predictor();
+        let mut predictions = DenseMatrix::zeros(x.shape().0, models.len());
+        for (label, model) in models.iter().enumerate() {
+            let column = match model {
+                LabelModel::Constant { value } => vec![*value; x.shape().0],
+                LabelModel::Fitted { model } => {
+                    let features = if matches!(self, Self::Chain { .. }) {
+                        chained_features(x, &predictions, label)
+                    } else {
+                        x.clone()
+                    };
+                    predictor(&features, model, settings)
+                }
+            };
+            for (row, value) in column.into_iter().enumerate() {
+                predictions.set(row, label, value);
+            }
+        }
+        predictions
+    }
+}
@@ -0,0 +1,396 @@
+//! Multi-output regression: joint fits of linear regression, decision trees and KNN to all of the
+//! targets, and per-target or chained fits of any other regressor
+
+use smartcore::{
+    linalg::{
+        naive::dense_matrix::DenseMatrix, qr::QRDecomposableMatrix, svd::SVDDecomposableMatrix,
+        BaseMatrix,
+    },
+    linear::linear_regression::LinearRegressionSolverName,
+    math::distance::{
+        euclidian::Euclidian, hamming::Hamming, mahalanobis::Mahalanobis, manhattan::Manhattan,
+        minkowski::Minkowski, Distance as _,
+    },
+    neighbors::KNNWeightFunction,
+};
+
+use crate::settings::{Algorithm, MultiOutput};
+use crate::{Distance, Settings};
+
+/// A regressor fitted to several targets at once
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum MultiOutputRegressor {
+    /// Least squares coefficients for every target, with the intercepts in the last row
+    Linear {
+        /// The coefficients, one column per target
+        coefficients: DenseMatrix<f32>,
+    },
+    /// A regression tree whose leaves predict every target, split to reduce the squared error
+    /// summed over the targets
+    Tree {
+        /// The nodes of the tree, with the root first
+        nodes: Vec<TreeNode>,
+    },
+    /// K-nearest neighbors, averaging the targets of the same neighbors
+    NearestNeighbors {
+        /// The training data
+        x: DenseMatrix<f32>,
+        /// The training targets
+        y: DenseMatrix<f32>,
+    },
+    /// Independent models of each target
+    PerTarget {
+        /// The algorithm of every model
+        algorithm: Algorithm,
+        /// The serialized model of each target
+        models: Vec<Vec<u8>>,
+    },
+    /// A regressor chain, in which the model of each target also sees the targets before it
+    Chain {
+        /// The algorithm of every model
+        algorithm: Algorithm,
+        /// The serialized model of each target
+        models: Vec<Vec<u8>>,
+    },
+}
+
+/// A node of a multi-output regression tree
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum TreeNode {
+    /// A leaf, predicting the mean of each target over its training rows
+    Leaf {
+        /// The prediction for each target
+        value: Vec<f32>,
+    },
+    /// A split sending rows with the feature at most the threshold left, and the rest right
+    Split {
+        /// The column of the feature
+        feature: usize,
+        /// The threshold on the feature
+        threshold: f32,
+        /// The index of the left child
+        left: usize,
+        /// The index of the right child
+        right: usize,
+    },
+}
+
+impl MultiOutputRegressor {
+    /// Whether the algorithm is fit jointly to all of the targets
+    pub const fn is_native(algorithm: &Algorithm) -> bool {
+        matches!(
+            algorithm,
+            Algorithm::Linear | Algorithm::DecisionTreeRegressor | Algorithm::KNNRegressor
+        )
+    }
+
+    /// Fit the algorithm to the targets, jointly if it supports several targets and with the
+    /// multi-output strategy in the settings otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to fit
+    /// * `x` - The input data
+    /// * `y` - The targets, one column per target
+    /// * `settings` - The settings for the model
+    pub fn fit(
+        algorithm: Algorithm,
+        x: &DenseMatrix<f32>,
+        y: &DenseMatrix<f32>,
+        settings: &Settings,
+    ) -> Self {
+        let x = &settings.without_group_column(x.clone());
+        match algorithm {
+            Algorithm::Linear => Self::fit_linear(x, y, settings),
+            Algorithm::DecisionTreeRegressor => Self::fit_tree(x, y, settings),
+            Algorithm::KNNRegressor => Self::NearestNeighbors {
+                x: x.clone(),
+                y: y.clone(),
+            },
+            _ => {
+                let chain = matches!(settings.multi_output, MultiOutput::Chain);
+                let models = (0..y.shape().1)
+                    .map(|target| {
+                        let features = if chain {
+                            chained_features(x, y, target)
+                        } else {
+                            x.clone()
+                        };
+                        algorithm.get_trainer()(&features, &y.get_col_as_vec(target), settings)
+                            .expect("Error during training.")
+                    })
+                    .collect();
+                if chain {
+                    Self::Chain { algorithm, models }
+                } else {
+                    Self::PerTarget { algorithm, models }
+                }
+            }
+        }
+    }
+
+    /// Predict every target, one column per target
+    pub fn predict(&self, x: &DenseMatrix<f32>, settings: &Settings) -> DenseMatrix<f32> {
+        let x = &settings.without_group_column(x.clone());
+        match self {
+            Self::Linear { coefficients } => x
+                .h_stack(&DenseMatrix::ones(x.shape().0, 1))
+                .matmul(coefficients),
+            Self::Tree { nodes } => {
+                let rows: Vec<Vec<f32>> = (0..x.shape().0)
+                    .map(|row| predict_tree(nodes, &x.get_row_as_vec(row)))
+                    .collect();
+                DenseMatrix::from_2d_vec(&rows)
+            }
+            Self::NearestNeighbors {
+                x: x_train,
+                y: y_train,
+            } => predict_knn(x_train, y_train, x, settings),
+            Self::PerTarget { algorithm, models } => {
+                let predictor = algorithm.get_predictor();
+                let columns: Vec<Vec<f32>> = models
+                    .iter()
+                    .map(|model| predictor(x, model, settings))
+                    .collect();
+                DenseMatrix::from_2d_vec(&columns).transpose()
+            }
+            Self::Chain { algorithm, models } => {
+                let predictor = algorithm.get_predictor();
+                let mut predictions = DenseMatrix::zeros(x.shape().0, models.len());
+                for (target, model) in models.iter().enumerate() {
+                    let features = chained_features(x, &predictions, target);
+                    for (row, value) in predictor(&features, model, settings)
+                        .into_iter()
+                        .enumerate()
+                    {
+                        predictions.set(row, target, value);
+                    }
+                }
+                predictions
+            }
+        }
+    }
+
+    /// Solve the least squares problem for every target at once, with the solver in the linear
+    /// regression settings
+    fn fit_linear(x: &DenseMatrix<f32>, y: &DenseMatrix<f32>, settings: &Settings) -> Self {
+        let (n, p) = x.shape();
+        let design = x.h_stack(&DenseMatrix::ones(n, 1));
+        let solution = match settings.linear_settings.as_ref().unwrap().solver {
+            LinearRegressionSolverName::QR => design.qr_solve_mut(y.clone()),
+            LinearRegressionSolverName::SVD => design.svd_solve_mut(y.clone()),
+        }
+        .unwrap();
+        Self::Linear {
+            coefficients: solution.slice(0..p + 1, 0..y.shape().1),
+        }
+    }
+
+    /// Grow a regression tree with the limits in the decision tree regression settings
+    fn fit_tree(x: &DenseMatrix<f32>, y: &DenseMatrix<f32>, settings: &Settings) -> Self {
+        let parameters = settings.decision_tree_regressor_settings.as_ref().unwrap();
+        let mut nodes = vec![];
+        grow_tree(
+            x,
+            y,
+            &(0..x.shape().0).collect::<Vec<_>>(),
+            0,
+            &TreeLimits {
+                max_depth: parameters.max_depth.map(usize::from),
+                min_samples_leaf: parameters.min_samples_leaf.max(1),
+                min_samples_split: parameters.min_samples_split.max(2),
+            },
+            &mut nodes,
+        );
+        Self::Tree { nodes }
+    }
+}
+
+/// The features seen by the model of a target in a regressor or classifier chain: the input data
+/// followed by the targets before it
+pub fn chained_features(
+    x: &DenseMatrix<f32>,
+    y: &DenseMatrix<f32>,
+    target: usize,
+) -> DenseMatrix<f32> {
+    if target == 0 {
+        x.clone()
+    } else {
+        x.h_stack(&y.slice(0..y.shape().0, 0..target))
+    }
+}
+
+/// The limits on the growth of a regression tree
+struct TreeLimits {
+    /// The maximum depth of the tree
+    max_depth: Option<usize>,
+    /// The minimum number of rows in a leaf
+    min_samples_leaf: usize,
+    /// The minimum number of rows in a node for it to be split
+    min_samples_split: usize,
+}
+
+/// Grow the subtree for the given rows, returning the index of its root
+#[allow(clippy::cast_precision_loss)]
+fn grow_tree(
+    x: &DenseMatrix<f32>,
+    y: &DenseMatrix<f32>,
+    rows: &[usize],
+    depth: usize,
+    limits: &TreeLimits,
+    nodes: &mut Vec<TreeNode>,
+) -> usize {
+    let targets = y.shape().1;
+    let totals: Vec<f32> = (0..targets)
+        .map(|target| rows.iter().map(|&row| y.get(row, target)).sum())
+        .collect();
+    let index = nodes.len();
+    nodes.push(TreeNode::Leaf {
+        value: totals
+            .iter()
+            .map(|total| total / rows.len() as f32)
+            .collect(),
+    });
+    if rows.len() < limits.min_samples_split || limits.max_depth.is_some_and(|max| depth >= max) {
+        return index;
+    }
+
+    // Minimizing the squared error of both sides maximizes the sum of their squared totals over
+    // their sizes, summed over the targets
+    let score = |left: &[f32], left_count: usize| -> f32 {
+        let right_count = rows.len() - left_count;
+        left.iter()
+            .zip(&totals)
+            .map(|(left, total)| {
+                left.powi(2) / left_count as f32 + (total - left).powi(2) / right_count as f32
+            })
+            .sum()
+    };
+    let unsplit: f32 = totals
+        .iter()
+        .map(|total| total.powi(2) / rows.len() as f32)
+        .sum();
+    let mut best = (unsplit, None);
+    for feature in 0..x.shape().1 {
+        let mut sorted = rows.to_vec();
+        sorted.sort_by(|&a, &b| x.get(a, feature).total_cmp(&x.get(b, feature)));
+        let mut left = vec![0.0; targets];
+        for (count, pair) in sorted.windows(2).enumerate() {
+            for (target, total) in left.iter_mut().enumerate() {
+                *total += y.get(pair[0], target);
+            }
+            let left_count = count + 1;
+            let (low, high) = (x.get(pair[0], feature), x.get(pair[1], feature));
+            if low >= high
+                || left_count < limits.min_samples_leaf
+                || rows.len() - left_count < limits.min_samples_leaf
+            {
+                continue;
+            }
+            let candidate = score(&left, left_count);
+            if candidate > best.0 * (1.0 + f32::EPSILON) {
+                best = (candidate, Some((feature, f32::midpoint(low, high))));
+            }
+        }
+    }
+
+    if let (_, Some((feature, threshold))) = best {
+        let (left_rows, right_rows): (Vec<usize>, Vec<usize>) = rows
+            .iter()
+            .partition(|&&row| x.get(row, feature) <= threshold);
+        let left = grow_tree(x, y, &left_rows, depth + 1, limits, nodes);
+        let right = grow_tree(x, y, &right_rows, depth + 1, limits, nodes);
+        nodes[index] = TreeNode::Split {
+            feature,
+            threshold,
+            left,
+            right,
+        };
+    }
+    index
+}
+
+/// Follow a row down the tree to its leaf
+fn predict_tree(nodes: &[TreeNode], row: &[f32]) -> Vec<f32> {
+    let mut index = 0;
+    loop {
+        match &nodes[index] {
+            TreeNode::Leaf { value } => return value.clone(),
+            TreeNode::Split {
+                feature,
+                threshold,
+                left,
+                right,
+            } => {
+                index = if row[*feature] <= *threshold {
+                    *left
+                } else {
+                    *right
+                }
+            }
+        }
+    }
+}
+
+/// Average the targets of the nearest training rows, with the number of neighbors, weighting and
+/// distance in the KNN regression settings
+#[allow(clippy::cast_precision_loss)]
+fn predict_knn(
+    x_train: &DenseMatrix<f32>,
+    y_train: &DenseMatrix<f32>,
+    x: &DenseMatrix<f32>,
+    settings: &Settings,
+) -> DenseMatrix<f32> {
+    let parameters = settings.knn_regressor_settings.as_ref().unwrap();
+    let mahalanobis = matches!(parameters.distance, Distance::Mahalanobis)
+        .then(|| Mahalanobis::<f32, DenseMatrix<f32>>::new(x_train));
+    let distance = |a: &Vec<f32>, b: &Vec<f32>| -> f32 {
+        match parameters.distance {
+            Distance::Euclidean => Euclidian {}.distance(a, b),
+            Distance::Manhattan => Manhattan {}.distance(a, b),
+            Distance::Minkowski(p) => Minkowski { p }.distance(a, b),
+            Distance::Mahalanobis => mahalanobis.as_ref().unwrap().distance(a, b),
+            Distance::Hamming => Hamming {}.distance(a, b),
+        }
+    };
+
+    let train_rows: Vec<Vec<f32>> = (0..x_train.shape().0)
+        .map(|row| x_train.get_row_as_vec(row))
+        .collect();
+    let targets = y_train.shape().1;
+    let rows: Vec<Vec<f32>> = (0..x.shape().0)
+        .map(|row| {
+            let query = x.get_row_as_vec(row);
+            let mut neighbors: Vec<(f32, usize)> = train_rows
+                .iter()
+                .enumerate()
+                .map(|(index, train_row)| (distance(&query, train_row), index))
+                .collect();
+            neighbors.sort_by(|a, b| a.0.total_cmp(&b.0));
+            neighbors.truncate(parameters.k.max(1));
+
+            // Exact matches take all of the weight when weighting by inverse distance
+            let exact = neighbors.iter().any(|&(d, _)| d <= 0.0);
+            let weights: Vec<f32> = neighbors
+                .iter()
+                .map(|&(d, _)| match parameters.weight {
+                    KNNWeightFunction::Uniform => 1.0,
+                    KNNWeightFunction::Distance if exact => f32::from(u8::from(d <= 0.0)),
+                    KNNWeightFunction::Distance => 1.0 / d,
+                })
+                .collect();
+            let total: f32 = weights.iter().sum();
+            (0..targets)
+                .map(|target| {
+                    neighbors
+                        .iter()
+                        .zip(&weights)
+                        .map(|(&(_, index), weight)| weight * y_train.get(index, target))
+                        .sum::<f32>()
+                        / total
+                })
+                .collect()
+        })
+        .collect();
+    DenseMatrix::from_2d_vec(&rows)
+}
@@ -0,0 +1,52 @@
+//! Multinomial Naive Bayes Classifier.
+
+use smartcore::{
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix,
+    naive_bayes::multinomial::MultinomialNB,
+};
+
+use crate::{Algorithm, Settings};
+
+use super::{cross_validate, CrossValidation};
+
+/// The Multinomial Naive Bayes Classifier.
+///
+/// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/naive_bayes.html#multinomial-naive-bayes)
+/// for a more in-depth description of the algorithm.
+pub struct MultinomialNaiveBayesClassifierWrapper {}
+
+impl super::ModelWrapper for MultinomialNaiveBayesClassifierWrapper {
+    fn cv(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        settings: &Settings,
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
+            cross_validate(
+                settings.rebalanced(MultinomialNB::fit),
+                x,
+                y,
+                settings.multinomial_nb_settings.as_ref().unwrap().clone(),
+                settings.get_kfolds(x, y),
+                settings.get_metric(),
+            )?,
+            Algorithm::MultinomialNaiveBayes,
+        ))
+    }
+
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&MultinomialNB::fit(
+            x,
+            y,
+            settings.multinomial_nb_settings.as_ref().unwrap().clone(),
+        )?)
+        .unwrap())
+    }
+
+    fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
+        let model: MultinomialNB<f32, DenseMatrix<f32>> =
+            bincode::deserialize(final_model).unwrap();
+        model.predict(x).unwrap()
+    }
+}
@@ -1,57 +1,65 @@
 //! Random Forest Classifier
 
 use smartcore::{
-    ensemble::random_forest_classifier::RandomForestClassifier,
+    ensemble::random_forest_classifier::{
+        RandomForestClassifier, RandomForestClassifierParameters,
+    },
+    error::Failed,
     linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Random Forest Classifier.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#random-forests)
 /// for a more in-depth description of the algorithm.
 pub struct RandomForestClassifierWrapper {}
 
+impl RandomForestClassifierWrapper {
+    /// Get the random forest parameters, seeded from the settings if a seed has been set.
+    fn parameters(settings: &Settings) -> RandomForestClassifierParameters {
+        let parameters = settings
+            .random_forest_classifier_settings
+            .as_ref()
+            .unwrap()
+            .clone();
+        match settings.seed {
+            Some(seed) => parameters.with_seed(seed),
+            None => parameters,
+        }
+    }
+}
+
 impl super::ModelWrapper for RandomForestClassifierWrapper {
     fn cv(
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
-                RandomForestClassifier::fit,
+                settings.rebalanced(RandomForestClassifier::fit),
                 x,
                 y,
-                settings
-                    .random_forest_classifier_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-                settings.get_kfolds(),
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::RandomForestClassifier,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &RandomForestClassifier::fit(
-                x,
-                y,
-                settings
-                    .random_forest_classifier_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        Ok(bincode::serialize(&RandomForestClassifier::fit(
+            x,
+            y,
+            Self::parameters(settings),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
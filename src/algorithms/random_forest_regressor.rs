@@ -1,57 +1,62 @@
 //! Random Forest Regressor
 
 use smartcore::{
-    ensemble::random_forest_regressor::RandomForestRegressor,
+    ensemble::random_forest_regressor::{RandomForestRegressor, RandomForestRegressorParameters},
+    error::Failed,
     linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::{cross_validate, CrossValidationResult},
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Random Forest Regressor.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/ensemble.html#random-forests)
 /// for a more in-depth description of the algorithm.
 pub struct RandomForestRegressorWrapper {}
 
+impl RandomForestRegressorWrapper {
+    /// Get the random forest parameters, seeded from the settings if a seed has been set.
+    fn parameters(settings: &Settings) -> RandomForestRegressorParameters {
+        let parameters = settings
+            .random_forest_regressor_settings
+            .as_ref()
+            .unwrap()
+            .clone();
+        match settings.seed {
+            Some(seed) => parameters.with_seed(seed),
+            None => parameters,
+        }
+    }
+}
+
 impl super::ModelWrapper for RandomForestRegressorWrapper {
     fn cv(
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
                 RandomForestRegressor::fit,
                 x,
                 y,
-                settings
-                    .random_forest_regressor_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-                settings.get_kfolds(),
+                Self::parameters(settings),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::RandomForestRegressor,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &RandomForestRegressor::fit(
-                x,
-                y,
-                settings
-                    .random_forest_regressor_settings
-                    .as_ref()
-                    .unwrap()
-                    .clone(),
-            )
-            .unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&RandomForestRegressor::fit(
+            x,
+            y,
+            Self::parameters(settings),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
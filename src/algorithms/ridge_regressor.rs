@@ -1,12 +1,14 @@
 //! Ridge regression algorithm.
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix, linear::ridge_regression::RidgeRegression,
-    model_selection::cross_validate, model_selection::CrossValidationResult,
+    error::Failed, linalg::naive::dense_matrix::DenseMatrix,
+    linear::ridge_regression::RidgeRegression,
 };
 
 use crate::{Algorithm, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Ridge regression algorithm.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/linear_model.html#ridge-regression)
@@ -18,26 +20,27 @@ impl super::ModelWrapper for RidgeRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
-        (
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        Ok((
             cross_validate(
                 RidgeRegression::fit,
                 x,
                 y,
                 settings.ridge_settings.as_ref().unwrap().clone(),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Algorithm::Ridge,
-        )
+        ))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
-        bincode::serialize(
-            &RidgeRegression::fit(x, y, settings.ridge_settings.as_ref().unwrap().clone()).unwrap(),
-        )
-        .unwrap()
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        Ok(bincode::serialize(&RidgeRegression::fit(
+            x,
+            y,
+            settings.ridge_settings.as_ref().unwrap().clone(),
+        )?)
+        .unwrap())
     }
 
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, _settings: &Settings) -> Vec<f32> {
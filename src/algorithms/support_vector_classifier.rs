@@ -1,15 +1,20 @@
 //! Support Vector Classifier
 
 use smartcore::{
-    linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::cross_validate,
-    model_selection::CrossValidationResult,
+    api::Predictor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
     svm::{
         svc::{SVCParameters as SmartcoreSVCParameters, SVC},
-        Kernels, LinearKernel, PolynomialKernel, RBFKernel, SigmoidKernel,
+        Kernel as SmartcoreKernel, Kernels, LinearKernel, PolynomialKernel, RBFKernel,
+        SigmoidKernel,
     },
 };
 
+use crate::settings::MulticlassStrategy;
+
+use super::{cross_validate, CrossValidation};
+use crate::utils::class_indices;
 use crate::{Algorithm, Kernel, Settings};
 
 /// The Support Vector Classifier.
@@ -23,10 +28,12 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
+        let strategy = settings.svc_settings.as_ref().unwrap().multiclass;
         let cv = match settings.svc_settings.as_ref().unwrap().kernel {
             Kernel::Linear => cross_validate(
-                SVC::fit,
+                settings
+                    .rebalanced(|x, y, parameters| MulticlassSVC::fit(x, y, parameters, strategy)),
                 x,
                 y,
                 SmartcoreSVCParameters::default()
@@ -34,12 +41,12 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_c(settings.svc_settings.as_ref().unwrap().c)
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::linear()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Kernel::Polynomial(degree, gamma, coef) => cross_validate(
-                SVC::fit,
+                settings
+                    .rebalanced(|x, y, parameters| MulticlassSVC::fit(x, y, parameters, strategy)),
                 x,
                 y,
                 SmartcoreSVCParameters::default()
@@ -47,12 +54,12 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_c(settings.svc_settings.as_ref().unwrap().c)
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::polynomial(degree, gamma, coef)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Kernel::RBF(gamma) => cross_validate(
-                SVC::fit,
+                settings
+                    .rebalanced(|x, y, parameters| MulticlassSVC::fit(x, y, parameters, strategy)),
                 x,
                 y,
                 SmartcoreSVCParameters::default()
@@ -60,12 +67,12 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_c(settings.svc_settings.as_ref().unwrap().c)
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::rbf(gamma)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Kernel::Sigmoid(gamma, coef) => cross_validate(
-                SVC::fit,
+                settings
+                    .rebalanced(|x, y, parameters| MulticlassSVC::fit(x, y, parameters, strategy)),
                 x,
                 y,
                 SmartcoreSVCParameters::default()
@@ -73,15 +80,16 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_c(settings.svc_settings.as_ref().unwrap().c)
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::sigmoid(gamma, coef)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
         };
-        (cv, Algorithm::SVC)
+        Ok((cv, Algorithm::SVC))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
+        let (x, y) = &settings.rebalance(x, y);
+        let strategy = settings.svc_settings.as_ref().unwrap().multiclass;
         match settings.svc_settings.as_ref().unwrap().kernel {
             Kernel::Linear => {
                 let params = SmartcoreSVCParameters::default()
@@ -90,7 +98,7 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::linear());
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&MulticlassSVC::fit(x, y, params, strategy)?).unwrap())
             }
             Kernel::Polynomial(degree, gamma, coef) => {
                 let params = SmartcoreSVCParameters::default()
@@ -99,7 +107,7 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::polynomial(degree, gamma, coef));
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&MulticlassSVC::fit(x, y, params, strategy)?).unwrap())
             }
             Kernel::RBF(gamma) => {
                 let params = SmartcoreSVCParameters::default()
@@ -108,7 +116,7 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::rbf(gamma));
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&MulticlassSVC::fit(x, y, params, strategy)?).unwrap())
             }
             Kernel::Sigmoid(gamma, coef) => {
                 let params = SmartcoreSVCParameters::default()
@@ -117,7 +125,7 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
                     .with_epoch(settings.svc_settings.as_ref().unwrap().epoch)
                     .with_kernel(Kernels::sigmoid(gamma, coef));
 
-                bincode::serialize(&SVC::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&MulticlassSVC::fit(x, y, params, strategy)?).unwrap())
             }
         }
     }
@@ -125,25 +133,139 @@ impl super::ModelWrapper for SupportVectorClassifierWrapper {
     fn predict(x: &DenseMatrix<f32>, final_model: &Vec<u8>, settings: &Settings) -> Vec<f32> {
         match settings.svc_settings.as_ref().unwrap().kernel {
             Kernel::Linear => {
-                let model: SVC<f32, DenseMatrix<f32>, LinearKernel> =
-                    bincode::deserialize(final_model).unwrap();
+                let model: MulticlassSVC<LinearKernel> = bincode::deserialize(final_model).unwrap();
                 model.predict(x).unwrap()
             }
             Kernel::Polynomial(_, _, _) => {
-                let model: SVC<f32, DenseMatrix<f32>, PolynomialKernel<f32>> =
+                let model: MulticlassSVC<PolynomialKernel<f32>> =
                     bincode::deserialize(final_model).unwrap();
                 model.predict(x).unwrap()
             }
             Kernel::RBF(_) => {
-                let model: SVC<f32, DenseMatrix<f32>, RBFKernel<f32>> =
+                let model: MulticlassSVC<RBFKernel<f32>> =
                     bincode::deserialize(final_model).unwrap();
                 model.predict(x).unwrap()
             }
             Kernel::Sigmoid(_, _) => {
-                let model: SVC<f32, DenseMatrix<f32>, SigmoidKernel<f32>> =
+                let model: MulticlassSVC<SigmoidKernel<f32>> =
                     bincode::deserialize(final_model).unwrap();
                 model.predict(x).unwrap()
             }
         }
     }
 }
+
+/// A set of binary smartcore support vector classifiers that vote on the class of each row
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "K: serde::Serialize",
+    deserialize = "K: serde::Deserialize<'de>"
+))]
+struct MulticlassSVC<K: SmartcoreKernel<f32, Vec<f32>>> {
+    /// How the binary classifiers are combined
+    strategy: MulticlassStrategy,
+    /// The class labels in ascending order
+    classes: Vec<f32>,
+    /// The number of training rows in each class, used to break ties between votes
+    class_counts: Vec<usize>,
+    /// One classifier per class for one-vs-rest, or one per pair of classes for one-vs-one
+    estimators: Vec<SVC<f32, DenseMatrix<f32>, K>>,
+}
+
+impl<K: SmartcoreKernel<f32, Vec<f32>> + Clone> MulticlassSVC<K> {
+    /// Fit the binary classifiers. With two classes a single classifier is fit, whatever the
+    /// strategy.
+    #[allow(clippy::ptr_arg, clippy::needless_pass_by_value)]
+    fn fit(
+        x: &DenseMatrix<f32>,
+        y: &Vec<f32>,
+        parameters: SmartcoreSVCParameters<f32, DenseMatrix<f32>, K>,
+        strategy: MulticlassStrategy,
+    ) -> Result<Self, Failed> {
+        let members = class_indices(y);
+        let classes: Vec<f32> = members.iter().map(|class| y[class[0]]).collect();
+        let class_counts: Vec<usize> = members.iter().map(Vec::len).collect();
+        let strategy = if classes.len() == 2 {
+            MulticlassStrategy::OneVsOne
+        } else {
+            strategy
+        };
+
+        let estimators = match strategy {
+            MulticlassStrategy::OneVsRest => members
+                .iter()
+                .map(|class| {
+                    let mut labels = vec![0.0; y.len()];
+                    for &idx in class {
+                        labels[idx] = 1.0;
+                    }
+                    SVC::fit(x, &labels, parameters.clone())
+                })
+                .collect::<Result<Vec<_>, Failed>>()?,
+            MulticlassStrategy::OneVsOne => {
+                let mut estimators = vec![];
+                for (i, first) in members.iter().enumerate() {
+                    for second in &members[i + 1..] {
+                        let mut rows = [first.as_slice(), second.as_slice()].concat();
+                        rows.sort_unstable();
+                        let labels: Vec<f32> = rows.iter().map(|&row| y[row]).collect();
+                        estimators.push(SVC::fit(&x.take(&rows, 0), &labels, parameters.clone())?);
+                    }
+                }
+                estimators
+            }
+        };
+
+        Ok(Self {
+            strategy,
+            classes,
+            class_counts,
+            estimators,
+        })
+    }
+}
+
+impl<K: SmartcoreKernel<f32, Vec<f32>>> Predictor<DenseMatrix<f32>, Vec<f32>> for MulticlassSVC<K> {
+    fn predict(&self, x: &DenseMatrix<f32>) -> Result<Vec<f32>, Failed> {
+        let (n, _) = x.shape();
+        let mut votes = vec![vec![0_usize; self.classes.len()]; n];
+        match self.strategy {
+            MulticlassStrategy::OneVsRest => {
+                for (class, estimator) in self.estimators.iter().enumerate() {
+                    for (row, prediction) in estimator.predict(x)?.into_iter().enumerate() {
+                        if prediction > 0.5 {
+                            votes[row][class] += 1;
+                        }
+                    }
+                }
+            }
+            MulticlassStrategy::OneVsOne => {
+                let pairs = (0..self.classes.len())
+                    .flat_map(|i| (i + 1..self.classes.len()).map(move |j| (i, j)));
+                for ((i, j), estimator) in pairs.zip(&self.estimators) {
+                    for (row, prediction) in estimator.predict(x)?.into_iter().enumerate() {
+                        let winner = if (prediction - self.classes[i]).abs()
+                            <= (prediction - self.classes[j]).abs()
+                        {
+                            i
+                        } else {
+                            j
+                        };
+                        votes[row][winner] += 1;
+                    }
+                }
+            }
+        }
+
+        // Most votes wins, with ties going to the more frequent class
+        Ok(votes
+            .iter()
+            .map(|row| {
+                let best = (0..self.classes.len())
+                    .max_by_key(|&class| (row[class], self.class_counts[class]))
+                    .unwrap_or(0);
+                self.classes[best]
+            })
+            .collect())
+    }
+}
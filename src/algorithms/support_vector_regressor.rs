@@ -1,9 +1,8 @@
 //! Support Vector Regressor
 
 use smartcore::{
+    error::Failed,
     linalg::naive::dense_matrix::DenseMatrix,
-    model_selection::cross_validate,
-    model_selection::CrossValidationResult,
     svm::{
         svr::{SVRParameters as SmartcoreSVRParameters, SVR},
         Kernels, LinearKernel, PolynomialKernel, RBFKernel, SigmoidKernel,
@@ -12,6 +11,8 @@ use smartcore::{
 
 use crate::{Algorithm, Kernel, Settings};
 
+use super::{cross_validate, CrossValidation};
+
 /// The Support Vector Regressor.
 ///
 /// See [scikit-learn's user guide](https://scikit-learn.org/stable/modules/svm.html#svm-regression)
@@ -23,7 +24,7 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
         x: &DenseMatrix<f32>,
         y: &Vec<f32>,
         settings: &Settings,
-    ) -> (CrossValidationResult<f32>, Algorithm) {
+    ) -> Result<(CrossValidation, Algorithm), Failed> {
         let cv = match settings.svr_settings.as_ref().unwrap().kernel {
             Kernel::Linear => cross_validate(
                 SVR::fit,
@@ -34,10 +35,9 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_c(settings.svr_settings.as_ref().unwrap().c)
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::linear()),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Kernel::Polynomial(degree, gamma, coef) => cross_validate(
                 SVR::fit,
                 x,
@@ -47,10 +47,9 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_c(settings.svr_settings.as_ref().unwrap().c)
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::polynomial(degree, gamma, coef)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Kernel::RBF(gamma) => cross_validate(
                 SVR::fit,
                 x,
@@ -60,10 +59,9 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_c(settings.svr_settings.as_ref().unwrap().c)
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::rbf(gamma)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
             Kernel::Sigmoid(gamma, coef) => cross_validate(
                 SVR::fit,
                 x,
@@ -73,15 +71,14 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_c(settings.svr_settings.as_ref().unwrap().c)
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::sigmoid(gamma, coef)),
-                settings.get_kfolds(),
+                settings.get_kfolds(x, y),
                 settings.get_metric(),
-            )
-            .unwrap(),
+            )?,
         };
-        (cv, Algorithm::SVR)
+        Ok((cv, Algorithm::SVR))
     }
 
-    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Vec<u8> {
+    fn train(x: &DenseMatrix<f32>, y: &Vec<f32>, settings: &Settings) -> Result<Vec<u8>, Failed> {
         match settings.svr_settings.as_ref().unwrap().kernel {
             Kernel::Linear => {
                 let params = SmartcoreSVRParameters::default()
@@ -90,7 +87,7 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::linear());
 
-                bincode::serialize(&SVR::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&SVR::fit(x, y, params)?).unwrap())
             }
             Kernel::Polynomial(degree, gamma, coef) => {
                 let params = SmartcoreSVRParameters::default()
@@ -99,7 +96,7 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::polynomial(degree, gamma, coef));
 
-                bincode::serialize(&SVR::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&SVR::fit(x, y, params)?).unwrap())
             }
             Kernel::RBF(gamma) => {
                 let params = SmartcoreSVRParameters::default()
@@ -108,7 +105,7 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::rbf(gamma));
 
-                bincode::serialize(&SVR::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&SVR::fit(x, y, params)?).unwrap())
             }
             Kernel::Sigmoid(gamma, coef) => {
                 let params = SmartcoreSVRParameters::default()
@@ -117,7 +114,7 @@ impl super::ModelWrapper for SupportVectorRegressorWrapper {
                     .with_eps(settings.svr_settings.as_ref().unwrap().c)
                     .with_kernel(Kernels::sigmoid(gamma, coef));
 
-                bincode::serialize(&SVR::fit(x, y, params).unwrap()).unwrap()
+                Ok(bincode::serialize(&SVR::fit(x, y, params)?).unwrap())
             }
         }
     }
@@ -0,0 +1,368 @@
+//! Comparison and training of anomaly detectors
+
+use std::{
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+    time::{Duration, Instant},
+};
+
+use comfy_table::{
+    modifiers::UTF8_SOLID_INNER_BORDERS, presets::UTF8_FULL, Attribute, Cell, Table,
+};
+use humantime::format_duration;
+use rand::{rngs::StdRng, SeedableRng};
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use crate::algorithms::AnomalyDetector;
+use crate::settings::{AnomalyAlgorithm, AnomalyMetric, AnomalySettings};
+use crate::{IntoFeatures, IntoSupervisedData};
+
+/// Trains and compares anomaly detectors
+///
+/// Each detector is fit to unlabelled training data and scores rows with higher scores for more
+/// anomalous rows. The contamination in the settings, the expected fraction of anomalies in the
+/// training data, sets the threshold above which rows are flagged. When a small labelled
+/// validation set is given, the detectors are ranked on it by the metric in the settings and the
+/// best one is kept; otherwise the first detector that is not skipped is kept.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AnomalyModel {
+    /// Settings for the model.
+    settings: AnomalySettings,
+    /// The training data.
+    x_train: DenseMatrix<f32>,
+    /// The labelled validation data, with 1 for anomalies and 0 for normal rows, if any.
+    validation: Option<(DenseMatrix<f32>, Vec<f32>)>,
+    /// The results of the model comparison, from best to worst.
+    comparison: Vec<AnomalyResult>,
+    /// The best detector, which scores new rows.
+    final_model: Option<AnomalyDetector>,
+    /// The score above which the best detector flags a row as an anomaly.
+    threshold: f32,
+}
+
+/// The results of a single anomaly detector
+#[derive(serde::Serialize, serde::Deserialize)]
+struct AnomalyResult {
+    /// The algorithm used
+    algorithm: AnomalyAlgorithm,
+    /// The score above which rows are flagged
+    threshold: f32,
+    /// The number of training rows flagged
+    flagged: usize,
+    /// The validation score by each metric, in the order of `AnomalyMetric::ALL`, if there is a
+    /// validation set
+    scores: Option<Vec<f32>>,
+    /// The time it took to fit and score the detector
+    duration: Duration,
+}
+
+impl AnomalyResult {
+    /// The validation score by a metric, if there is a validation set
+    fn score(&self, metric: AnomalyMetric) -> Option<f32> {
+        let index = AnomalyMetric::ALL
+            .iter()
+            .position(|&other| other == metric)?;
+        self.scores.as_ref().map(|scores| scores[index])
+    }
+}
+
+impl AnomalyModel {
+    /// Create a new anomaly model from unlabelled data.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// let x: Vec<Vec<f32>> = (0..50)
+    ///     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    ///     .collect();
+    /// let model = AnomalyModel::new(x, AnomalySettings::default());
+    /// ```
+    pub fn new<X>(x: X, settings: AnomalySettings) -> Self
+    where
+        X: IntoFeatures,
+    {
+        Self {
+            settings,
+            x_train: x.to_dense_matrix(),
+            validation: None,
+            comparison: vec![],
+            final_model: None,
+            threshold: f32::INFINITY,
+        }
+    }
+
+    /// Create a new anomaly model from unlabelled training data and a small labelled validation
+    /// set, with a label of 1 for anomalies and 0 for normal rows, which ranks the detectors.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// let x: Vec<Vec<f32>> = (0..50)
+    ///     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    ///     .collect();
+    /// let validation_x = vec![vec![0.5, 0.5], vec![0.2, 0.4], vec![10.0, -10.0]];
+    /// let validation_y = vec![0.0, 0.0, 1.0];
+    /// let model = AnomalyModel::new_with_validation(
+    ///     x,
+    ///     (validation_x, validation_y),
+    ///     AnomalySettings::default(),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If a validation label is not 0 or 1, this function will panic.
+    pub fn new_with_validation<X, V>(x: X, validation: V, settings: AnomalySettings) -> Self
+    where
+        X: IntoFeatures,
+        V: IntoSupervisedData,
+    {
+        let (validation_x, validation_y) = validation.to_supervised_data();
+        assert!(
+            validation_y
+                .iter()
+                .all(|&label| label.abs() < f32::EPSILON || (label - 1.0).abs() < f32::EPSILON),
+            "Validation labels must be 1 for anomalies and 0 for normal rows."
+        );
+        Self {
+            validation: Some((validation_x, validation_y)),
+            ..Self::new(x, settings)
+        }
+    }
+
+    /// Load an anomaly model from a file saved with `AnomalyModel::save`.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// # let x: Vec<Vec<f32>> = (0..50)
+    /// #     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    /// #     .collect();
+    /// let model = AnomalyModel::new(x, AnomalySettings::default());
+    /// model.save("tests/load_that_anomaly_model.aml");
+    /// let model = AnomalyModel::new_from_file("tests/load_that_anomaly_model.aml");
+    /// # std::fs::remove_file("tests/load_that_anomaly_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold an anomaly model, this function will panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot load model from file.");
+        bincode::deserialize(&buf).expect("Can not deserialize the model")
+    }
+
+    /// Runs a comparison of anomaly detectors and keeps the best one.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// # let x: Vec<Vec<f32>> = (0..50)
+    /// #     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    /// #     .collect();
+    /// let mut model = AnomalyModel::new(x, AnomalySettings::default().with_seed(0));
+    /// model.train();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the contamination is not above 0 and at most one half, or there are fewer than two
+    /// training rows, this function will panic.
+    pub fn train(&mut self) {
+        let contamination = self.settings.contamination;
+        assert!(
+            contamination > 0.0 && contamination <= 0.5,
+            "The contamination must be above 0 and at most 0.5."
+        );
+        assert!(
+            self.x_train.shape().0 >= 2,
+            "At least two training rows are needed."
+        );
+        let mut rng = self
+            .settings
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+        let mut fitted: Vec<(AnomalyResult, AnomalyDetector)> = vec![];
+        for algorithm in [
+            AnomalyAlgorithm::IsolationForest,
+            AnomalyAlgorithm::LocalOutlierFactor,
+            AnomalyAlgorithm::RobustCovariance,
+        ] {
+            if self.settings.skiplist.contains(&algorithm) {
+                continue;
+            }
+            let start = Instant::now();
+            let (model, scores) = match algorithm {
+                AnomalyAlgorithm::IsolationForest => {
+                    AnomalyDetector::isolation_forest(&self.x_train, &self.settings, &mut rng)
+                }
+                AnomalyAlgorithm::LocalOutlierFactor => {
+                    AnomalyDetector::local_outlier_factor(&self.x_train, &self.settings)
+                }
+                AnomalyAlgorithm::RobustCovariance => {
+                    AnomalyDetector::robust_covariance(&self.x_train, &self.settings, &mut rng)
+                }
+            };
+            let threshold = Self::contamination_threshold(&scores, contamination);
+            let validation_scores = self.validation.as_ref().map(|(x, y)| {
+                let scores = model.scores(x);
+                AnomalyMetric::ALL
+                    .iter()
+                    .map(|metric| metric.compute(y, &scores, threshold))
+                    .collect()
+            });
+            fitted.push((
+                AnomalyResult {
+                    algorithm,
+                    threshold,
+                    flagged: scores.iter().filter(|&&score| score > threshold).count(),
+                    scores: validation_scores,
+                    duration: start.elapsed(),
+                },
+                model,
+            ));
+        }
+
+        // Without a validation set every score is missing and the order is kept
+        let metric = self.settings.sort_by;
+        fitted.sort_by(|(a, _), (b, _)| match (a.score(metric), b.score(metric)) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            _ => std::cmp::Ordering::Equal,
+        });
+        let (comparison, models): (Vec<AnomalyResult>, Vec<AnomalyDetector>) =
+            fitted.into_iter().unzip();
+        self.threshold = comparison
+            .first()
+            .map_or(f32::INFINITY, |result| result.threshold);
+        self.comparison = comparison;
+        self.final_model = models.into_iter().next();
+    }
+
+    /// Score rows with the best detector, with higher scores for more anomalous rows.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// # let x: Vec<Vec<f32>> = (0..50)
+    /// #     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    /// #     .collect();
+    /// let mut model = AnomalyModel::new(x, AnomalySettings::default().with_seed(0));
+    /// model.train();
+    /// let scores = model.anomaly_scores(vec![vec![0.5, 0.5], vec![10.0, -10.0]]);
+    /// assert!(scores[1] > scores[0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, this function will panic.
+    pub fn anomaly_scores<X: IntoFeatures>(&self, x: X) -> Vec<f32> {
+        self.final_model
+            .as_ref()
+            .expect("The model has not been trained.")
+            .scores(&x.to_dense_matrix())
+    }
+
+    /// Flag rows as anomalies with 1, or as normal with 0, by whether the best detector scores
+    /// them above its contamination threshold.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// # let x: Vec<Vec<f32>> = (0..50)
+    /// #     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    /// #     .collect();
+    /// let mut model = AnomalyModel::new(x, AnomalySettings::default().with_seed(0));
+    /// model.train();
+    /// assert_eq!(model.predict(vec![vec![10.0, -10.0]]), vec![1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, this function will panic.
+    pub fn predict<X: IntoFeatures>(&self, x: X) -> Vec<f32> {
+        self.anomaly_scores(x)
+            .into_iter()
+            .map(|score| if score > self.threshold { 1.0 } else { 0.0 })
+            .collect()
+    }
+
+    /// The score above which the best detector flags a row as an anomaly, which leaves the
+    /// contamination fraction of the training rows above it.
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// # let x: Vec<Vec<f32>> = (0..50)
+    /// #     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    /// #     .collect();
+    /// let mut model = AnomalyModel::new(x, AnomalySettings::default().with_seed(0));
+    /// model.train();
+    /// let threshold = model.threshold();
+    /// ```
+    #[must_use]
+    pub const fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Save the anomaly model to a file for later use
+    /// ```
+    /// # use automl::{AnomalySettings, AnomalyModel};
+    /// # let x: Vec<Vec<f32>> = (0..50)
+    /// #     .map(|i| vec![(i % 7) as f32 / 7.0, (i % 5) as f32 / 5.0])
+    /// #     .collect();
+    /// let model = AnomalyModel::new(x, AnomalySettings::default());
+    /// model.save("tests/save_that_anomaly_model.aml");
+    /// # std::fs::remove_file("tests/save_that_anomaly_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = bincode::serialize(&self).expect("Cannot serialize model.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(&serial))
+            .expect("Cannot write model to file.");
+    }
+}
+
+/// Private functions go here
+impl AnomalyModel {
+    /// The training score with the contamination fraction of the training scores above it.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn contamination_threshold(scores: &[f32], contamination: f32) -> f32 {
+        let mut sorted = scores.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let flagged =
+            ((contamination * sorted.len() as f32).round() as usize).min(sorted.len() - 1);
+        sorted[sorted.len() - flagged - 1]
+    }
+}
+
+impl Display for AnomalyModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+        let mut header = vec![
+            Cell::new("Model").add_attribute(Attribute::Bold),
+            Cell::new("Threshold").add_attribute(Attribute::Bold),
+            Cell::new("Flagged").add_attribute(Attribute::Bold),
+            Cell::new("Time").add_attribute(Attribute::Bold),
+        ];
+        for metric in AnomalyMetric::ALL {
+            header.push(Cell::new(format!("Validation {metric}")).add_attribute(Attribute::Bold));
+        }
+        table.set_header(header);
+        for result in &self.comparison {
+            let mut row = vec![
+                format!("{}", result.algorithm),
+                format!("{:.3}", result.threshold),
+                format!("{}", result.flagged),
+                format!("{}", format_duration(result.duration)),
+            ];
+            row.extend(AnomalyMetric::ALL.iter().map(|&metric| {
+                result
+                    .score(metric)
+                    .map_or_else(|| "-".to_string(), |score| format!("{score:.3}"))
+            }));
+            table.add_row(row);
+        }
+        write!(f, "{table}")
+    }
+}
@@ -0,0 +1,546 @@
+//! Calibration of class probabilities with Platt scaling or isotonic regression, and measures of
+//! how well calibrated the probabilities are
+
+use smartcore::{
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    model_selection::BaseKFold,
+};
+
+use crate::settings::{Algorithm, Calibration, Splitter, ThresholdObjective};
+use crate::Settings;
+
+/// The number of equal-width bins in the reliability diagram
+const RELIABILITY_BINS: usize = 10;
+
+/// One bin of a reliability diagram, comparing the mean predicted probability of the rows in the
+/// bin to how often the predicted class actually occurred
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReliabilityBin {
+    /// The mean predicted probability in the bin
+    pub mean_predicted_probability: f32,
+    /// The fraction of the predictions in the bin for which the class occurred
+    pub observed_frequency: f32,
+    /// The number of predictions in the bin
+    pub count: usize,
+}
+
+/// Maps a model's output for one class to the probability of that class
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+enum Calibrator {
+    /// A sigmoid of the output, `1 / (1 + exp(-(a * output + b)))`
+    Sigmoid {
+        /// The slope
+        a: f32,
+        /// The intercept
+        b: f32,
+    },
+    /// A non-decreasing piecewise linear function through the given points, constant beyond them
+    Isotonic {
+        /// The points, in ascending order of output
+        points: Vec<(f32, f32)>,
+    },
+}
+
+impl Calibrator {
+    /// Fit the calibrator to the model outputs and whether the class occurred
+    fn fit(calibration: Calibration, outputs: &[f32], targets: &[bool]) -> Self {
+        match calibration {
+            Calibration::Isotonic { .. } => Self::fit_isotonic(outputs, targets),
+            _ => Self::fit_sigmoid(outputs, targets),
+        }
+    }
+
+    /// Platt scaling: maximize the likelihood of a sigmoid with Newton's method, against targets
+    /// smoothed towards the class prior to avoid overfitting (Platt, 1999)
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::suboptimal_flops
+    )]
+    fn fit_sigmoid(outputs: &[f32], targets: &[bool]) -> Self {
+        let positives = targets.iter().filter(|&&target| target).count() as f64;
+        let negatives = targets.len() as f64 - positives;
+        let (high, low) = (
+            (positives + 1.0) / (positives + 2.0),
+            1.0 / (negatives + 2.0),
+        );
+        let data: Vec<(f64, f64)> = outputs
+            .iter()
+            .zip(targets)
+            .map(|(&output, &target)| (f64::from(output), if target { high } else { low }))
+            .collect();
+        let loss = |a: f64, b: f64| -> f64 {
+            data.iter()
+                .map(|(s, t)| {
+                    let z = a * s + b;
+                    z.max(0.0) + (-z.abs()).exp().ln_1p() - t * z
+                })
+                .sum()
+        };
+
+        let (mut a, mut b) = (0.0, ((positives + 1.0) / (negatives + 1.0)).ln());
+        let mut current = loss(a, b);
+        for _ in 0..100 {
+            // The gradient and Hessian of the loss, with a little ridge to keep it invertible
+            let mut gradient = [0.0; 2];
+            let mut hessian = [[1e-9, 0.0], [0.0, 1e-9]];
+            for (s, t) in &data {
+                let p = 1.0 / (1.0 + (-(a * s + b)).exp());
+                let w = p * (1.0 - p);
+                gradient[0] += (p - t) * s;
+                gradient[1] += p - t;
+                hessian[0][0] += w * s * s;
+                hessian[0][1] += w * s;
+                hessian[1][1] += w;
+            }
+            let determinant = hessian[0][0].mul_add(hessian[1][1], -hessian[0][1].powi(2));
+            let step_a =
+                hessian[1][1].mul_add(gradient[0], -hessian[0][1] * gradient[1]) / determinant;
+            let step_b =
+                hessian[0][0].mul_add(gradient[1], -hessian[0][1] * gradient[0]) / determinant;
+
+            // Backtrack until the step reduces the loss
+            let mut size = 1.0;
+            while size > 1e-10 && loss(a - size * step_a, b - size * step_b) > current {
+                size /= 2.0;
+            }
+            if size <= 1e-10 {
+                break;
+            }
+            a -= size * step_a;
+            b -= size * step_b;
+            current = loss(a, b);
+            if (size * step_a).abs() + (size * step_b).abs() < 1e-8 {
+                break;
+            }
+        }
+        Self::Sigmoid {
+            a: a as f32,
+            b: b as f32,
+        }
+    }
+
+    /// Isotonic regression with the pool adjacent violators algorithm, keeping the smallest and
+    /// largest output of each pooled block as the points of the function
+    #[allow(clippy::cast_precision_loss)]
+    fn fit_isotonic(outputs: &[f32], targets: &[bool]) -> Self {
+        let mut order: Vec<usize> = (0..outputs.len()).collect();
+        order.sort_by(|&i, &j| outputs[i].total_cmp(&outputs[j]));
+
+        // Each block holds its smallest and largest output, its total target and its size
+        let mut blocks: Vec<(f32, f32, f32, f32)> = vec![];
+        for i in order {
+            let target = if targets[i] { 1.0 } else { 0.0 };
+            blocks.push((outputs[i], outputs[i], target, 1.0));
+            while blocks.len() > 1 {
+                let (lowest, highest, total, count) = blocks[blocks.len() - 1];
+                let (_, previous_highest, previous_total, previous_count) =
+                    blocks[blocks.len() - 2];
+                // Pool ties in the output, and any pair of blocks out of order
+                if lowest > previous_highest && total / count > previous_total / previous_count {
+                    break;
+                }
+                blocks.pop();
+                let merged = blocks.last_mut().unwrap();
+                merged.1 = highest;
+                merged.2 += total;
+                merged.3 += count;
+            }
+        }
+
+        let mut points = vec![];
+        for (lowest, highest, total, count) in blocks {
+            points.push((lowest, total / count));
+            if highest > lowest {
+                points.push((highest, total / count));
+            }
+        }
+        Self::Isotonic { points }
+    }
+
+    /// The calibrated probability for a model output
+    fn apply(&self, output: f32) -> f32 {
+        match self {
+            Self::Sigmoid { a, b } => 1.0 / (1.0 + (-a.mul_add(output, *b)).exp()),
+            Self::Isotonic { points } => {
+                let next = points.partition_point(|&(x, _)| x <= output);
+                match (next.checked_sub(1).map(|i| points[i]), points.get(next)) {
+                    (None, None) => 0.5,
+                    (Some((_, y)), None) | (None, Some(&(_, y))) => y,
+                    (Some((x0, y0)), Some(&(x1, y1))) => y0 + (y1 - y0) * (output - x0) / (x1 - x0),
+                }
+            }
+        }
+    }
+}
+
+/// A model refit on all but one of the folds, with a calibrator for each class fit on the fold
+/// left out
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CalibratedFold {
+    /// The serialized model
+    model: Vec<u8>,
+    /// The index among all of the classes of each class the model was trained on, in the order of
+    /// its scores
+    columns: Vec<usize>,
+    /// The calibrator of each class, if the model has a score for it
+    calibrators: Vec<Option<Calibrator>>,
+}
+
+/// A classifier whose class probabilities are calibrated with cross-validation
+///
+/// The output of a model for a class is the margin of its score for that class over its best score
+/// for any other class, and each calibrator turns that margin into the probability of the class.
+/// The probabilities of each fold model are normalized, then averaged over the fold models. Only
+/// algorithms with scores, as given by `Algorithm::get_score_predictor`, can be calibrated.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CalibratedClassifier {
+    /// The algorithm of the fold models
+    algorithm: Algorithm,
+    /// The classes, in ascending order
+    classes: Vec<f32>,
+    /// The fold models and their calibrators
+    folds: Vec<CalibratedFold>,
+    /// The Brier score of the calibrated probabilities
+    pub brier_score: f32,
+    /// The reliability diagram of the calibrated probabilities
+    pub reliability: Vec<ReliabilityBin>,
+}
+
+impl CalibratedClassifier {
+    /// The classes, in ascending order
+    pub(crate) fn classes(&self) -> &[f32] {
+        &self.classes
+    }
+
+    /// Fit calibrated fold models with the method and number of folds in the settings. The Brier
+    /// score and reliability diagram are measured on each fold with calibrators fit to the other
+    /// folds, so they are not flattered by calibrating on the same rows they are measured on.
+    /// Those out-of-fold probabilities are returned alongside the classifier, one row per labeled
+    /// row of the data.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to calibrate, which must have scores
+    /// * `x` - The input data
+    /// * `y` - The output data
+    /// * `labeled` - The number of leading rows to calibrate on, with the rest only trained on
+    /// * `classes` - The classes, in ascending order
+    /// * `settings` - The settings for the model
+    pub fn fit(
+        algorithm: Algorithm,
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        labeled: usize,
+        classes: &[f32],
+        settings: &Settings,
+    ) -> (Self, Vec<Vec<f32>>) {
+        let calibration = settings.probability_calibration();
+        let number_of_folds = match calibration {
+            Calibration::Sigmoid { folds } | Calibration::Isotonic { folds } => folds,
+            Calibration::None => panic!("Calibration is disabled in the settings."),
+        };
+        let scorer = algorithm
+            .get_score_predictor()
+            .expect("Only algorithms with scores can be calibrated.");
+
+        // Score each fold with a model trained on the others
+        let mut held_out = vec![];
+        for (train, test) in Splitter::StratifiedKFold
+            .split(
+                x,
+                &y[..labeled],
+                number_of_folds,
+                settings.shuffle,
+                &mut settings.rng("calibration", y),
+            )
+            .with_train_only(labeled..y.len())
+            .split(x)
+        {
+            let y_train: Vec<f32> = train.iter().map(|&idx| y[idx]).collect();
+            let model = algorithm.get_trainer()(&x.take(&train, 0), &y_train, settings)
+                .expect("Error during training.");
+            let margins = margins(&scorer(&x.take(&test, 0), &model, settings));
+            held_out.push((model, columns(&y_train, classes), test, margins));
+        }
+
+        // Fit the calibrators of each fold, and of the other folds for scoring
+        let fit_calibrators = |folds: &[usize]| -> Vec<Option<Calibrator>> {
+            (0..classes.len())
+                .map(|class| {
+                    let mut outputs = vec![];
+                    let mut targets = vec![];
+                    for &fold in folds {
+                        let (_, columns, test, margins) = &held_out[fold];
+                        if let Some(column) = columns.iter().position(|&c| c == class) {
+                            for (&idx, row) in test.iter().zip(margins) {
+                                outputs.push(row[column]);
+                                targets.push(y[idx].total_cmp(&classes[class]).is_eq());
+                            }
+                        }
+                    }
+                    (!outputs.is_empty()).then(|| Calibrator::fit(calibration, &outputs, &targets))
+                })
+                .collect()
+        };
+        let mut probabilities = vec![vec![]; labeled];
+        for (fold, (_, columns, test, margins)) in held_out.iter().enumerate() {
+            let others: Vec<usize> = (0..held_out.len()).filter(|&i| i != fold).collect();
+            let calibrators = fit_calibrators(&others);
+            for (&idx, row) in test.iter().zip(margins) {
+                probabilities[idx] = normalized_probabilities(&calibrators, columns, row);
+            }
+        }
+        let calibrators: Vec<Vec<Option<Calibrator>>> = (0..held_out.len())
+            .map(|fold| fit_calibrators(&[fold]))
+            .collect();
+        let folds = held_out
+            .into_iter()
+            .zip(calibrators)
+            .map(|((model, columns, _, _), calibrators)| CalibratedFold {
+                model,
+                columns,
+                calibrators,
+            })
+            .collect();
+
+        (
+            Self {
+                algorithm,
+                classes: classes.to_vec(),
+                folds,
+                brier_score: brier_score(&y[..labeled], classes, &probabilities),
+                reliability: reliability_diagram(&y[..labeled], classes, &probabilities),
+            },
+            probabilities,
+        )
+    }
+
+    /// The calibrated probability of each class for each row, with the classes in ascending order
+    #[allow(clippy::cast_precision_loss)]
+    pub fn predict_proba(&self, x: &DenseMatrix<f32>, settings: &Settings) -> Vec<Vec<f32>> {
+        let scorer = self
+            .algorithm
+            .get_score_predictor()
+            .expect("Only algorithms with scores can be calibrated.");
+        let mut probabilities = vec![vec![0.0; self.classes.len()]; x.shape().0];
+        for fold in &self.folds {
+            let margins = margins(&scorer(x, &fold.model, settings));
+            for (row, margins) in margins.iter().enumerate() {
+                let fold_probabilities =
+                    normalized_probabilities(&fold.calibrators, &fold.columns, margins);
+                for (total, probability) in probabilities[row].iter_mut().zip(fold_probabilities) {
+                    *total += probability / self.folds.len() as f32;
+                }
+            }
+        }
+        probabilities
+    }
+}
+
+/// The index among all of the classes of each class in the training labels of a model, in
+/// ascending order
+fn columns(y: &[f32], classes: &[f32]) -> Vec<usize> {
+    classes
+        .iter()
+        .enumerate()
+        .filter(|(_, &class)| y.iter().any(|label| label.total_cmp(&class).is_eq()))
+        .map(|(column, _)| column)
+        .collect()
+}
+
+/// The margin of the score of each class over the best score of any other class, for each row. A
+/// model trained on a single class has a margin of zero.
+fn margins(scores: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    scores
+        .iter()
+        .map(|row| {
+            let row: Vec<f32> = row.iter().map(|score| score.max(f32::MIN)).collect();
+            (0..row.len())
+                .map(|class| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|&(other, _)| other != class)
+                        .map(|(_, &score)| score)
+                        .reduce(f32::max)
+                        .map_or(0.0, |best| row[class] - best)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// One if the label is the class, zero otherwise
+fn indicator(label: f32, class: f32) -> f32 {
+    if label.total_cmp(&class).is_eq() {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// The calibrated probability of each class for the margins of a single row, normalized to sum
+/// to one. Classes the model has no calibrated score for have a probability of zero.
+#[allow(clippy::cast_precision_loss)]
+fn normalized_probabilities(
+    calibrators: &[Option<Calibrator>],
+    columns: &[usize],
+    margins: &[f32],
+) -> Vec<f32> {
+    let mut probabilities = vec![0.0; calibrators.len()];
+    for (&class, &margin) in columns.iter().zip(margins) {
+        if let Some(calibrator) = &calibrators[class] {
+            probabilities[class] = calibrator.apply(margin);
+        }
+    }
+    let total: f32 = probabilities.iter().sum();
+    if total > 0.0 {
+        probabilities.iter().map(|p| p / total).collect()
+    } else {
+        vec![1.0 / calibrators.len() as f32; calibrators.len()]
+    }
+}
+
+/// The pairs of predicted probability and whether the class occurred that calibration is
+/// measured on: the probability of the larger class for two classes, or of every class otherwise
+fn probability_outcomes(y: &[f32], classes: &[f32], probabilities: &[Vec<f32>]) -> Vec<(f32, f32)> {
+    let measured: Vec<usize> = if classes.len() == 2 {
+        vec![1]
+    } else {
+        (0..classes.len()).collect()
+    };
+    y.iter()
+        .zip(probabilities)
+        .flat_map(|(&y, row)| {
+            measured
+                .iter()
+                .map(move |&class| (row[class], indicator(y, classes[class])))
+        })
+        .collect()
+}
+
+/// The Brier score of class probabilities: the mean squared difference between the probability
+/// of the larger class and whether it occurred for two classes, or the squared differences summed
+/// over the classes for more (Brier's original definition)
+#[allow(clippy::cast_precision_loss)]
+fn brier_score(y: &[f32], classes: &[f32], probabilities: &[Vec<f32>]) -> f32 {
+    probability_outcomes(y, classes, probabilities)
+        .iter()
+        .map(|(probability, outcome)| (probability - outcome).powi(2))
+        .sum::<f32>()
+        / y.len() as f32
+}
+
+/// The reliability diagram of class probabilities, leaving out empty bins
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn reliability_diagram(
+    y: &[f32],
+    classes: &[f32],
+    probabilities: &[Vec<f32>],
+) -> Vec<ReliabilityBin> {
+    let mut bins = vec![(0.0, 0.0, 0); RELIABILITY_BINS];
+    for (probability, outcome) in probability_outcomes(y, classes, probabilities) {
+        let bin = ((probability * RELIABILITY_BINS as f32) as usize).min(RELIABILITY_BINS - 1);
+        bins[bin].0 += probability;
+        bins[bin].1 += outcome;
+        bins[bin].2 += 1;
+    }
+    bins.into_iter()
+        .filter(|&(_, _, count)| count > 0)
+        .map(|(probability, outcome, count)| ReliabilityBin {
+            mean_predicted_probability: probability / count as f32,
+            observed_frequency: outcome / count as f32,
+            count,
+        })
+        .collect()
+}
+
+/// Tune the decision threshold on the probability of the positive class, choosing the probability
+/// of one of the rows that scores best on the objective
+///
+/// # Arguments
+///
+/// * `objective` - The objective to optimize
+/// * `y` - The output data
+/// * `positive` - The positive class
+/// * `probabilities` - The probability of the positive class for each row
+#[allow(clippy::suboptimal_flops)]
+pub fn tune_threshold(
+    objective: ThresholdObjective,
+    y: &[f32],
+    positive: f32,
+    probabilities: &[f32],
+) -> f32 {
+    // The rows from the most to the least likely to be positive
+    let mut rows: Vec<(f32, bool)> = probabilities
+        .iter()
+        .zip(y)
+        .map(|(&probability, y)| (probability, y.total_cmp(&positive).is_eq()))
+        .collect();
+    rows.sort_by(|a, b| b.0.total_cmp(&a.0));
+    #[allow(clippy::cast_precision_loss)]
+    let positives = rows.iter().filter(|(_, is_positive)| *is_positive).count() as f32;
+    #[allow(clippy::cast_precision_loss)]
+    let negatives = rows.len() as f32 - positives;
+
+    let score = |true_positives: f32, false_positives: f32| -> f32 {
+        let true_negatives = negatives - false_positives;
+        let false_negatives = positives - true_positives;
+        let recall = true_positives / f32::max(true_positives + false_negatives, 1.0);
+        let precision = true_positives / f32::max(true_positives + false_positives, 1.0);
+        match objective {
+            ThresholdObjective::None => 0.0,
+            ThresholdObjective::Cost { matrix } => {
+                -(true_negatives * matrix[0][0]
+                    + false_positives * matrix[0][1]
+                    + false_negatives * matrix[1][0]
+                    + true_positives * matrix[1][1])
+            }
+            ThresholdObjective::F1 => {
+                2.0 * true_positives
+                    / f32::max(
+                        2.0 * true_positives + false_positives + false_negatives,
+                        1.0,
+                    )
+            }
+            // Any threshold reaching the precision beats every threshold that does not
+            ThresholdObjective::RecallAtPrecision { precision: target } => {
+                if true_positives > 0.0 && precision >= target {
+                    recall
+                } else {
+                    precision - 1.0
+                }
+            }
+            ThresholdObjective::YoudensJ => {
+                recall - false_positives / f32::max(false_positives + true_negatives, 1.0)
+            }
+        }
+    };
+
+    // Lower the threshold one distinct probability at a time, counting the rows it lets through,
+    // and keep the highest of the thresholds that score best
+    let (mut true_positives, mut false_positives) = (0.0, 0.0);
+    let mut best: Option<(f32, f32)> = None;
+    for (i, &(probability, is_positive)) in rows.iter().enumerate() {
+        if is_positive {
+            true_positives += 1.0;
+        } else {
+            false_positives += 1.0;
+        }
+        if rows
+            .get(i + 1)
+            .is_some_and(|next| next.0.total_cmp(&probability).is_eq())
+        {
+            continue;
+        }
+        let score = score(true_positives, false_positives);
+        if best.is_none_or(|(_, best_score)| score.total_cmp(&best_score).is_gt()) {
+            best = Some((probability, score));
+        }
+    }
+    best.map_or(0.5, |(threshold, _)| threshold)
+}
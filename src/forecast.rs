@@ -0,0 +1,274 @@
+//! Forecasting of time-ordered targets from their own history
+
+use std::{
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+};
+
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use crate::settings::Splitter;
+use crate::{IntoFeatures, IntoLabels, IntoSupervisedData, Settings, SupervisedModel};
+
+/// Trains and compares regressors that forecast a time-ordered target
+///
+/// Each time step is described by lags, rolling means and deltas of the target at earlier time
+/// steps, as set with `Settings::with_forecast_settings`, followed by the exogenous columns of that
+/// time step, if any. The regressors are compared with expanding-window cross-validation, which
+/// always tests on time steps after the training time steps, whatever splitter the settings hold.
+/// Likewise, holdout data, the validation data of a blended final model and the calibration data
+/// of split conformal prediction are the latest time steps.
+///
+/// Forecasts of several steps are recursive: each forecast becomes the history of the next.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ForecastModel {
+    /// Settings for the model.
+    settings: Settings,
+    /// The target at each time step, oldest first.
+    y_train: Vec<f32>,
+    /// The exogenous columns at each time step, if any.
+    exogenous: Option<DenseMatrix<f32>>,
+    /// The comparison of regressors on the generated features, which keeps the best one.
+    model: Option<SupervisedModel>,
+}
+
+impl ForecastModel {
+    /// Create a new forecasting model from a time-ordered target, oldest first.
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// let y: Vec<f32> = (0..60).map(|t| t as f32 + (t % 7) as f32).collect();
+    /// let model = ForecastModel::new(y, Settings::default_regression());
+    /// ```
+    pub fn new<Y>(y: Y, settings: Settings) -> Self
+    where
+        Y: IntoLabels,
+    {
+        Self {
+            settings,
+            y_train: y.into_vec(),
+            exogenous: None,
+            model: None,
+        }
+    }
+
+    /// Create a new forecasting model from exogenous columns and a time-ordered target, with one
+    /// row of the columns per time step, oldest first.
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// let x: Vec<Vec<f32>> = (0..60).map(|t| vec![(t % 7 == 5) as u8 as f32]).collect();
+    /// let y: Vec<f32> = x.iter().map(|row| 10.0 + 5.0 * row[0]).collect();
+    /// let model = ForecastModel::new_with_exogenous((x, y), Settings::default_regression());
+    /// ```
+    pub fn new_with_exogenous<D>(data: D, settings: Settings) -> Self
+    where
+        D: IntoSupervisedData,
+    {
+        let (x, y) = data.to_supervised_data();
+        Self {
+            settings,
+            y_train: y,
+            exogenous: Some(x),
+            model: None,
+        }
+    }
+
+    /// Load a forecasting model from a file saved with `ForecastModel::save`.
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// # let y: Vec<f32> = (0..60).map(|t| t as f32 + (t % 7) as f32).collect();
+    /// let model = ForecastModel::new(y, Settings::default_regression());
+    /// model.save("tests/load_that_forecast_model.aml");
+    /// let model = ForecastModel::new_from_file("tests/load_that_forecast_model.aml");
+    /// # std::fs::remove_file("tests/load_that_forecast_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold a forecasting model, this function will panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot load model from file.");
+        bincode::deserialize(&buf).expect("Can not deserialize the model")
+    }
+
+    /// Builds the features of every time step with enough history and runs a model comparison
+    /// on them.
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// # let y: Vec<f32> = (0..60).map(|t| t as f32 + (t % 7) as f32).collect();
+    /// let mut model = ForecastModel::new(
+    ///     y,
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// # .with_number_of_folds(3)
+    /// );
+    /// model.train();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the settings are for classification, a lag or rolling window is zero, or the target is
+    /// not longer than the history the features need, this function will panic.
+    pub fn train(&mut self) {
+        assert!(
+            !self.settings.is_classification(),
+            "Forecasting models are for regression."
+        );
+        let parameters = &self.settings.forecast_settings;
+        assert!(
+            !parameters
+                .lags
+                .iter()
+                .chain(&parameters.rolling_windows)
+                .any(|&steps| steps == 0),
+            "Lags and rolling windows must be at least one step."
+        );
+        let history = parameters.history();
+        assert!(
+            self.y_train.len() > history,
+            "The target must be longer than the {history} steps of history the features need."
+        );
+
+        let rows: Vec<Vec<f32>> = (history..self.y_train.len())
+            .map(|step| self.step_features(&self.y_train[..step], step, self.exogenous.as_ref()))
+            .collect();
+        let mut model = SupervisedModel::build(
+            DenseMatrix::from_2d_vec(&rows),
+            self.y_train[history..].to_vec(),
+            self.settings
+                .clone()
+                .with_splitter(Splitter::TimeSeriesSplit)
+                .shuffle_data(false),
+        );
+        model.train();
+        self.model = Some(model);
+    }
+
+    /// Forecast the next `h` time steps after the training data.
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// # let y: Vec<f32> = (0..60).map(|t| t as f32 + (t % 7) as f32).collect();
+    /// let mut model = ForecastModel::new(
+    ///     y,
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// # .with_number_of_folds(3)
+    /// );
+    /// model.train();
+    /// let forecasts = model.forecast(7);
+    /// assert_eq!(forecasts.len(), 7);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, or was created with exogenous columns, whose future
+    /// values `ForecastModel::forecast_with_exogenous` takes instead, this function will panic.
+    #[must_use]
+    pub fn forecast(&self, h: usize) -> Vec<f32> {
+        assert!(
+            self.exogenous.is_none(),
+            "Forecasts with exogenous columns need their future values."
+        );
+        self.recursive_forecast(h, None)
+    }
+
+    /// Forecast the time steps after the training data, one per row of the future values of the
+    /// exogenous columns.
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..60).map(|t| vec![(t % 7 == 5) as u8 as f32]).collect();
+    /// # let y: Vec<f32> = x.iter().map(|row| 10.0 + 5.0 * row[0]).collect();
+    /// let mut model = ForecastModel::new_with_exogenous(
+    ///     (x, y),
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// # .with_number_of_folds(3)
+    /// );
+    /// model.train();
+    /// let forecasts = model.forecast_with_exogenous(vec![vec![0.0], vec![1.0]]);
+    /// assert_eq!(forecasts.len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, was created without exogenous columns, or the future
+    /// values have a different number of columns, this function will panic.
+    pub fn forecast_with_exogenous<X: IntoFeatures>(&self, x: X) -> Vec<f32> {
+        let x = x.to_dense_matrix();
+        let columns = self
+            .exogenous
+            .as_ref()
+            .expect("The model was created without exogenous columns.")
+            .shape()
+            .1;
+        assert_eq!(
+            x.shape().1,
+            columns,
+            "The future values must have the {columns} exogenous columns of the training data."
+        );
+        self.recursive_forecast(x.shape().0, Some(&x))
+    }
+
+    /// Save the forecasting model to a file for later use
+    /// ```
+    /// # use automl::{ForecastModel, Settings};
+    /// # let y: Vec<f32> = (0..60).map(|t| t as f32 + (t % 7) as f32).collect();
+    /// let model = ForecastModel::new(y, Settings::default_regression());
+    /// model.save("tests/save_that_forecast_model.aml");
+    /// # std::fs::remove_file("tests/save_that_forecast_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = bincode::serialize(&self).expect("Cannot serialize model.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(&serial))
+            .expect("Cannot write model to file.");
+    }
+}
+
+/// Private functions go here
+impl ForecastModel {
+    /// The features of a time step: those built from the target before it, followed by its row of
+    /// the exogenous columns, if any.
+    fn step_features(
+        &self,
+        history: &[f32],
+        row: usize,
+        exogenous: Option<&DenseMatrix<f32>>,
+    ) -> Vec<f32> {
+        let mut features = self.settings.forecast_settings.features(history);
+        if let Some(exogenous) = exogenous {
+            features.extend(exogenous.get_row_as_vec(row));
+        }
+        features
+    }
+
+    /// Forecast `h` steps, feeding each forecast back as history for the next.
+    fn recursive_forecast(&self, h: usize, exogenous: Option<&DenseMatrix<f32>>) -> Vec<f32> {
+        let model = self
+            .model
+            .as_ref()
+            .expect("The model has not been trained.");
+        let mut history = self.y_train.clone();
+        for step in 0..h {
+            let features = self.step_features(&history, step, exogenous);
+            history.extend(model.predict(vec![features]));
+        }
+        history.split_off(self.y_train.len())
+    }
+}
+
+impl Display for ForecastModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.model {
+            Some(model) => write!(f, "{model}"),
+            None => write!(f, "The forecasting model has not been trained."),
+        }
+    }
+}
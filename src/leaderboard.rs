@@ -0,0 +1,224 @@
+//! Per-fold scores, nested cross-validation and self-training results and statistical comparison
+//! of the trained models
+
+use crate::settings::Algorithm;
+use crate::utils::standard_deviation;
+use crate::ReliabilityBin;
+
+/// The significance level used to decide whether a model is tied with the best
+const SIGNIFICANCE_LEVEL: f32 = 0.05;
+
+/// The cross-validation results of a single model, ranked against the best model
+#[derive(Clone)]
+pub struct LeaderboardEntry {
+    /// The algorithm used
+    pub algorithm: Algorithm,
+    /// The training score on each cross-validation fold
+    pub train_scores: Vec<f32>,
+    /// The testing score on each cross-validation fold
+    pub test_scores: Vec<f32>,
+    /// The mean testing score across folds
+    pub mean_test_score: f32,
+    /// The standard deviation of the testing score across folds
+    pub std_test_score: f32,
+    /// The lower and upper bounds of the 95% confidence interval for the mean testing score
+    pub confidence_interval: (f32, f32),
+    /// The p-value of a corrected resampled t-test against the best model, or `None` for the
+    /// best model itself
+    pub p_value: Option<f32>,
+    /// Whether the model is statistically indistinguishable from the best model
+    pub tied_with_best: bool,
+    /// The Brier score of the calibrated class probabilities, if calibration is enabled
+    pub brier_score: Option<f32>,
+    /// The reliability diagram of the calibrated class probabilities, if calibration is enabled
+    pub reliability: Vec<ReliabilityBin>,
+}
+
+/// The result of a single outer fold of nested cross-validation
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct NestedFold {
+    /// The algorithm that won model selection on the outer training fold
+    pub winner: Algorithm,
+    /// The score of the final model on the outer testing fold
+    pub score: f32,
+}
+
+/// The result of a single round of self-training
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct SelfTrainingRound {
+    /// The number of pseudo-labeled rows in the training data of the round
+    pub pseudo_labeled: usize,
+    /// The algorithm that won model selection in the round
+    pub winner: Algorithm,
+    /// The mean cross-validation testing score of the winner, whose testing folds include the
+    /// pseudo-labeled rows
+    pub score: f32,
+    /// The score of the final model of the round on the holdout data, if a holdout set was used
+    pub holdout_score: Option<f32>,
+}
+
+impl LeaderboardEntry {
+    /// Build the leaderboard entry for a model, comparing it to the best model's fold scores.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm used
+    /// * `train_scores` - The training score on each fold
+    /// * `test_scores` - The testing score on each fold
+    /// * `best_test_scores` - The testing score of the best model on each fold, or `None` if this
+    ///   is the best model
+    /// * `test_train_ratio` - The ratio of testing rows to training rows in each fold
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn new(
+        algorithm: Algorithm,
+        train_scores: Vec<f32>,
+        test_scores: Vec<f32>,
+        best_test_scores: Option<&[f32]>,
+        test_train_ratio: f32,
+    ) -> Self {
+        let k = test_scores.len() as f32;
+        let mean_test_score = test_scores.iter().sum::<f32>() / k;
+        let std_test_score = standard_deviation(&test_scores);
+        let half_width = if test_scores.len() > 1 {
+            students_t_quantile(0.975, k - 1.0) * std_test_score / k.sqrt()
+        } else {
+            0.0
+        };
+        let p_value = best_test_scores
+            .map(|best| corrected_resampled_t_test(best, &test_scores, test_train_ratio));
+
+        Self {
+            algorithm,
+            train_scores,
+            test_scores,
+            mean_test_score,
+            std_test_score,
+            confidence_interval: (mean_test_score - half_width, mean_test_score + half_width),
+            p_value,
+            tied_with_best: p_value.is_none_or(|p| p >= SIGNIFICANCE_LEVEL),
+            brier_score: None,
+            reliability: vec![],
+        }
+    }
+}
+
+/// Two-sided p-value of the Nadeau and Bengio corrected resampled t-test, which accounts for the
+/// overlap between training sets of different folds.
+#[allow(clippy::cast_precision_loss)]
+fn corrected_resampled_t_test(a: &[f32], b: &[f32], test_train_ratio: f32) -> f32 {
+    let differences: Vec<f32> = a.iter().zip(b).map(|(x, y)| x - y).collect();
+    let k = differences.len() as f32;
+    if differences.len() < 2 {
+        return 1.0;
+    }
+    let mean = differences.iter().sum::<f32>() / k;
+    let variance = standard_deviation(&differences).powi(2);
+    if variance <= f32::EPSILON {
+        return if mean.abs() <= f32::EPSILON { 1.0 } else { 0.0 };
+    }
+    let t = mean / ((1.0 / k + test_train_ratio) * variance).sqrt();
+    2.0 * (1.0 - students_t_cdf(t.abs(), k - 1.0))
+}
+
+/// Cumulative distribution function of Student's t distribution with `df` degrees of freedom
+fn students_t_cdf(t: f32, df: f32) -> f32 {
+    let tail = 0.5 * regularized_incomplete_beta(df / 2.0, 0.5, df / t.mul_add(t, df));
+    if t >= 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Quantile function of Student's t distribution, found by bisection on the CDF
+fn students_t_quantile(p: f32, df: f32) -> f32 {
+    let (mut low, mut high) = (-1000.0_f32, 1000.0_f32);
+    for _ in 0..100 {
+        let mid = f32::midpoint(low, high);
+        if students_t_cdf(mid, df) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    f32::midpoint(low, high)
+}
+
+/// Regularized incomplete beta function, evaluated with a continued fraction
+#[allow(clippy::many_single_char_names, clippy::suboptimal_flops)]
+fn regularized_incomplete_beta(a: f32, b: f32, x: f32) -> f32 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let (a, b, x) = (f64::from(a), f64::from(b), f64::from(x));
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    #[allow(clippy::cast_possible_truncation)]
+    if x < (a + 1.0) / (a + b + 2.0) {
+        (front * beta_continued_fraction(a, b, x) / a) as f32
+    } else {
+        (1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b) as f32
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz's method)
+#[allow(clippy::many_single_char_names, clippy::suboptimal_flops)]
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let tiny = 1e-30;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < tiny {
+        d = tiny;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..200 {
+        let m = f64::from(m);
+        let numerator = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        for (i, numerator) in [
+            numerator,
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            d = numerator.mul_add(d, 1.0);
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = numerator / c + 1.0;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+            if i == 1 && (d * c - 1.0).abs() < 1e-10 {
+                return h;
+            }
+        }
+    }
+    h
+}
+
+/// Natural log of the gamma function (Lanczos approximation)
+fn ln_gamma(x: f64) -> f64 {
+    let coefficients = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = (x + 0.5).mul_add(tmp.ln(), -tmp);
+    let mut y = x;
+    let series = coefficients.iter().fold(1.000_000_000_190_015, |sum, c| {
+        y += 1.0;
+        sum + c / y
+    });
+    tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
@@ -14,23 +14,41 @@
 #![doc = include_str!("../README.md")]
 
 pub mod settings;
-pub use settings::Settings;
-use settings::{Algorithm, Distance, FinalModel, Kernel, Metric, PreProcessing};
+use settings::{
+    Algorithm, Calibration, ClassWeight, Conformal, Distance, FinalModel, Folds, Halving, Kernel,
+    Metric, PreProcessing, Resampling, SelfTraining, Splitter, ThresholdObjective,
+};
+pub use settings::{AnomalySettings, ClusteringSettings, Settings};
 
 pub mod cookbook;
 
+mod leaderboard;
+pub use leaderboard::{LeaderboardEntry, NestedFold, SelfTrainingRound};
+
+mod multi_output;
+pub use multi_output::MultiOutputModel;
+
+mod multi_label;
+pub use multi_label::MultiLabelModel;
+
+mod unsupervised;
+pub use unsupervised::UnsupervisedModel;
+
+mod anomaly;
+pub use anomaly::AnomalyModel;
+
+mod forecast;
+pub use forecast::ForecastModel;
+
+mod calibration;
+pub use calibration::ReliabilityBin;
+use calibration::{tune_threshold, CalibratedClassifier};
+
 mod algorithms;
-use algorithms::{
-    CategoricalNaiveBayesClassifierWrapper, DecisionTreeClassifierWrapper,
-    DecisionTreeRegressorWrapper, ElasticNetRegressorWrapper, GaussianNaiveBayesClassifierWrapper,
-    KNNClassifierWrapper, KNNRegressorWrapper, LassoRegressorWrapper, LinearRegressorWrapper,
-    LogisticRegressionWrapper, ModelWrapper, RandomForestClassifierWrapper,
-    RandomForestRegressorWrapper, RidgeRegressorWrapper, SupportVectorClassifierWrapper,
-    SupportVectorRegressorWrapper,
-};
+use algorithms::CrossValidation;
 
 mod utils;
-use utils::elementwise_multiply;
+use utils::{elementwise_multiply, standard_deviation, subsample, supports_tweedie_targets};
 
 use itertools::Itertools;
 use smartcore::{
@@ -40,7 +58,7 @@ use smartcore::{
         svd::{SVDParameters, SVD},
     },
     linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
-    model_selection::{train_test_split, CrossValidationResult},
+    model_selection::{BaseKFold, CrossValidationResult},
 };
 use std::{
     cmp::Ordering::Equal,
@@ -85,6 +103,20 @@ pub trait IntoLabels {
     fn into_vec(self) -> Vec<f32>;
 }
 
+/// This trait must be implemented for any types passed to the `MultiOutputModel::new` or
+/// `MultiLabelModel::new` as data.
+pub trait IntoMultiOutputData {
+    /// Converts the struct into paired features and targets, with one column per target or label
+    fn to_multi_output_data(self) -> (DenseMatrix<f32>, DenseMatrix<f32>);
+}
+
+/// Types that implement this trait can be paired in a tuple with a type implementing
+/// `IntoFeatures` to automatically satisfy `IntoMultiOutputData`.
+pub trait IntoTargets {
+    /// Converts the struct into a dense matrix of targets, with one column per target
+    fn to_target_matrix(self) -> DenseMatrix<f32>;
+}
+
 impl IntoSupervisedData for Dataset<f32, f32> {
     fn to_supervised_data(self) -> (DenseMatrix<f32>, Vec<f32>) {
         (
@@ -138,6 +170,22 @@ where
     }
 }
 
+impl<X, Y> IntoMultiOutputData for (X, Y)
+where
+    X: IntoFeatures,
+    Y: IntoTargets,
+{
+    fn to_multi_output_data(self) -> (DenseMatrix<f32>, DenseMatrix<f32>) {
+        (self.0.to_dense_matrix(), self.1.to_target_matrix())
+    }
+}
+
+impl IntoTargets for Vec<Vec<f32>> {
+    fn to_target_matrix(self) -> DenseMatrix<f32> {
+        DenseMatrix::from_2d_vec(&self)
+    }
+}
+
 impl IntoFeatures for Vec<Vec<f32>> {
     fn to_dense_matrix(self) -> DenseMatrix<f32> {
         DenseMatrix::from_2d_vec(&self)
@@ -157,6 +205,13 @@ impl IntoFeatures for Array2<f32> {
     }
 }
 
+#[cfg(feature = "nd")]
+impl IntoTargets for Array2<f32> {
+    fn to_target_matrix(self) -> DenseMatrix<f32> {
+        self.to_dense_matrix()
+    }
+}
+
 #[cfg(any(feature = "nd"))]
 impl IntoLabels for Array1<f32> {
     fn into_vec(self) -> Vec<f32> {
@@ -177,12 +232,39 @@ pub struct SupervisedModel {
     x_val: DenseMatrix<f32>,
     /// The validation labels.
     y_val: Vec<f32>,
+    /// The holdout data, never used during model selection.
+    x_holdout: DenseMatrix<f32>,
+    /// The holdout labels.
+    y_holdout: Vec<f32>,
+    /// The unlabeled data, for self-training.
+    x_unlabeled: DenseMatrix<f32>,
+    /// The pseudo-labeled data from self-training, only ever trained on.
+    x_pseudo_labeled: DenseMatrix<f32>,
+    /// The pseudo-labels.
+    y_pseudo_labeled: Vec<f32>,
+    /// The score of the final model on the holdout data.
+    holdout_score: Option<f32>,
+    /// The results of each outer fold of nested cross-validation.
+    nested_cv: Vec<NestedFold>,
+    /// The results of each round of self-training.
+    self_training: Vec<SelfTrainingRound>,
     /// The number of classes in the data.
     number_of_classes: usize,
     /// The results of the model comparison.
     comparison: Vec<Model>,
     /// The final model.
     metamodel: Model,
+    /// The weight of each model in the comparison, for voting and ensemble selection.
+    ensemble_weights: Vec<f32>,
+    /// The nonconformity score of each calibration row, for split conformal prediction.
+    conformal_scores: Vec<f32>,
+    /// The models trained without each fold and the scores of the rows they left out, for CV+
+    /// and jackknife+.
+    conformal_folds: Vec<ConformalFold>,
+    /// The classes in the training data, for conformal prediction sets and probabilities.
+    classes: Vec<f32>,
+    /// The tuned decision threshold on the probability of the positive class, if any.
+    decision_threshold: Option<f32>,
     /// PCA model for preprocessing.
     preprocessing_pca: Option<PCA<f32, DenseMatrix<f32>>>,
     /// SVD model for preprocessing.
@@ -247,6 +329,68 @@ impl SupervisedModel {
         Self::build(x, y, settings)
     }
 
+    /// Create a new supervised model with a separate test set. The test set is never used during
+    /// model selection, and the final model is scored on it once training is done.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let model = SupervisedModel::new_with_holdout(
+    ///     (vec![vec![1.0; 5]; 5], vec![1.0; 5]),
+    ///     (vec![vec![1.0; 5]; 2], vec![1.0; 2]),
+    ///     Settings::default_regression(),
+    /// );
+    /// ```
+    pub fn new_with_holdout<D, H>(data: D, holdout: H, settings: Settings) -> Self
+    where
+        D: IntoSupervisedData,
+        H: IntoSupervisedData,
+    {
+        let (x, y) = data.to_supervised_data();
+        let (x_holdout, y_holdout) = holdout.to_supervised_data();
+        let mut model = Self::build(x, y, settings);
+        model.x_holdout = x_holdout;
+        model.y_holdout = y_holdout;
+        model
+    }
+
+    /// Create a new supervised model with additional unlabeled rows, which a classifier learns
+    /// from when self-training is set with `Settings::with_self_training`.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::SelfTraining;
+    /// let model = SupervisedModel::new_with_unlabeled(
+    ///     (vec![vec![1.0; 5]; 5], vec![1.0; 5]),
+    ///     vec![vec![1.0; 5]; 20],
+    ///     Settings::default_classification().with_self_training(
+    ///         SelfTraining::ConfidenceThreshold {
+    ///             threshold: 0.9,
+    ///             max_iterations: 3,
+    ///         },
+    ///     ),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the unlabeled rows have a different number of columns than the labeled rows, this
+    /// function will panic.
+    pub fn new_with_unlabeled<D, U>(data: D, unlabeled: U, settings: Settings) -> Self
+    where
+        D: IntoSupervisedData,
+        U: IntoFeatures,
+    {
+        let (x, y) = data.to_supervised_data();
+        let x_unlabeled = unlabeled.to_dense_matrix();
+        let columns = x.shape().1;
+        assert_eq!(
+            x_unlabeled.shape().1,
+            columns,
+            "The unlabeled rows must have the {columns} columns of the labeled rows."
+        );
+        let mut model = Self::build(x, y, settings);
+        model.x_unlabeled = x_unlabeled;
+        model
+    }
+
     /// Load the supervised model from a file saved previously
     /// ```
     /// # use automl::{SupervisedModel, Settings};
@@ -316,54 +460,538 @@ impl SupervisedModel {
     ///
     /// If the model has not been trained, this function will panic.
     pub fn predict<X: IntoFeatures>(&self, x: X) -> Vec<f32> {
-        let x = &self.preprocess(x.to_dense_matrix());
-        match self.settings.final_model_approach {
-            FinalModel::None => panic!(""),
-            FinalModel::Best => self.predict_by_model(x, &self.comparison[0]),
-            FinalModel::Blending { algorithm, .. } => self.predict_blended_model(x, algorithm),
+        self.predict_preprocessed(&self.preprocess(x.to_dense_matrix()))
+    }
+
+    /// Predict values along with the standard deviation of each prediction, if the final model
+    /// has predictive uncertainty. This is only the case when the best model is chosen as the
+    /// final model and it is a Gaussian process.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i * i) as f32]).collect();
+    /// let y: Vec<f32> = (0..20).map(|i| (i as f32).sin()).collect();
+    /// let mut model = SupervisedModel::new(
+    ///     (x, y),
+    ///     Settings::default_regression()
+    ///         .only(automl::settings::Algorithm::GaussianProcessRegressor)
+    ///         .with_number_of_folds(2),
+    /// );
+    /// model.train();
+    /// let (means, stds) = model.predict_with_std(vec![vec![2.5, 6.25]; 5]).unwrap();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, this function will panic.
+    pub fn predict_with_std<X: IntoFeatures>(&self, x: X) -> Option<(Vec<f32>, Vec<f32>)> {
+        let FinalModel::Best = self.settings.final_model_approach else {
+            return None;
+        };
+        let model = self.best_model();
+        let predictor = model.name.get_std_predictor()?;
+        Some(predictor(
+            &self.preprocess(x.to_dense_matrix()),
+            &model.model,
+            &self.settings,
+        ))
+    }
+
+    /// Predict conformal intervals for a regression, which contain the true value with the
+    /// coverage set with `Settings::with_coverage`. The intervals are calibrated during training
+    /// with the method set with `Settings::with_conformal`, and may be infinite when there is too
+    /// little calibration data for the target coverage.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Conformal;
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    ///         .with_conformal(Conformal::Split { calibration_fraction: 0.2 })
+    ///         .with_coverage(0.9)
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// for (lower, upper) in model.predict_interval(vec![vec![0.01; 10]; 5]) {
+    ///     println!("[{lower}, {upper}]");
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model is a classifier, or was not trained with conformal calibration, this function
+    /// will panic.
+    pub fn predict_interval<X: IntoFeatures>(&self, x: X) -> Vec<(f32, f32)> {
+        assert!(
+            !self.settings.is_classification(),
+            "Conformal intervals are for regression, use predict_set for classification."
+        );
+        assert!(
+            !matches!(self.settings.conformal, Conformal::None),
+            "The model was not trained with conformal calibration."
+        );
+        let x = x.to_dense_matrix();
+        let alpha = 1.0 - self.settings.coverage;
+        if let Conformal::Split { .. } = self.settings.conformal {
+            let radius = self.conformal_quantile(alpha);
+            return self
+                .predict_preprocessed(&self.preprocess(x))
+                .iter()
+                .map(|prediction| (prediction - radius, prediction + radius))
+                .collect();
+        }
+
+        // CV+ takes quantiles of the out-of-fold residuals around each fold model's prediction
+        let fold_predictions: Vec<Vec<f32>> = self
+            .conformal_folds
+            .iter()
+            .map(|fold| fold.predict(&x, &self.settings))
+            .collect();
+        let n = self
+            .conformal_folds
+            .iter()
+            .map(|fold| fold.scores.len())
+            .sum();
+        let (lower_rank, upper_rank) = Self::conformal_ranks(alpha, n);
+        (0..x.shape().0)
+            .map(|row| {
+                let (mut lows, mut highs): (Vec<f32>, Vec<f32>) = self
+                    .conformal_folds
+                    .iter()
+                    .zip(&fold_predictions)
+                    .flat_map(|(fold, predictions)| {
+                        let prediction = predictions[row];
+                        fold.scores
+                            .iter()
+                            .map(move |score| (prediction - score, prediction + score))
+                    })
+                    .unzip();
+                lows.sort_by(f32::total_cmp);
+                highs.sort_by(f32::total_cmp);
+                (
+                    lower_rank.map_or(f32::NEG_INFINITY, |rank| lows[rank - 1]),
+                    upper_rank.map_or(f32::INFINITY, |rank| highs[rank - 1]),
+                )
+            })
+            .collect()
+    }
+
+    /// Predict conformal sets of classes for a classification, which contain the true class with
+    /// the coverage set with `Settings::with_coverage`. The sets are calibrated during training
+    /// with the method set with `Settings::with_conformal`, taking one minus the calibrated
+    /// probability of a class as its nonconformity, so only algorithms with scores are compared.
+    /// A set holds the likely classes when the model is confident enough for the target coverage,
+    /// and grows towards every class otherwise.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Conformal;
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::breast_cancer::load_dataset(),
+    ///     Settings::default_classification()
+    ///         .with_conformal(Conformal::Split { calibration_fraction: 0.2 })
+    ///         .with_coverage(0.9)
+    /// # .only(automl::settings::Algorithm::LogisticRegression)
+    /// );
+    /// model.train();
+    /// for classes in model.predict_set(vec![vec![0.01; 30]; 5]) {
+    ///     println!("{classes:?}");
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model is a regressor, or was not trained with conformal calibration, this function
+    /// will panic.
+    pub fn predict_set<X: IntoFeatures>(&self, x: X) -> Vec<Vec<f32>> {
+        assert!(
+            self.settings.is_classification(),
+            "Conformal sets are for classification, use predict_interval for regression."
+        );
+        assert!(
+            !matches!(self.settings.conformal, Conformal::None),
+            "The model was not trained with conformal calibration."
+        );
+        let x = x.to_dense_matrix();
+        let alpha = 1.0 - self.settings.coverage;
+        if let Conformal::Split { .. } = self.settings.conformal {
+            let threshold = self.conformal_quantile(alpha);
+            return self
+                .predict_proba_preprocessed(&self.preprocess(x))
+                .iter()
+                .map(|probabilities| {
+                    self.classes
+                        .iter()
+                        .zip(probabilities)
+                        .filter(|(_, &probability)| 1.0 - probability <= threshold)
+                        .map(|(&class, _)| class)
+                        .collect()
+                })
+                .collect();
         }
+
+        // CV+ keeps a class unless too many of the calibration rows conform better than it does
+        let fold_probabilities: Vec<Vec<Vec<f32>>> = self
+            .conformal_folds
+            .iter()
+            .map(|fold| fold.predict_proba(&x, &self.settings))
+            .collect();
+        let n: usize = self
+            .conformal_folds
+            .iter()
+            .map(|fold| fold.scores.len())
+            .sum();
+        #[allow(clippy::cast_precision_loss)]
+        let limit = (1.0 - alpha) * (n + 1) as f32;
+        (0..x.shape().0)
+            .map(|row| {
+                self.classes
+                    .iter()
+                    .copied()
+                    .filter(|&class| {
+                        let better: usize = self
+                            .conformal_folds
+                            .iter()
+                            .zip(&fold_probabilities)
+                            .map(|(fold, probabilities)| {
+                                let nonconformity =
+                                    1.0 - fold.class_probability(&probabilities[row], class);
+                                fold.scores
+                                    .iter()
+                                    .filter(|&&score| nonconformity > score)
+                                    .count()
+                            })
+                            .sum();
+                        #[allow(clippy::cast_precision_loss)]
+                        let keep = (better as f32) < limit;
+                        keep
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Predict the calibrated probability of each class, with one column per class in ascending
+    /// order of the class labels. The probabilities come from the best model, or are averaged
+    /// over the members of a voting or selected ensemble with their weights.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::Calibration;
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::breast_cancer::load_dataset(),
+    ///     Settings::default_classification()
+    ///         .with_calibration(Calibration::Sigmoid { folds: 3 })
+    /// # .only(automl::settings::Algorithm::LogisticRegression)
+    /// );
+    /// model.train();
+    /// for probabilities in model.predict_proba(vec![vec![0.01; 30]; 5]) {
+    ///     println!("{probabilities:?}");
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model is a regressor, was not trained with probability calibration, blends its
+    /// final model, or draws probabilities from a model that only predicts labels (see
+    /// `Calibration`), this function will panic.
+    pub fn predict_proba<X: IntoFeatures>(&self, x: X) -> Vec<Vec<f32>> {
+        assert!(
+            self.settings.is_classification(),
+            "Probabilities are for classification."
+        );
+        assert!(
+            !matches!(self.settings.probability_calibration(), Calibration::None),
+            "The model was not trained with probability calibration."
+        );
+        self.predict_proba_preprocessed(&self.preprocess(x.to_dense_matrix()))
+    }
+
+    /// Get the decision threshold on the probability of the positive class tuned during training,
+    /// if a threshold objective was set.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::ThresholdObjective;
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::breast_cancer::load_dataset(),
+    ///     Settings::default_classification()
+    ///         .with_threshold_objective(ThresholdObjective::F1)
+    /// # .only(automl::settings::Algorithm::LogisticRegression)
+    /// );
+    /// model.train();
+    /// assert!(model.decision_threshold().is_some());
+    /// ```
+    #[must_use]
+    pub const fn decision_threshold(&self) -> Option<f32> {
+        self.decision_threshold
     }
 
-    /// Runs a model comparison and trains a final model.
+    /// Get the score of the final model on the holdout data, if a holdout set was used.
     /// ```
     /// # use automl::{SupervisedModel, Settings};
     /// let mut model = SupervisedModel::new(
     ///     smartcore::dataset::diabetes::load_dataset(),
     ///     Settings::default_regression()
+    ///         .with_holdout_fraction(0.2)
     /// # .only(automl::settings::Algorithm::Linear)
     /// );
     /// model.train();
+    /// assert!(model.holdout_score().is_some());
     /// ```
+    #[must_use]
+    pub const fn holdout_score(&self) -> Option<f32> {
+        self.holdout_score
+    }
+
+    /// Get the winning algorithm and its score on each outer fold of nested cross-validation.
+    /// This is empty unless nested cross-validation was enabled in the settings.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    ///         .with_nested_cv(3)
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// for fold in model.nested_cv_results() {
+    ///     println!("{}: {}", fold.winner, fold.score);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn nested_cv_results(&self) -> &[NestedFold] {
+        &self.nested_cv
+    }
+
+    /// Get the winning algorithm and its scores in each round of self-training, the last of which
+    /// trained the final model. This is empty unless self-training was enabled in the settings and
+    /// unlabeled rows were given.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// use automl::settings::SelfTraining;
+    /// # let dataset = smartcore::dataset::breast_cancer::load_dataset();
+    /// # let rows: Vec<Vec<f32>> = dataset.data.chunks(30).map(<[f32]>::to_vec).collect();
+    /// # let (x, y, unlabeled) = (rows[..200].to_vec(), dataset.target[..200].to_vec(), rows[200..].to_vec());
+    /// let mut model = SupervisedModel::new_with_unlabeled(
+    ///     (x, y),
+    ///     unlabeled,
+    ///     Settings::default_classification().with_self_training(
+    ///         SelfTraining::ConfidenceThreshold {
+    ///             threshold: 0.95,
+    ///             max_iterations: 2,
+    ///         },
+    ///     )
+    /// # .only(automl::settings::Algorithm::LogisticRegression)
+    /// # .with_number_of_folds(3)
+    /// );
+    /// model.train();
+    /// for round in model.self_training_results() {
+    ///     println!("{} pseudo-labels: {}", round.pseudo_labeled, round.score);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn self_training_results(&self) -> &[SelfTrainingRound] {
+        &self.self_training
+    }
+
+    /// Get the per-fold cross-validation scores of every model, ranked from best to worst, with
+    /// confidence intervals and a significance test against the best model.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// for entry in model.leaderboard() {
+    ///     println!("{:?}: {:?}", entry.mean_test_score, entry.test_scores);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn leaderboard(&self) -> Vec<LeaderboardEntry> {
+        let test_train_ratio = self
+            .settings
+            .folds
+            .as_ref()
+            .map_or(0.0, Folds::test_train_ratio);
+        self.comparison
+            .iter()
+            .enumerate()
+            .map(|(i, model)| {
+                let mut entry = LeaderboardEntry::new(
+                    model.name.clone(),
+                    model.score.train_score.clone(),
+                    model.score.test_score.clone(),
+                    (i > 0).then(|| self.comparison[0].score.test_score.as_slice()),
+                    test_train_ratio,
+                );
+                if let Some(calibrated) = &model.calibrated {
+                    entry.brier_score = Some(calibrated.brier_score);
+                    entry.reliability.clone_from(&calibrated.reliability);
+                }
+                entry
+            })
+            .collect()
+    }
+
+    /// Runs a model comparison and trains a final model. Algorithms that fail to fit the data are
+    /// left out of the comparison.
+    /// ```
+    /// # use automl::{SupervisedModel, Settings};
+    /// let mut model = SupervisedModel::new(
+    ///     smartcore::dataset::diabetes::load_dataset(),
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If a decision threshold objective is set for anything but a binary classification with the
+    /// best model or an ensemble as the final model, self-training or soft voting is set for
+    /// regression, conformal sets are calibrated around a blended classifier, any of these is set
+    /// with no algorithm that has scores, or custom class weights do not have one positive weight
+    /// per class, this function will panic.
     pub fn train(&mut self) {
-        // Train any necessary preprocessing
-        if let PreProcessing::ReplaceWithPCA {
-            number_of_components,
-        } = self.settings.preprocessing
+        assert!(
+            self.settings.is_classification()
+                || matches!(self.settings.self_training, SelfTraining::None),
+            "Self-training is for classification."
+        );
+        assert!(
+            self.settings.is_classification() || !self.settings.is_soft_voting(),
+            "Soft voting is for classification."
+        );
+        if self.settings.is_classification() {
+            self.settings.check_class_weights(self.number_of_classes);
+        }
+        if !matches!(self.settings.threshold_objective, ThresholdObjective::None) {
+            assert!(
+                self.settings.is_classification() && self.number_of_classes == 2,
+                "Decision thresholds can only be tuned for binary classification."
+            );
+            assert!(
+                !matches!(
+                    self.settings.final_model_approach,
+                    FinalModel::Blending { .. }
+                ),
+                "Decision thresholds cannot be tuned for a blended final model."
+            );
+        }
+
+        if self.settings.is_classification() && !matches!(self.settings.conformal, Conformal::None)
         {
-            self.train_pca(&self.x_train.clone(), number_of_components);
+            assert!(
+                !matches!(
+                    self.settings.final_model_approach,
+                    FinalModel::Blending { .. }
+                ),
+                "Conformal sets cannot be built around a blended final model."
+            );
         }
-        if let PreProcessing::ReplaceWithSVD {
-            number_of_components,
-        } = self.settings.preprocessing
+
+        // Split out the holdout data before anything else sees it
+        if let Some(holdout_fraction) = self.settings.holdout_fraction {
+            if self.y_holdout.is_empty() {
+                let (x_train, x_holdout, y_train, y_holdout) =
+                    self.settings
+                        .train_test_split(&self.x_train, &self.y_train, holdout_fraction);
+                self.x_train = x_train;
+                self.y_train = y_train;
+                self.x_holdout = x_holdout;
+                self.y_holdout = y_holdout;
+            }
+        }
+
+        // Repeat the whole selection procedure in each outer fold for nested cross-validation
+        if let Some(outer_folds) = self.settings.get_outer_folds(&self.x_train, &self.y_train) {
+            let mut inner_settings = self.settings.clone();
+            inner_settings.outer_folds = None;
+            inner_settings.holdout_fraction = None;
+            inner_settings.conformal = Conformal::None;
+            if matches!(inner_settings.threshold_objective, ThresholdObjective::None) {
+                inner_settings.calibration = Calibration::None;
+            }
+            self.nested_cv = outer_folds
+                .split(&self.x_train)
+                .map(|(train, test)| {
+                    let mut inner = Self::build(
+                        self.x_train.take(&train, 0),
+                        train.iter().map(|&idx| self.y_train[idx]).collect(),
+                        inner_settings.clone(),
+                    );
+                    inner.x_holdout = self.x_train.take(&test, 0);
+                    inner.y_holdout = test.iter().map(|&idx| self.y_train[idx]).collect();
+                    inner.train();
+                    NestedFold {
+                        winner: inner.best_model().name.clone(),
+                        score: inner.holdout_score.unwrap_or(f32::NAN),
+                    }
+                })
+                .collect();
+        }
+
+        // Add confident predictions on the unlabeled data to the training data
+        let mut pseudo_labeled = None;
+        if let SelfTraining::ConfidenceThreshold {
+            threshold,
+            max_iterations,
+        } = self.settings.self_training
         {
-            self.train_svd(&self.x_train.clone(), number_of_components);
+            if self.x_unlabeled.shape().0 > 0 {
+                pseudo_labeled = Some(self.pseudo_label(threshold, max_iterations));
+            }
         }
 
-        // Preprocess the data
-        self.x_train = self.preprocess(self.x_train.clone());
+        // Calibrate conformal prediction on models trained without each fold, or set aside the
+        // calibration data for split conformal prediction
+        if self.settings.is_classification() {
+            self.classes = self.y_train.clone();
+            self.classes.sort_by(f32::total_cmp);
+            self.classes.dedup();
+        }
+        let mut calibration = None;
+        match self.settings.conformal {
+            Conformal::None => {}
+            Conformal::Split {
+                calibration_fraction,
+            } => {
+                let (x_train, x_calibration, y_train, y_calibration) = self
+                    .settings
+                    .train_test_split(&self.x_train, &self.y_train, calibration_fraction);
+                self.x_train = x_train;
+                self.y_train = y_train;
+                calibration = Some((x_calibration, y_calibration));
+            }
+            Conformal::CVPlus { folds } => {
+                let folds = self
+                    .settings
+                    .get_conformal_folds(&self.x_train, &self.y_train, folds);
+                self.train_conformal_models(&folds);
+            }
+            Conformal::JackknifePlus => {
+                // Leave each row out in turn, whatever the splitter
+                let folds = Splitter::KFold.split(
+                    &self.x_train,
+                    &self.y_train,
+                    self.y_train.len(),
+                    false,
+                    &mut self.settings.rng("jackknife", &self.y_train),
+                );
+                self.train_conformal_models(&folds);
+            }
+        }
 
-        // Split validatino out if blending
+        // Split validation out if blending
         if let FinalModel::Blending {
             meta_training_fraction,
             meta_testing_fraction: _,
             algorithm: _,
         } = &self.settings.final_model_approach
         {
-            let (x_train, x_val, y_train, y_val) = train_test_split(
+            let (x_train, x_val, y_train, y_val) = self.settings.train_test_split(
                 &self.x_train,
                 &self.y_train,
                 *meta_training_fraction,
-                self.settings.shuffle,
             );
             self.x_train = x_train;
             self.y_train = y_train;
@@ -371,172 +999,180 @@ impl SupervisedModel {
             self.x_val = x_val;
         }
 
-        // Run logistic regression
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::LogisticRegression)
-        {
-            self.record_model(LogisticRegressionWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
-
-        // Run random forest classification
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::RandomForestClassifier)
-        {
-            self.record_model(RandomForestClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
-
-        // Run k-nearest neighbor classifier
-        if !self.settings.skiplist.contains(&Algorithm::KNNClassifier) {
-            self.record_model(KNNClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
-
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::DecisionTreeClassifier)
-        {
-            self.record_model(DecisionTreeClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
-        }
+        // Fix the cross-validation splits once, so every algorithm is compared on the same folds
+        // even without a seed, while the data still has the group column that preprocessing drops
+        self.settings.folds = None;
+        let folds = self.settings.get_kfolds(&self.x_train, &self.y_train);
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::GaussianNaiveBayes)
+        // Train any necessary preprocessing
+        if let PreProcessing::ReplaceWithPCA {
+            number_of_components,
+        } = self.settings.preprocessing
         {
-            self.record_model(GaussianNaiveBayesClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+            self.train_pca(
+                &self.settings.without_group_column(self.x_train.clone()),
+                number_of_components,
+            );
         }
-
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::CategoricalNaiveBayes)
-            && std::mem::discriminant(&self.settings.preprocessing)
-                != std::mem::discriminant(&PreProcessing::ReplaceWithPCA {
-                    number_of_components: 1,
-                })
-            && std::mem::discriminant(&self.settings.preprocessing)
-                != std::mem::discriminant(&PreProcessing::ReplaceWithSVD {
-                    number_of_components: 1,
-                })
+        if let PreProcessing::ReplaceWithSVD {
+            number_of_components,
+        } = self.settings.preprocessing
         {
-            self.record_model(CategoricalNaiveBayesClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+            self.train_svd(
+                &self.settings.without_group_column(self.x_train.clone()),
+                number_of_components,
+            );
         }
 
-        if self.number_of_classes == 2 && !self.settings.skiplist.contains(&Algorithm::SVC) {
-            self.record_model(SupportVectorClassifierWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Preprocess the data
+        self.x_train = self.preprocess(self.x_train.clone());
+        if matches!(
+            self.settings.final_model_approach,
+            FinalModel::Blending { .. }
+        ) {
+            self.x_val = self.preprocess(self.x_val.clone());
         }
 
-        if !self.settings.skiplist.contains(&Algorithm::Linear) {
-            self.record_model(LinearRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Add the pseudo-labeled data to the training data of every fold, but never test on it
+        let labeled = self.y_train.len();
+        if !self.y_pseudo_labeled.is_empty() {
+            self.x_train = self
+                .x_train
+                .v_stack(&self.preprocess(self.x_pseudo_labeled.clone()));
+            self.y_train.extend_from_slice(&self.y_pseudo_labeled);
         }
-
-        if !self.settings.skiplist.contains(&Algorithm::SVR) {
-            self.record_model(SupportVectorRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        self.settings.folds = Some(folds.with_train_only(labeled..self.y_train.len()));
+
+        // Cross-validate every candidate that survives early elimination
+        let candidates = self.candidate_algorithms();
+        assert!(
+            !candidates.is_empty() || !self.settings.needs_probabilities(),
+            "Probabilities need an algorithm with scores, but every one is skipped or only \
+             predicts labels."
+        );
+        for algorithm in self.halving_survivors(candidates) {
+            // Algorithms that fail to fit are left out of the comparison
+            if let Ok(model) =
+                algorithm.get_cross_validator()(&self.x_train, &self.y_train, &self.settings)
+            {
+                self.record_model(model);
+            }
         }
 
-        if !self.settings.skiplist.contains(&Algorithm::Lasso) {
-            self.record_model(RidgeRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Calibrate the class probabilities of every model in the comparison with scores to
+        // calibrate, keeping the out-of-fold probabilities in the order of the comparison
+        let mut out_of_fold_probabilities = vec![];
+        if self.settings.is_classification()
+            && !matches!(self.settings.probability_calibration(), Calibration::None)
+        {
+            for model in &mut self.comparison {
+                if !model.name.has_scores() {
+                    out_of_fold_probabilities.push(None);
+                    continue;
+                }
+                let (calibrated, probabilities) = CalibratedClassifier::fit(
+                    model.name.clone(),
+                    &self.x_train,
+                    &self.y_train,
+                    labeled,
+                    &self.classes,
+                    &self.settings,
+                );
+                model.calibrated = Some(calibrated);
+                out_of_fold_probabilities.push(Some(probabilities));
+            }
         }
 
-        if !self.settings.skiplist.contains(&Algorithm::Ridge) {
-            self.record_model(LassoRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        if let FinalModel::Blending {
+            algorithm,
+            meta_training_fraction,
+            meta_testing_fraction,
+        } = self.settings.final_model_approach.clone()
+        {
+            self.train_blended_model(algorithm, meta_training_fraction, meta_testing_fraction);
         }
 
-        if !self.settings.skiplist.contains(&Algorithm::ElasticNet) {
-            self.record_model(ElasticNetRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Weight the models for voting or ensemble selection
+        match &self.settings.final_model_approach {
+            FinalModel::Voting { top_k, weights, .. } => {
+                // Rank the models without the baselines, which only serve as a reference
+                let mut rank = 0;
+                self.ensemble_weights = self
+                    .comparison
+                    .iter()
+                    .map(|model| {
+                        if model.name.is_baseline() {
+                            return 0.0;
+                        }
+                        rank += 1;
+                        match (rank <= *top_k, weights) {
+                            (false, _) => 0.0,
+                            (true, Some(weights)) => weights.get(rank - 1).copied().unwrap_or(1.0),
+                            (true, None) => 1.0,
+                        }
+                    })
+                    .collect();
+            }
+            FinalModel::EnsembleSelection { iterations } => {
+                self.train_ensemble_selection(*iterations);
+            }
+            _ => {}
         }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::DecisionTreeRegressor)
+        // Tune the decision threshold on the out-of-fold probabilities of the final model
+        if !matches!(self.settings.threshold_objective, ThresholdObjective::None)
+            && !matches!(self.settings.final_model_approach, FinalModel::None)
         {
-            self.record_model(DecisionTreeRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
+            let weights = self.probability_weights();
+            let total: f32 = weights.iter().sum();
+            let probabilities: Vec<f32> = (0..labeled)
+                .map(|row| {
+                    out_of_fold_probabilities
+                        .iter()
+                        .zip(&weights)
+                        .filter(|(_, &weight)| weight > 0.0)
+                        .map(|(probabilities, weight)| {
+                            let probabilities = probabilities
+                                .as_ref()
+                                .expect("Only models with scores have probabilities.");
+                            weight * probabilities[row][1] / total
+                        })
+                        .sum()
+                })
+                .collect();
+            self.decision_threshold = Some(tune_threshold(
+                self.settings.threshold_objective,
+                &self.y_train[..labeled],
+                self.classes[1],
+                &probabilities,
             ));
         }
 
-        if !self
-            .settings
-            .skiplist
-            .contains(&Algorithm::RandomForestRegressor)
+        // Score the final model on the holdout data
+        if !self.y_holdout.is_empty()
+            && !matches!(self.settings.final_model_approach, FinalModel::None)
         {
-            self.record_model(RandomForestRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
+            let x_holdout = self.preprocess(self.x_holdout.clone());
+            self.holdout_score = Some(self.settings.get_metric()(
+                &self.y_holdout,
+                &self.predict_preprocessed(&x_holdout),
             ));
         }
 
-        if !self.settings.skiplist.contains(&Algorithm::KNNRegressor) {
-            self.record_model(KNNRegressorWrapper::cv_model(
-                &self.x_train,
-                &self.y_train,
-                &self.settings,
-            ));
+        // Record the final round of self-training
+        if let Some(pseudo_labeled) = pseudo_labeled {
+            self.self_training.push(SelfTrainingRound {
+                pseudo_labeled,
+                winner: self.best_model().name.clone(),
+                score: self.best_model().score.mean_test_score(),
+                holdout_score: self.holdout_score,
+            });
         }
 
-        if let FinalModel::Blending {
-            algorithm,
-            meta_training_fraction,
-            meta_testing_fraction,
-        } = self.settings.final_model_approach
-        {
-            self.train_blended_model(algorithm, meta_training_fraction, meta_testing_fraction);
+        // Score the final model on the calibration data for split conformal prediction
+        if let Some((x_calibration, y_calibration)) = calibration {
+            self.conformal_scores =
+                self.nonconformity(&self.preprocess(x_calibration), &y_calibration);
         }
     }
 
@@ -574,7 +1210,7 @@ impl SupervisedModel {
     pub fn save_best(&self, file_name: &str) {
         if matches!(self.settings.final_model_approach, FinalModel::Best) {
             std::fs::File::create(file_name)
-                .and_then(|mut f| f.write_all(&self.comparison[0].model))
+                .and_then(|mut f| f.write_all(&self.best_model().model))
                 .expect("Cannot write model to file.");
         }
     }
@@ -597,13 +1233,259 @@ impl SupervisedModel {
             y_train: y,
             x_val: DenseMatrix::new(0, 0, vec![]),
             y_val: vec![],
+            x_holdout: DenseMatrix::new(0, 0, vec![]),
+            y_holdout: vec![],
+            x_unlabeled: DenseMatrix::new(0, 0, vec![]),
+            x_pseudo_labeled: DenseMatrix::new(0, 0, vec![]),
+            y_pseudo_labeled: vec![],
+            holdout_score: None,
+            nested_cv: vec![],
+            self_training: vec![],
             comparison: vec![],
             metamodel: Model::default(),
+            ensemble_weights: vec![],
+            conformal_scores: vec![],
+            conformal_folds: vec![],
+            classes: vec![],
+            decision_threshold: None,
             preprocessing_pca: None,
             preprocessing_svd: None,
         }
     }
 
+    /// Repeat the whole training procedure on the training data, adding the unlabeled rows whose
+    /// most likely class is confident enough to the pseudo-labeled data with that class after
+    /// each round, and return the number of rows added.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The smallest calibrated probability of a pseudo-label
+    /// * `max_iterations` - The largest number of rounds
+    fn pseudo_label(&mut self, threshold: f32, max_iterations: usize) -> usize {
+        let mut inner_settings = self.settings.clone();
+        inner_settings.outer_folds = None;
+        inner_settings.holdout_fraction = None;
+        inner_settings.conformal = Conformal::None;
+        inner_settings.calibration = self.settings.probability_calibration();
+        inner_settings.threshold_objective = ThresholdObjective::None;
+        if matches!(
+            inner_settings.final_model_approach,
+            FinalModel::None | FinalModel::Blending { .. }
+        ) {
+            inner_settings.final_model_approach = FinalModel::Best;
+        }
+
+        let mut unlabeled = self.x_unlabeled.clone();
+        let mut pseudo_labeled = 0;
+        for _ in 0..max_iterations {
+            if unlabeled.shape().0 == 0 {
+                break;
+            }
+            let mut inner = Self::build(
+                self.x_train.clone(),
+                self.y_train.clone(),
+                inner_settings.clone(),
+            );
+            inner.x_holdout = self.x_holdout.clone();
+            inner.y_holdout.clone_from(&self.y_holdout);
+            inner.x_pseudo_labeled = self.x_pseudo_labeled.clone();
+            inner.y_pseudo_labeled.clone_from(&self.y_pseudo_labeled);
+            inner.train();
+            self.self_training.push(SelfTrainingRound {
+                pseudo_labeled,
+                winner: inner.best_model().name.clone(),
+                score: inner.best_model().score.mean_test_score(),
+                holdout_score: inner.holdout_score,
+            });
+
+            let (confident, rest): (Vec<_>, Vec<_>) = inner
+                .predict_proba_preprocessed(&inner.preprocess(unlabeled.clone()))
+                .into_iter()
+                .enumerate()
+                .map(|(row, probabilities)| {
+                    let (class, probability) = probabilities
+                        .into_iter()
+                        .enumerate()
+                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                        .unwrap_or((0, 0.0));
+                    (row, inner.classes[class], probability)
+                })
+                .partition(|&(_, _, probability)| probability >= threshold);
+            if confident.is_empty() {
+                break;
+            }
+            let rows: Vec<usize> = confident.iter().map(|&(row, _, _)| row).collect();
+            self.x_pseudo_labeled = if self.y_pseudo_labeled.is_empty() {
+                unlabeled.take(&rows, 0)
+            } else {
+                self.x_pseudo_labeled.v_stack(&unlabeled.take(&rows, 0))
+            };
+            self.y_pseudo_labeled
+                .extend(confident.iter().map(|&(_, class, _)| class));
+            pseudo_labeled += rows.len();
+            let rows: Vec<usize> = rest.iter().map(|&(row, _, _)| row).collect();
+            unlabeled = if rows.is_empty() {
+                DenseMatrix::new(0, 0, vec![])
+            } else {
+                unlabeled.take(&rows, 0)
+            };
+        }
+        pseudo_labeled
+    }
+
+    /// Repeat the whole training procedure without each fold of the training data, keeping the
+    /// best model of each and the nonconformity score of every row under the model that left it
+    /// out, for CV+ and jackknife+.
+    ///
+    /// # Arguments
+    ///
+    /// * `folds` - The splits of the training data
+    fn train_conformal_models(&mut self, folds: &Folds) {
+        let mut inner_settings = self.settings.clone();
+        inner_settings.conformal = Conformal::None;
+        if self.settings.is_classification() {
+            // Nonconformity comes from calibrated probabilities, which label-only algorithms lack
+            inner_settings.calibration = self.settings.probability_calibration();
+            inner_settings.skiplist.extend(
+                Algorithm::ALL
+                    .into_iter()
+                    .filter(|algorithm| !algorithm.has_scores()),
+            );
+        } else {
+            inner_settings.calibration = Calibration::None;
+        }
+        inner_settings.outer_folds = None;
+        inner_settings.holdout_fraction = None;
+        inner_settings.final_model_approach = FinalModel::Best;
+        inner_settings.threshold_objective = ThresholdObjective::None;
+        self.conformal_folds = vec![];
+        for (train, test) in folds.split(&self.x_train) {
+            let mut inner = Self::build(
+                self.x_train.take(&train, 0),
+                train.iter().map(|&idx| self.y_train[idx]).collect(),
+                inner_settings.clone(),
+            );
+            inner.x_pseudo_labeled = self.x_pseudo_labeled.clone();
+            inner.y_pseudo_labeled.clone_from(&self.y_pseudo_labeled);
+            inner.train();
+            let y_test: Vec<f32> = test.iter().map(|&idx| self.y_train[idx]).collect();
+            let scores =
+                inner.nonconformity(&inner.preprocess(self.x_train.take(&test, 0)), &y_test);
+            self.conformal_folds.push(ConformalFold {
+                model: inner.comparison.remove(inner.best_index()),
+                preprocessing_pca: inner.preprocessing_pca,
+                preprocessing_svd: inner.preprocessing_svd,
+                scores,
+            });
+        }
+    }
+
+    /// How poorly each value conforms to the final model on preprocessed data: the absolute error
+    /// for regression, or one minus the calibrated probability of the class for classification.
+    fn nonconformity(&self, x: &DenseMatrix<f32>, y: &[f32]) -> Vec<f32> {
+        if self.settings.is_classification() {
+            self.predict_proba_preprocessed(x)
+                .iter()
+                .zip(y)
+                .map(|(probabilities, &class)| 1.0 - self.class_probability(probabilities, class))
+                .collect()
+        } else {
+            self.predict_preprocessed(x)
+                .iter()
+                .zip(y)
+                .map(|(prediction, value)| (value - prediction).abs())
+                .collect()
+        }
+    }
+
+    /// The probability of a class among the probabilities of every class, which is zero for a
+    /// class the model never saw.
+    fn class_probability(&self, probabilities: &[f32], class: f32) -> f32 {
+        class_probability(&self.classes, probabilities, class)
+    }
+
+    /// The ranks (counting from one) of the lower and upper conformal quantiles among `n` sorted
+    /// calibration values, or `None` where there are too few values and the bound is infinite.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn conformal_ranks(alpha: f32, n: usize) -> (Option<usize>, Option<usize>) {
+        let lower = (alpha * (n + 1) as f32).floor() as usize;
+        let upper = ((1.0 - alpha) * (n + 1) as f32).ceil() as usize;
+        (
+            (lower >= 1).then_some(lower.min(n)),
+            (upper <= n).then_some(upper.max(1)),
+        )
+    }
+
+    /// The conformal quantile of the calibration scores for split conformal prediction, which may
+    /// be infinite when there are too few scores.
+    fn conformal_quantile(&self, alpha: f32) -> f32 {
+        let mut scores = self.conformal_scores.clone();
+        scores.sort_by(f32::total_cmp);
+        Self::conformal_ranks(alpha, scores.len())
+            .1
+            .map_or(f32::INFINITY, |rank| scores[rank - 1])
+    }
+
+    /// The position in the comparison of the best model, passing over the baselines unless every
+    /// model is one.
+    fn best_index(&self) -> usize {
+        self.comparison
+            .iter()
+            .position(|model| !model.name.is_baseline())
+            .unwrap_or(0)
+    }
+
+    /// The best model, which is the final model unless an ensemble or blending is used.
+    fn best_model(&self) -> &Model {
+        &self.comparison[self.best_index()]
+    }
+
+    /// The weight of each model in the comparison in the class probabilities of the final model.
+    fn probability_weights(&self) -> Vec<f32> {
+        match self.settings.final_model_approach {
+            FinalModel::Best => {
+                let mut weights = vec![0.0; self.comparison.len()];
+                weights[self.best_index()] = 1.0;
+                weights
+            }
+            FinalModel::Voting { .. } | FinalModel::EnsembleSelection { .. } => {
+                self.ensemble_weights.clone()
+            }
+            _ => panic!("Probabilities need the best model or an ensemble as the final model."),
+        }
+    }
+
+    /// The calibrated probability of each class for preprocessed data.
+    fn predict_proba_preprocessed(&self, x: &DenseMatrix<f32>) -> Vec<Vec<f32>> {
+        let weights = self.probability_weights();
+        let total: f32 = weights.iter().sum();
+        let mut probabilities = vec![vec![0.0; self.classes.len()]; x.shape().0];
+        for (model, weight) in self.comparison.iter().zip(weights) {
+            if weight <= 0.0 {
+                continue;
+            }
+            let calibrated = model.calibrated.as_ref().unwrap_or_else(|| {
+                panic!(
+                    "{} only predicts labels, so it has no probabilities.",
+                    model.name
+                )
+            });
+            for (row, model_row) in probabilities
+                .iter_mut()
+                .zip(calibrated.predict_proba(x, &self.settings))
+            {
+                for (probability, model_probability) in row.iter_mut().zip(model_row) {
+                    *probability += weight * model_probability / total;
+                }
+            }
+        }
+        probabilities
+    }
+
     /// Train the supervised model.
     ///
     /// # Arguments
@@ -617,24 +1499,28 @@ impl SupervisedModel {
         training_fraction: f32,
         testing_fraction: f32,
     ) {
-        // Make the data
+        // Make the data, leaving out baselines since they carry no information about the features
         let mut meta_x: Vec<Vec<f32>> = Vec::new();
-        for model in &self.comparison {
+        for model in self
+            .comparison
+            .iter()
+            .filter(|model| !model.name.is_baseline())
+        {
             meta_x.push(self.predict_by_model(&self.x_val, model));
         }
         let xdm = DenseMatrix::from_2d_vec(&meta_x).transpose();
 
         // Split into datasets
-        let (x_train, x_test, y_train, y_test) = train_test_split(
+        let (x_train, x_test, y_train, y_test) = self.settings.train_test_split(
             &xdm,
             &self.y_val,
             training_fraction / (training_fraction + testing_fraction),
-            self.settings.shuffle,
         );
 
         // Train the model
         // let model = LassoRegressorWrapper::train(&x_train, &y_train, &self.settings);
-        let model = algo.get_trainer()(&x_train, &y_train, &self.settings);
+        let model =
+            algo.get_trainer()(&x_train, &y_train, &self.settings).expect("Error during training.");
 
         // Score the model
         let train_score = self.settings.get_metric()(
@@ -653,12 +1539,125 @@ impl SupervisedModel {
                 test_score: vec![test_score; 1],
                 train_score: vec![train_score; 1],
             },
+            out_of_fold: vec![],
             name: algo,
             duration: Duration::default(),
             model,
+            calibrated: None,
         };
     }
 
+    /// Choose ensemble weights by Caruana-style greedy forward selection with replacement, scoring
+    /// each candidate ensemble on the out-of-fold predictions recorded during cross-validation.
+    /// Baseline models never join the ensemble.
+    ///
+    /// # Arguments
+    ///
+    /// * `iterations` - How many models to add to the ensemble
+    fn train_ensemble_selection(&mut self, iterations: usize) {
+        // Only score the rows that were in a testing fold, since pseudo-labeled rows never are
+        let mut rows: Vec<usize> = self
+            .settings
+            .get_kfolds(&self.x_train, &self.y_train)
+            .split(&self.x_train)
+            .flat_map(|(_, test)| test)
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        let y: Vec<f32> = rows.iter().map(|&idx| self.y_train[idx]).collect();
+        let out_of_fold: Vec<Vec<f32>> = self
+            .comparison
+            .iter()
+            .map(|model| rows.iter().map(|&idx| model.out_of_fold[idx]).collect())
+            .collect();
+
+        // Repeatedly add whichever model most improves the ensemble
+        let higher_is_better =
+            self.settings.sort_by == Metric::RSquared || self.settings.sort_by == Metric::Accuracy;
+        let mut counts = vec![0.0; self.comparison.len()];
+        for _ in 0..iterations {
+            let mut best: Option<(usize, f32)> = None;
+            for candidate in 0..counts.len() {
+                if self.comparison[candidate].name.is_baseline() {
+                    continue;
+                }
+                counts[candidate] += 1.0;
+                let score = self.settings.get_metric()(
+                    &y,
+                    &self.combine_predictions(&out_of_fold, &counts),
+                );
+                counts[candidate] -= 1.0;
+                let better = best.is_none_or(|(_, best_score)| {
+                    if higher_is_better {
+                        score > best_score
+                    } else {
+                        score < best_score
+                    }
+                });
+                if better && !score.is_nan() {
+                    best = Some((candidate, score));
+                }
+            }
+            match best {
+                Some((candidate, _)) => counts[candidate] += 1.0,
+                None => break,
+            }
+        }
+        self.ensemble_weights = counts;
+    }
+
+    /// Combine the predictions of several models, averaging them for regression or taking a
+    /// weighted vote for classification. Models with zero weight are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `predictions` - The predictions of each model
+    /// * `weights` - The weight of each model
+    ///
+    /// # Returns
+    ///
+    /// * The combined predictions
+    fn combine_predictions(&self, predictions: &[Vec<f32>], weights: &[f32]) -> Vec<f32> {
+        let members: Vec<(&Vec<f32>, f32)> = predictions
+            .iter()
+            .zip(weights.iter().copied())
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        let total: f32 = members.iter().map(|(_, weight)| weight).sum();
+        let n = members
+            .first()
+            .map_or(0, |(predictions, _)| predictions.len());
+        (0..n)
+            .map(|row| {
+                if self.settings.is_classification() {
+                    let mut votes: Vec<(f32, f32)> = vec![];
+                    for (predictions, weight) in &members {
+                        match votes
+                            .iter_mut()
+                            .find(|(class, _)| class.total_cmp(&predictions[row]).is_eq())
+                        {
+                            Some((_, votes)) => *votes += weight,
+                            None => votes.push((predictions[row], *weight)),
+                        }
+                    }
+                    // Ties go to the smallest class label
+                    votes.sort_by(|a, b| a.0.total_cmp(&b.0));
+                    votes
+                        .iter()
+                        .rev()
+                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                        .map_or(f32::NAN, |(class, _)| *class)
+                } else {
+                    members
+                        .iter()
+                        .map(|(predictions, weight)| predictions[row] * weight)
+                        .sum::<f32>()
+                        / total
+                }
+            })
+            .collect()
+    }
+
     /// Predict using all of the trained models.
     ///
     /// # Arguments
@@ -669,11 +1668,14 @@ impl SupervisedModel {
     /// # Returns
     ///
     /// * The predicted values
-    fn predict_blended_model(&self, x: &DenseMatrix<f32>, algo: Algorithm) -> Vec<f32> {
+    fn predict_blended_model(&self, x: &DenseMatrix<f32>, algo: &Algorithm) -> Vec<f32> {
         // Make the data
         let mut meta_x: Vec<Vec<f32>> = Vec::new();
-        for i in 0..self.comparison.len() {
-            let model = &self.comparison[i];
+        for model in self
+            .comparison
+            .iter()
+            .filter(|model| !model.name.is_baseline())
+        {
             meta_x.push(self.predict_by_model(x, model));
         }
 
@@ -699,6 +1701,71 @@ impl SupervisedModel {
         model.name.get_predictor()(x, &model.model, &self.settings)
     }
 
+    /// Predict using the final model.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The input data, already preprocessed
+    ///
+    /// # Returns
+    ///
+    /// * The predicted values
+    fn predict_preprocessed(&self, x: &DenseMatrix<f32>) -> Vec<f32> {
+        if let Some(threshold) = self.decision_threshold {
+            return self
+                .predict_proba_preprocessed(x)
+                .iter()
+                .map(|probabilities| {
+                    if probabilities[1] >= threshold {
+                        self.classes[1]
+                    } else {
+                        self.classes[0]
+                    }
+                })
+                .collect();
+        }
+        if self.settings.is_soft_voting() {
+            return self
+                .predict_proba_preprocessed(x)
+                .iter()
+                .map(|probabilities| {
+                    // Ties go to the smallest class label
+                    let (class, _) = probabilities.iter().enumerate().fold(
+                        (0, f32::NEG_INFINITY),
+                        |best, (class, &probability)| {
+                            if probability > best.1 {
+                                (class, probability)
+                            } else {
+                                best
+                            }
+                        },
+                    );
+                    self.classes[class]
+                })
+                .collect();
+        }
+        match &self.settings.final_model_approach {
+            FinalModel::None => panic!(""),
+            FinalModel::Best => self.predict_by_model(x, self.best_model()),
+            FinalModel::Blending { algorithm, .. } => self.predict_blended_model(x, algorithm),
+            FinalModel::Voting { .. } | FinalModel::EnsembleSelection { .. } => {
+                let predictions: Vec<Vec<f32>> = self
+                    .comparison
+                    .iter()
+                    .zip(&self.ensemble_weights)
+                    .map(|(model, &weight)| {
+                        if weight > 0.0 {
+                            self.predict_by_model(x, model)
+                        } else {
+                            vec![]
+                        }
+                    })
+                    .collect();
+                self.combine_predictions(&predictions, &self.ensemble_weights)
+            }
+        }
+    }
+
     /// Get interaction features for the data.
     ///
     /// # Arguments
@@ -761,13 +1828,13 @@ impl SupervisedModel {
     ///
     /// # Arguments
     ///
+    /// * `pca` - The trained PCA preprocessor
     /// * `x` - The input data
-    fn pca_features(&self, x: &DenseMatrix<f32>, _: usize) -> DenseMatrix<f32> {
-        self.preprocessing_pca
-            .as_ref()
-            .unwrap()
-            .transform(x)
-            .unwrap()
+    fn pca_features(
+        pca: Option<&PCA<f32, DenseMatrix<f32>>>,
+        x: &DenseMatrix<f32>,
+    ) -> DenseMatrix<f32> {
+        pca.unwrap().transform(x).unwrap()
     }
 
     /// Train SVD on the data for preprocessing.
@@ -781,13 +1848,12 @@ impl SupervisedModel {
         self.preprocessing_svd = Some(svd);
     }
 
-    /// Get SVD features for the data.
-    fn svd_features(&self, x: &DenseMatrix<f32>, _: usize) -> DenseMatrix<f32> {
-        self.preprocessing_svd
-            .as_ref()
-            .unwrap()
-            .transform(x)
-            .unwrap()
+    /// Get SVD features for the data using the trained SVD preprocessor.
+    fn svd_features(
+        svd: Option<&SVD<f32, DenseMatrix<f32>>>,
+        x: &DenseMatrix<f32>,
+    ) -> DenseMatrix<f32> {
+        svd.unwrap().transform(x).unwrap()
     }
 
     /// Pre process the data.
@@ -800,16 +1866,35 @@ impl SupervisedModel {
     ///
     /// * The preprocessed data
     fn preprocess(&self, x: DenseMatrix<f32>) -> DenseMatrix<f32> {
-        match self.settings.preprocessing {
+        Self::preprocess_with(
+            &self.settings,
+            self.preprocessing_pca.as_ref(),
+            self.preprocessing_svd.as_ref(),
+            x,
+        )
+    }
+
+    /// Pre process the data with the given PCA and SVD models, if the settings use them.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The settings that choose the preprocessing
+    /// * `pca` - The trained PCA preprocessor, if any
+    /// * `svd` - The trained SVD preprocessor, if any
+    /// * `x` - The input data
+    fn preprocess_with(
+        settings: &Settings,
+        pca: Option<&PCA<f32, DenseMatrix<f32>>>,
+        svd: Option<&SVD<f32, DenseMatrix<f32>>>,
+        x: DenseMatrix<f32>,
+    ) -> DenseMatrix<f32> {
+        let x = settings.without_group_column(x);
+        match settings.preprocessing {
             PreProcessing::None => x,
             PreProcessing::AddInteractions => Self::interaction_features(x),
             PreProcessing::AddPolynomial { order } => Self::polynomial_features(x, order),
-            PreProcessing::ReplaceWithPCA {
-                number_of_components,
-            } => self.pca_features(&x, number_of_components),
-            PreProcessing::ReplaceWithSVD {
-                number_of_components,
-            } => self.svd_features(&x, number_of_components),
+            PreProcessing::ReplaceWithPCA { .. } => Self::pca_features(pca, &x),
+            PreProcessing::ReplaceWithSVD { .. } => Self::svd_features(svd, &x),
         }
     }
 
@@ -829,13 +1914,191 @@ impl SupervisedModel {
         sorted_targets.len()
     }
 
+    /// Get the algorithms to compare, leaving out skipped algorithms and those that cannot be
+    /// used with this data.
+    fn candidate_algorithms(&self) -> Vec<Algorithm> {
+        Algorithm::ALL
+            .into_iter()
+            .chain(self.settings.bagging.iter().cloned())
+            .filter(|algorithm| !self.settings.skiplist.contains(algorithm))
+            .filter(|algorithm| self.is_eligible(algorithm))
+            .collect()
+    }
+
+    /// Whether an algorithm can be used with the (preprocessed) training data.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to check
+    ///
+    /// # Returns
+    ///
+    /// * Whether the algorithm can be trained
+    fn is_eligible(&self, algorithm: &Algorithm) -> bool {
+        match algorithm {
+            // Probabilities come from calibrated scores, which label-only algorithms lack
+            _ if self.settings.needs_probabilities() && !algorithm.has_scores() => false,
+            // Replaced features are no longer categories
+            Algorithm::CategoricalNaiveBayes => !matches!(
+                self.settings.preprocessing,
+                PreProcessing::ReplaceWithPCA { .. } | PreProcessing::ReplaceWithSVD { .. }
+            ),
+            // Features must already be binary unless a threshold is given
+            Algorithm::BernoulliNaiveBayes => {
+                self.settings
+                    .bernoulli_nb_settings
+                    .as_ref()
+                    .unwrap()
+                    .binarize
+                    .is_some()
+                    || self.x_train.iter().all(|value| {
+                        value.abs() < f32::EPSILON || (value - 1.0).abs() < f32::EPSILON
+                    })
+            }
+            // Counts cannot be negative
+            Algorithm::MultinomialNaiveBayes => self.x_train.iter().all(|value| value >= 0.0),
+            // The targets must be in the range of the distribution
+            Algorithm::Poisson => supports_tweedie_targets(1.0, &self.y_train),
+            Algorithm::Gamma => supports_tweedie_targets(2.0, &self.y_train),
+            Algorithm::Tweedie => supports_tweedie_targets(
+                self.settings.tweedie_settings.as_ref().unwrap().power,
+                &self.y_train,
+            ),
+            // The cost grows with the cube of the number of rows
+            Algorithm::GaussianProcessRegressor => {
+                self.y_train.len()
+                    <= self
+                        .settings
+                        .gaussian_process_regressor_settings
+                        .as_ref()
+                        .unwrap()
+                        .max_samples
+            }
+            Algorithm::Bagging { base, .. } => {
+                !matches!(**base, Algorithm::Bagging { .. }) && self.is_eligible(base)
+            }
+            _ => true,
+        }
+    }
+
+    /// Eliminate weak candidates early according to the halving schedule in the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` - The algorithms to choose from
+    ///
+    /// # Returns
+    ///
+    /// * The algorithms that deserve a full cross-validation
+    fn halving_survivors(&self, candidates: Vec<Algorithm>) -> Vec<Algorithm> {
+        match self.settings.halving {
+            Halving::None => candidates,
+            Halving::Successive {
+                min_fraction,
+                reduction_factor,
+            } => self.successive_halving(candidates, min_fraction, reduction_factor),
+            Halving::Hyperband {
+                min_fraction,
+                reduction_factor,
+            } => {
+                let mut survivors = vec![];
+                let mut start_fraction = min_fraction;
+                #[allow(clippy::cast_precision_loss, clippy::while_float)]
+                while start_fraction < 1.0 {
+                    survivors.extend(self.successive_halving(
+                        candidates.clone(),
+                        start_fraction,
+                        reduction_factor,
+                    ));
+                    start_fraction *= reduction_factor.max(2) as f32;
+                }
+                candidates
+                    .into_iter()
+                    .filter(|algorithm| survivors.contains(algorithm))
+                    .collect()
+            }
+        }
+    }
+
+    /// Run one round of successive halving, scoring candidates on growing subsamples of the
+    /// training data and keeping the best `1 / reduction_factor` of them each time.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` - The algorithms to choose from
+    /// * `start_fraction` - The fraction of the data used in the first round
+    /// * `reduction_factor` - How many times more data each round gets
+    ///
+    /// # Returns
+    ///
+    /// * The algorithms that survived every round
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn successive_halving(
+        &self,
+        mut candidates: Vec<Algorithm>,
+        start_fraction: f32,
+        reduction_factor: usize,
+    ) -> Vec<Algorithm> {
+        let reduction_factor = reduction_factor.max(2);
+        let mut rung_settings = self.settings.clone();
+        rung_settings.final_model_approach = FinalModel::None;
+        let folds = self.settings.get_kfolds(&self.x_train, &self.y_train);
+        let mut rng = self.settings.rng("halving", &self.y_train);
+        let higher_is_better =
+            self.settings.sort_by == Metric::RSquared || self.settings.sort_by == Metric::Accuracy;
+        let mut fraction = start_fraction;
+        while fraction < 1.0 && candidates.len() > 1 {
+            // Cross-validate on the rows of the subsample within the fixed splits
+            let rows = subsample(
+                &self.y_train,
+                fraction,
+                self.settings.is_classification(),
+                &mut rng,
+            );
+            let x = self.x_train.take(&rows, 0);
+            let y: Vec<f32> = rows.iter().map(|&idx| self.y_train[idx]).collect();
+            rung_settings.folds = Some(folds.restricted_to(&rows));
+            // Rank from best to worst, with failed fits (scored NaN) last
+            let mut scored: Vec<(Algorithm, f32)> = candidates
+                .iter()
+                .map(|algorithm| {
+                    let score = algorithm.get_cross_validator()(&x, &y, &rung_settings)
+                        .map_or(f32::NAN, |model| model.0.scores.mean_test_score());
+                    let rank = if score.is_nan() {
+                        f32::INFINITY
+                    } else if higher_is_better {
+                        -score
+                    } else {
+                        score
+                    };
+                    (algorithm.clone(), rank)
+                })
+                .collect();
+            scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+            let keep = (candidates.len() as f32 / reduction_factor as f32).ceil() as usize;
+            candidates = scored
+                .into_iter()
+                .take(keep)
+                .map(|(algorithm, _)| algorithm)
+                .collect();
+            fraction *= reduction_factor as f32;
+        }
+        candidates
+    }
+
     /// Record a model in the comparison.
-    fn record_model(&mut self, model: (CrossValidationResult<f32>, Algorithm, Duration, Vec<u8>)) {
+    fn record_model(&mut self, model: (CrossValidation, Algorithm, Duration, Vec<u8>)) {
         self.comparison.push(Model {
-            score: model.0,
+            score: model.0.scores,
+            out_of_fold: model.0.predictions,
             name: model.1,
             duration: model.2,
             model: model.3,
+            calibrated: None,
         });
         self.sort();
     }
@@ -859,24 +2122,103 @@ impl Display for SupervisedModel {
         let mut table = Table::new();
         table.load_preset(UTF8_FULL);
         table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
-        table.set_header(vec![
+        let mut header = vec![
             Cell::new("Model").add_attribute(Attribute::Bold),
             Cell::new("Time").add_attribute(Attribute::Bold),
             Cell::new(format!("Training {}", self.settings.sort_by)).add_attribute(Attribute::Bold),
             Cell::new(format!("Testing {}", self.settings.sort_by)).add_attribute(Attribute::Bold),
-        ]);
-        for model in &self.comparison {
+            Cell::new("95% CI").add_attribute(Attribute::Bold),
+            Cell::new("p-value vs. Best").add_attribute(Attribute::Bold),
+        ];
+        let baseline = self
+            .comparison
+            .iter()
+            .find(|model| model.name.is_baseline())
+            .map(|model| model.score.mean_test_score());
+        if baseline.is_some() {
+            header.push(Cell::new("vs. Baseline").add_attribute(Attribute::Bold));
+        }
+        let show_brier = self
+            .comparison
+            .iter()
+            .any(|model| model.calibrated.is_some());
+        if show_brier {
+            header.push(Cell::new("Brier Score").add_attribute(Attribute::Bold));
+        }
+        let show_holdout = self.holdout_score.is_some()
+            && matches!(self.settings.final_model_approach, FinalModel::Best);
+        if show_holdout {
+            header.push(
+                Cell::new(format!("Holdout {}", self.settings.sort_by))
+                    .add_attribute(Attribute::Bold),
+            );
+        }
+        table.set_header(header);
+        let leaderboard = if self.comparison.is_empty() {
+            vec![]
+        } else {
+            self.leaderboard()
+        };
+        let best = self.best_index();
+        for (i, (model, entry)) in self.comparison.iter().zip(&leaderboard).enumerate() {
             let mut row_vec = vec![];
             row_vec.push(format!("{}", &model.name));
             row_vec.push(format!("{}", format_duration(model.duration)));
             let decider =
                 ((model.score.mean_train_score() + model.score.mean_test_score()) / 2.0).abs();
+            let (low, high) = entry.confidence_interval;
             if decider > 0.01 && decider < 1000.0 {
                 row_vec.push(format!("{:.2}", &model.score.mean_train_score()));
-                row_vec.push(format!("{:.2}", &model.score.mean_test_score()));
+                row_vec.push(format!(
+                    "{:.2} ± {:.2}",
+                    &entry.mean_test_score, &entry.std_test_score
+                ));
+                row_vec.push(format!("[{low:.2}, {high:.2}]"));
             } else {
                 row_vec.push(format!("{:.3e}", &model.score.mean_train_score()));
-                row_vec.push(format!("{:.3e}", &model.score.mean_test_score()));
+                row_vec.push(format!(
+                    "{:.3e} ± {:.3e}",
+                    &entry.mean_test_score, &entry.std_test_score
+                ));
+                row_vec.push(format!("[{low:.3e}, {high:.3e}]"));
+            }
+            row_vec.push(match entry.p_value {
+                None => "-".to_string(),
+                Some(p) if entry.tied_with_best => format!("{p:.3} (tied)"),
+                Some(p) => format!("{p:.3}"),
+            });
+            if let Some(baseline) = baseline {
+                // Positive when the model beats the baseline, whichever direction the metric runs
+                let improvement = if self.settings.sort_by == Metric::RSquared
+                    || self.settings.sort_by == Metric::Accuracy
+                {
+                    entry.mean_test_score - baseline
+                } else {
+                    baseline - entry.mean_test_score
+                };
+                row_vec.push(if model.name.is_baseline() {
+                    "-".to_string()
+                } else if decider > 0.01 && decider < 1000.0 {
+                    format!("{improvement:+.2}")
+                } else {
+                    format!("{improvement:+.3e}")
+                });
+            }
+            if show_brier {
+                row_vec.push(
+                    entry
+                        .brier_score
+                        .map_or_else(|| "-".to_string(), |score| format!("{score:.3}")),
+                );
+            }
+            if show_holdout {
+                row_vec.push(match self.holdout_score {
+                    Some(score) if i == best && decider > 0.01 && decider < 1000.0 => {
+                        format!("{score:.2}")
+                    }
+                    Some(score) if i == best => format!("{score:.3e}"),
+                    _ => "-".to_string(),
+                });
             }
 
             table.add_row(row_vec);
@@ -885,11 +2227,23 @@ impl Display for SupervisedModel {
         let mut meta_table = Table::new();
         meta_table.load_preset(UTF8_FULL);
         meta_table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
-        meta_table.set_header(vec![
+        let mut meta_header = vec![
             Cell::new("Meta Model").add_attribute(Attribute::Bold),
             Cell::new(format!("Training {}", self.settings.sort_by)).add_attribute(Attribute::Bold),
             Cell::new(format!("Testing {}", self.settings.sort_by)).add_attribute(Attribute::Bold),
-        ]);
+        ];
+        let show_meta_holdout = self.holdout_score.is_some()
+            && matches!(
+                self.settings.final_model_approach,
+                FinalModel::Blending { .. }
+            );
+        if show_meta_holdout {
+            meta_header.push(
+                Cell::new(format!("Holdout {}", self.settings.sort_by))
+                    .add_attribute(Attribute::Bold),
+            );
+        }
+        meta_table.set_header(meta_header);
 
         // Populate row
         let mut row_vec = vec![];
@@ -905,12 +2259,134 @@ impl Display for SupervisedModel {
             row_vec.push(format!("{:.3e}", self.metamodel.score.mean_train_score()));
             row_vec.push(format!("{:.3e}", self.metamodel.score.mean_test_score()));
         }
+        if let (true, Some(score)) = (show_meta_holdout, self.holdout_score) {
+            if decider > 0.01 && decider < 1000.0 {
+                row_vec.push(format!("{score:.2}"));
+            } else {
+                row_vec.push(format!("{score:.3e}"));
+            }
+        }
 
         // Add row to table
         meta_table.add_row(row_vec);
 
         // Write
-        write!(f, "{table}\n{meta_table}")
+        write!(f, "{table}\n{meta_table}")?;
+
+        // Report the members of a voting or selected ensemble, if any
+        if self.ensemble_weights.iter().any(|&weight| weight > 0.0) {
+            let mut ensemble_table = Table::new();
+            ensemble_table.load_preset(UTF8_FULL);
+            ensemble_table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+            ensemble_table.set_header(vec![
+                Cell::new("Ensemble Member").add_attribute(Attribute::Bold),
+                Cell::new("Weight").add_attribute(Attribute::Bold),
+            ]);
+            for (model, weight) in self.comparison.iter().zip(&self.ensemble_weights) {
+                if *weight > 0.0 {
+                    ensemble_table.add_row(vec![format!("{}", model.name), format!("{weight:.2}")]);
+                }
+            }
+            if let Some(score) = self.holdout_score {
+                ensemble_table.add_row(vec![
+                    format!("Holdout {}", self.settings.sort_by),
+                    format!("{score:.3}"),
+                ]);
+            }
+            write!(f, "\n{ensemble_table}")?;
+        }
+
+        // Report the outer folds of nested cross-validation, if any
+        if !self.nested_cv.is_empty() {
+            let mut nested_table = Table::new();
+            nested_table.load_preset(UTF8_FULL);
+            nested_table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+            nested_table.set_header(vec![
+                Cell::new("Outer Fold").add_attribute(Attribute::Bold),
+                Cell::new("Winner").add_attribute(Attribute::Bold),
+                Cell::new(format!("Outer Testing {}", self.settings.sort_by))
+                    .add_attribute(Attribute::Bold),
+            ]);
+            for (i, fold) in self.nested_cv.iter().enumerate() {
+                nested_table.add_row(vec![
+                    format!("{}", i + 1),
+                    format!("{}", fold.winner),
+                    format!("{:.3}", fold.score),
+                ]);
+            }
+            let scores: Vec<f32> = self.nested_cv.iter().map(|fold| fold.score).collect();
+            #[allow(clippy::cast_precision_loss)]
+            let mean = scores.iter().sum::<f32>() / scores.len() as f32;
+            nested_table.add_row(vec![
+                "All".to_string(),
+                "-".to_string(),
+                format!("{:.3} ± {:.3}", mean, standard_deviation(&scores)),
+            ]);
+            write!(f, "\n{nested_table}")?;
+        }
+
+        // Report the rounds of self-training, if any
+        if !self.self_training.is_empty() {
+            let mut self_training_table = Table::new();
+            self_training_table.load_preset(UTF8_FULL);
+            self_training_table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+            let mut header = vec![
+                Cell::new("Self-Training Round").add_attribute(Attribute::Bold),
+                Cell::new("Pseudo-Labeled Rows").add_attribute(Attribute::Bold),
+                Cell::new("Winner").add_attribute(Attribute::Bold),
+                Cell::new(format!("Testing {}", self.settings.sort_by))
+                    .add_attribute(Attribute::Bold),
+            ];
+            let show_holdout = self
+                .self_training
+                .iter()
+                .any(|round| round.holdout_score.is_some());
+            if show_holdout {
+                header.push(
+                    Cell::new(format!("Holdout {}", self.settings.sort_by))
+                        .add_attribute(Attribute::Bold),
+                );
+            }
+            self_training_table.set_header(header);
+            for (i, round) in self.self_training.iter().enumerate() {
+                let mut row_vec = vec![
+                    if i + 1 == self.self_training.len() {
+                        "Final".to_string()
+                    } else {
+                        format!("{}", i + 1)
+                    },
+                    format!("{}", round.pseudo_labeled),
+                    format!("{}", round.winner),
+                    format!("{:.3}", round.score),
+                ];
+                if show_holdout {
+                    row_vec.push(
+                        round
+                            .holdout_score
+                            .map_or_else(|| "-".to_string(), |score| format!("{score:.3}")),
+                    );
+                }
+                self_training_table.add_row(row_vec);
+            }
+            write!(f, "\n{self_training_table}")?;
+        }
+
+        // Report how the classes were rebalanced, if at all
+        if !matches!(self.settings.resampling, Resampling::None)
+            || !matches!(self.settings.class_weight, ClassWeight::None)
+        {
+            write!(
+                f,
+                "\nRebalancing: {} (resampling), {} (class weights)",
+                self.settings.resampling, self.settings.class_weight
+            )?;
+        }
+
+        // Report the tuned decision threshold, if any
+        if let Some(threshold) = self.decision_threshold {
+            write!(f, "\nDecision threshold: {threshold:.3}")?;
+        }
+        Ok(())
     }
 }
 
@@ -920,12 +2396,17 @@ struct Model {
     /// The cross validation score of the model
     #[serde(with = "CrossValidationResultDef")]
     score: CrossValidationResult<f32>,
+    /// The out-of-fold predictions of the cross-validation, for ensemble selection
+    #[serde(skip)]
+    out_of_fold: Vec<f32>,
     /// The algorithm used
     name: Algorithm,
     /// The time it took to train the model
     duration: Duration,
     /// What is this? TODO
     model: Vec<u8>,
+    /// The model with calibrated class probabilities, if calibration is enabled
+    calibrated: Option<CalibratedClassifier>,
 }
 
 impl Default for Model {
@@ -935,13 +2416,72 @@ impl Default for Model {
                 test_score: vec![],
                 train_score: vec![],
             },
+            out_of_fold: vec![],
             name: Algorithm::Linear,
             duration: Duration::default(),
             model: vec![],
+            calibrated: None,
         }
     }
 }
 
+/// The best model trained without one fold of the training data, with what it needs to predict
+/// and the nonconformity scores of the rows it left out, for CV+ and jackknife+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ConformalFold {
+    /// The best model trained without the fold
+    model: Model,
+    /// The PCA preprocessor trained without the fold, if any
+    preprocessing_pca: Option<PCA<f32, DenseMatrix<f32>>>,
+    /// The SVD preprocessor trained without the fold, if any
+    preprocessing_svd: Option<SVD<f32, DenseMatrix<f32>>>,
+    /// The nonconformity score of each row in the fold
+    scores: Vec<f32>,
+}
+
+impl ConformalFold {
+    /// Preprocess the data the way the model was trained.
+    fn preprocess(&self, x: &DenseMatrix<f32>, settings: &Settings) -> DenseMatrix<f32> {
+        SupervisedModel::preprocess_with(
+            settings,
+            self.preprocessing_pca.as_ref(),
+            self.preprocessing_svd.as_ref(),
+            x.clone(),
+        )
+    }
+
+    /// Predict with the model trained without the fold.
+    fn predict(&self, x: &DenseMatrix<f32>, settings: &Settings) -> Vec<f32> {
+        self.model.name.get_predictor()(&self.preprocess(x, settings), &self.model.model, settings)
+    }
+
+    /// The calibrated probability of each class the model trained without the fold saw.
+    fn predict_proba(&self, x: &DenseMatrix<f32>, settings: &Settings) -> Vec<Vec<f32>> {
+        self.model
+            .calibrated
+            .as_ref()
+            .expect("Conformal sets need calibrated probabilities.")
+            .predict_proba(&self.preprocess(x, settings), settings)
+    }
+
+    /// The probability of a class among the probabilities from `predict_proba`, which is zero
+    /// for a class the model never saw.
+    fn class_probability(&self, probabilities: &[f32], class: f32) -> f32 {
+        self.model.calibrated.as_ref().map_or(0.0, |calibrated| {
+            class_probability(calibrated.classes(), probabilities, class)
+        })
+    }
+}
+
+/// The probability of a class among the probabilities of the given classes, which is zero for a
+/// class not among them.
+fn class_probability(classes: &[f32], probabilities: &[f32], class: f32) -> f32 {
+    classes
+        .iter()
+        .position(|known| known.total_cmp(&class).is_eq())
+        .map_or(0.0, |idx| probabilities[idx])
+}
+
 /// This is a wrapper for the `CrossValidationResult`
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(remote = "CrossValidationResult::<f32>")]
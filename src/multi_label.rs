@@ -0,0 +1,333 @@
+//! Comparison and training of classifiers with several labels per row
+
+use std::{
+    cmp::Ordering::Equal,
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+    time::{Duration, Instant},
+};
+
+use comfy_table::{
+    modifiers::UTF8_SOLID_INNER_BORDERS, presets::UTF8_FULL, Attribute, Cell, Table,
+};
+use humantime::format_duration;
+use smartcore::{
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    model_selection::BaseKFold,
+};
+
+use crate::algorithms::MultiLabelClassifier;
+use crate::settings::{Algorithm, FinalModel, MultiLabelMetric};
+use crate::{IntoFeatures, IntoMultiOutputData, Settings, SupervisedModel};
+
+/// Trains and compares classifiers for rows that can carry several labels
+///
+/// The labels are given as a label-indicator matrix, with one column per label holding a one
+/// where the row carries the label and a zero otherwise. Every classifier is fit with the
+/// strategy set with `Settings::with_multi_label`, and the models are sorted by the metric set
+/// with `Settings::with_multi_label_metric`.
+///
+/// Feature engineering, early elimination and final model approaches other than choosing the best
+/// model are not used.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MultiLabelModel {
+    /// Settings for the model.
+    settings: Settings,
+    /// The training data.
+    x_train: DenseMatrix<f32>,
+    /// The training labels, one column per label.
+    y_train: DenseMatrix<f32>,
+    /// The results of the model comparison, from best to worst.
+    comparison: Vec<MultiLabelResult>,
+    /// The best model, trained on all of the data.
+    final_model: Option<MultiLabelClassifier>,
+}
+
+/// The cross-validation results of a single algorithm
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MultiLabelResult {
+    /// The algorithm used
+    algorithm: Algorithm,
+    /// The mean training score across folds, by the metric in the settings
+    train_score: f32,
+    /// The mean testing score across folds by each metric, in the order of
+    /// `MultiLabelMetric::ALL`
+    test_scores: Vec<f32>,
+    /// The time it took to cross-validate the algorithm
+    duration: Duration,
+}
+
+impl MultiLabelResult {
+    /// The mean testing score across folds by a metric
+    fn test_score(&self, metric: MultiLabelMetric) -> f32 {
+        MultiLabelMetric::ALL
+            .iter()
+            .position(|&other| other == metric)
+            .map_or(f32::NAN, |index| self.test_scores[index])
+    }
+}
+
+impl MultiLabelModel {
+    /// Create a new multi-label model from features and a label-indicator matrix, with one row per
+    /// row of the features and one column of zeros and ones per label.
+    /// ```
+    /// # use automl::{MultiLabelModel, Settings};
+    /// let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// let y: Vec<Vec<f32>> = x
+    ///     .iter()
+    ///     .map(|row| vec![f32::from(row[0] > 9.0), f32::from(row[1] > 0.0)])
+    ///     .collect();
+    /// let model = MultiLabelModel::new((x, y), Settings::default_classification());
+    /// ```
+    pub fn new<D>(data: D, settings: Settings) -> Self
+    where
+        D: IntoMultiOutputData,
+    {
+        let (x_train, y_train) = data.to_multi_output_data();
+        Self {
+            settings,
+            x_train,
+            y_train,
+            comparison: vec![],
+            final_model: None,
+        }
+    }
+
+    /// Load a multi-label model from a file saved with `MultiLabelModel::save`.
+    /// ```
+    /// # use automl::{MultiLabelModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x
+    /// #     .iter()
+    /// #     .map(|row| vec![f32::from(row[0] > 9.0), f32::from(row[1] > 0.0)])
+    /// #     .collect();
+    /// let model = MultiLabelModel::new((x, y), Settings::default_classification());
+    /// model.save("tests/load_that_multi_label_model.aml");
+    /// let model = MultiLabelModel::new_from_file("tests/load_that_multi_label_model.aml");
+    /// # std::fs::remove_file("tests/load_that_multi_label_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold a multi-label model, this function will panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot load model from file.");
+        bincode::deserialize(&buf).expect("Can not deserialize the model")
+    }
+
+    /// Runs a model comparison and trains the best model on all of the data.
+    /// ```
+    /// # use automl::{MultiLabelModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x
+    /// #     .iter()
+    /// #     .map(|row| vec![f32::from(row[0] > 9.0), f32::from(row[1] > 0.0)])
+    /// #     .collect();
+    /// let mut model = MultiLabelModel::new(
+    ///     (x, y),
+    ///     Settings::default_classification()
+    /// # .only(automl::settings::Algorithm::DecisionTreeClassifier)
+    /// );
+    /// model.train();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the settings are not for classification, or the labels are not all zeros and ones, this
+    /// function will panic.
+    pub fn train(&mut self) {
+        assert!(
+            self.settings.is_classification(),
+            "Multi-label models are for classification."
+        );
+        assert!(
+            self.y_train
+                .iter()
+                .all(|value| value.abs() < f32::EPSILON || (value - 1.0).abs() < f32::EPSILON),
+            "The labels must be a matrix of zeros and ones."
+        );
+        let metric = self.settings.multi_label_metric;
+        // Split once so every algorithm is compared on the same folds
+        let folds = self
+            .settings
+            .get_kfolds(&self.x_train, &self.y_train.get_col_as_vec(0));
+        #[allow(clippy::cast_precision_loss)]
+        let number_of_folds = folds.n_splits() as f32;
+        for algorithm in self.candidate_algorithms() {
+            let start = Instant::now();
+            let mut result = MultiLabelResult {
+                algorithm,
+                train_score: 0.0,
+                test_scores: vec![0.0; MultiLabelMetric::ALL.len()],
+                duration: Duration::default(),
+            };
+            for (train, test) in folds.split(&self.x_train) {
+                let model = MultiLabelClassifier::fit(
+                    result.algorithm.clone(),
+                    &self.x_train.take(&train, 0),
+                    &self.y_train.take(&train, 0),
+                    &self.settings,
+                );
+                result.train_score += self.score(&model, &train, metric) / number_of_folds;
+                for (total, &test_metric) in
+                    result.test_scores.iter_mut().zip(&MultiLabelMetric::ALL)
+                {
+                    *total += self.score(&model, &test, test_metric) / number_of_folds;
+                }
+            }
+            result.duration = start.elapsed();
+            self.comparison.push(result);
+        }
+
+        self.comparison.sort_by(|a, b| {
+            a.test_score(metric)
+                .partial_cmp(&b.test_score(metric))
+                .unwrap_or(Equal)
+        });
+        if metric.higher_is_better() {
+            self.comparison.reverse();
+        }
+
+        if !matches!(self.settings.final_model_approach, FinalModel::None) {
+            // The baselines only serve as a reference, unless every model is one
+            let best = self
+                .comparison
+                .iter()
+                .find(|result| !result.algorithm.is_baseline())
+                .or_else(|| self.comparison.first());
+            self.final_model = best.map(|best| {
+                MultiLabelClassifier::fit(
+                    best.algorithm.clone(),
+                    &self.x_train,
+                    &self.y_train,
+                    &self.settings,
+                )
+            });
+        }
+    }
+
+    /// Predict every label with the best model, returning a label-indicator matrix with one row
+    /// per row of the data and one column per label.
+    /// ```
+    /// # use automl::{MultiLabelModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x
+    /// #     .iter()
+    /// #     .map(|row| vec![f32::from(row[0] > 9.0), f32::from(row[1] > 0.0)])
+    /// #     .collect();
+    /// let mut model = MultiLabelModel::new(
+    ///     (x, y),
+    ///     Settings::default_classification()
+    /// # .only(automl::settings::Algorithm::DecisionTreeClassifier)
+    /// );
+    /// model.train();
+    /// let labels = model.predict(vec![vec![15.0, 1.0]; 3]);
+    /// assert_eq!(labels.len(), 3);
+    /// assert_eq!(labels[0].len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, this function will panic.
+    pub fn predict<X: IntoFeatures>(&self, x: X) -> Vec<Vec<f32>> {
+        let predictions = self
+            .final_model
+            .as_ref()
+            .expect("The model has not been trained.")
+            .predict(&x.to_dense_matrix(), &self.settings);
+        (0..predictions.shape().0)
+            .map(|row| predictions.get_row_as_vec(row))
+            .collect()
+    }
+
+    /// Save the multi-label model to a file for later use
+    /// ```
+    /// # use automl::{MultiLabelModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x
+    /// #     .iter()
+    /// #     .map(|row| vec![f32::from(row[0] > 9.0), f32::from(row[1] > 0.0)])
+    /// #     .collect();
+    /// let model = MultiLabelModel::new((x, y), Settings::default_classification());
+    /// model.save("tests/save_that_multi_label_model.aml");
+    /// # std::fs::remove_file("tests/save_that_multi_label_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = bincode::serialize(&self).expect("Cannot serialize model.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(&serial))
+            .expect("Cannot write model to file.");
+    }
+}
+
+/// Private functions go here
+impl MultiLabelModel {
+    /// The classification algorithms that are not skipped and can be used with every label.
+    fn candidate_algorithms(&self) -> Vec<Algorithm> {
+        let per_label: Vec<Vec<Algorithm>> = (0..self.y_train.shape().1)
+            .map(|label| {
+                SupervisedModel::build(
+                    self.x_train.clone(),
+                    self.y_train.get_col_as_vec(label),
+                    self.settings.clone(),
+                )
+                .candidate_algorithms()
+            })
+            .collect();
+        per_label[0]
+            .iter()
+            .filter(|algorithm| {
+                per_label
+                    .iter()
+                    .all(|candidates| candidates.contains(algorithm))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Score a model on some of the training rows with a multi-label metric.
+    fn score(&self, model: &MultiLabelClassifier, rows: &[usize], metric: MultiLabelMetric) -> f32 {
+        metric.get_function()(
+            &self.y_train.take(rows, 0),
+            &model.predict(&self.x_train.take(rows, 0), &self.settings),
+        )
+    }
+}
+
+impl Display for MultiLabelModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+        let mut header = vec![
+            Cell::new("Model").add_attribute(Attribute::Bold),
+            Cell::new("Fit").add_attribute(Attribute::Bold),
+            Cell::new("Time").add_attribute(Attribute::Bold),
+            Cell::new(format!("Training {}", self.settings.multi_label_metric))
+                .add_attribute(Attribute::Bold),
+        ];
+        for metric in MultiLabelMetric::ALL {
+            header.push(Cell::new(format!("Testing {metric}")).add_attribute(Attribute::Bold));
+        }
+        table.set_header(header);
+        for result in &self.comparison {
+            let mut row = vec![
+                format!("{}", result.algorithm),
+                format!("{}", self.settings.multi_label),
+                format!("{}", format_duration(result.duration)),
+                format!("{:.2}", result.train_score),
+            ];
+            row.extend(result.test_scores.iter().map(|score| format!("{score:.2}")));
+            table.add_row(row);
+        }
+        write!(f, "{table}")
+    }
+}
@@ -0,0 +1,344 @@
+//! Comparison and training of regressors with several targets
+
+use std::{
+    cmp::Ordering::Equal,
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+    time::{Duration, Instant},
+};
+
+use comfy_table::{
+    modifiers::UTF8_SOLID_INNER_BORDERS, presets::UTF8_FULL, Attribute, Cell, Table,
+};
+use humantime::format_duration;
+use smartcore::{
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    model_selection::BaseKFold,
+};
+
+use crate::algorithms::MultiOutputRegressor;
+use crate::settings::{Algorithm, FinalModel, Metric};
+use crate::{IntoFeatures, IntoMultiOutputData, Settings, SupervisedModel};
+
+/// Trains and compares regressors with several targets
+///
+/// Linear regression, decision tree regression and KNN regression are fit jointly to all of the
+/// targets, and the other algorithms are fit with the strategy set with
+/// `Settings::with_multi_output`. Scores are averaged over the targets.
+///
+/// Feature engineering, early elimination and final model approaches other than choosing the best
+/// model are not used.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MultiOutputModel {
+    /// Settings for the model.
+    settings: Settings,
+    /// The training data.
+    x_train: DenseMatrix<f32>,
+    /// The training targets, one column per target.
+    y_train: DenseMatrix<f32>,
+    /// The results of the model comparison, from best to worst.
+    comparison: Vec<MultiOutputResult>,
+    /// The best model, trained on all of the data.
+    final_model: Option<MultiOutputRegressor>,
+}
+
+/// The cross-validation results of a single algorithm
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MultiOutputResult {
+    /// The algorithm used
+    algorithm: Algorithm,
+    /// The training score on each fold, averaged over the targets
+    train_scores: Vec<f32>,
+    /// The testing score on each fold, averaged over the targets
+    test_scores: Vec<f32>,
+    /// The mean testing score of each target across folds
+    target_test_scores: Vec<f32>,
+    /// The time it took to cross-validate the algorithm
+    duration: Duration,
+}
+
+impl MultiOutputResult {
+    /// The mean training score across folds
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_train_score(&self) -> f32 {
+        self.train_scores.iter().sum::<f32>() / self.train_scores.len() as f32
+    }
+
+    /// The mean testing score across folds
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_test_score(&self) -> f32 {
+        self.test_scores.iter().sum::<f32>() / self.test_scores.len() as f32
+    }
+}
+
+impl MultiOutputModel {
+    /// Create a new multi-output model from features and a matrix of targets, with one row per
+    /// row of the features and one column per target.
+    /// ```
+    /// # use automl::{MultiOutputModel, Settings};
+    /// let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// let y: Vec<Vec<f32>> = x.iter().map(|row| vec![row[0] + row[1], 2.0 * row[0]]).collect();
+    /// let model = MultiOutputModel::new((x, y), Settings::default_regression());
+    /// ```
+    pub fn new<D>(data: D, settings: Settings) -> Self
+    where
+        D: IntoMultiOutputData,
+    {
+        let (x_train, y_train) = data.to_multi_output_data();
+        Self {
+            settings,
+            x_train,
+            y_train,
+            comparison: vec![],
+            final_model: None,
+        }
+    }
+
+    /// Load a multi-output model from a file saved with `MultiOutputModel::save`.
+    /// ```
+    /// # use automl::{MultiOutputModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x.iter().map(|row| vec![row[0] + row[1], 2.0 * row[0]]).collect();
+    /// let model = MultiOutputModel::new((x, y), Settings::default_regression());
+    /// model.save("tests/load_that_multi_output_model.aml");
+    /// let model = MultiOutputModel::new_from_file("tests/load_that_multi_output_model.aml");
+    /// # std::fs::remove_file("tests/load_that_multi_output_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold a multi-output model, this function will
+    /// panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot load model from file.");
+        bincode::deserialize(&buf).expect("Can not deserialize the model")
+    }
+
+    /// Runs a model comparison and trains the best model on all of the data.
+    /// ```
+    /// # use automl::{MultiOutputModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x.iter().map(|row| vec![row[0] + row[1], 2.0 * row[0]]).collect();
+    /// let mut model = MultiOutputModel::new(
+    ///     (x, y),
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the settings are for classification, this function will panic.
+    pub fn train(&mut self) {
+        assert!(
+            !self.settings.is_classification(),
+            "Multi-output models are for regression."
+        );
+        // Split once so every algorithm is compared on the same folds
+        let folds = self
+            .settings
+            .get_kfolds(&self.x_train, &self.y_train.get_col_as_vec(0));
+        #[allow(clippy::cast_precision_loss)]
+        let number_of_folds = folds.n_splits() as f32;
+        for algorithm in self.candidate_algorithms() {
+            let start = Instant::now();
+            let mut result = MultiOutputResult {
+                algorithm,
+                train_scores: vec![],
+                test_scores: vec![],
+                target_test_scores: vec![0.0; self.y_train.shape().1],
+                duration: Duration::default(),
+            };
+            for (train, test) in folds.split(&self.x_train) {
+                let model = MultiOutputRegressor::fit(
+                    result.algorithm.clone(),
+                    &self.x_train.take(&train, 0),
+                    &self.y_train.take(&train, 0),
+                    &self.settings,
+                );
+                let (train_score, _) = self.score(&model, &train);
+                let (test_score, target_scores) = self.score(&model, &test);
+                result.train_scores.push(train_score);
+                result.test_scores.push(test_score);
+                for (total, score) in result.target_test_scores.iter_mut().zip(target_scores) {
+                    *total += score / number_of_folds;
+                }
+            }
+            result.duration = start.elapsed();
+            self.comparison.push(result);
+        }
+
+        self.comparison.sort_by(|a, b| {
+            a.mean_test_score()
+                .partial_cmp(&b.mean_test_score())
+                .unwrap_or(Equal)
+        });
+        if self.settings.sort_by == Metric::RSquared {
+            self.comparison.reverse();
+        }
+
+        if !matches!(self.settings.final_model_approach, FinalModel::None) {
+            // The baselines only serve as a reference, unless every model is one
+            let best = self
+                .comparison
+                .iter()
+                .find(|result| !result.algorithm.is_baseline())
+                .or_else(|| self.comparison.first());
+            self.final_model = best.map(|best| {
+                MultiOutputRegressor::fit(
+                    best.algorithm.clone(),
+                    &self.x_train,
+                    &self.y_train,
+                    &self.settings,
+                )
+            });
+        }
+    }
+
+    /// Predict every target with the best model, returning one row per row of the data and one
+    /// column per target.
+    /// ```
+    /// # use automl::{MultiOutputModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x.iter().map(|row| vec![row[0] + row[1], 2.0 * row[0]]).collect();
+    /// let mut model = MultiOutputModel::new(
+    ///     (x, y),
+    ///     Settings::default_regression()
+    /// # .only(automl::settings::Algorithm::Linear)
+    /// );
+    /// model.train();
+    /// let predictions = model.predict(vec![vec![5.0, 1.0]; 3]);
+    /// assert_eq!(predictions.len(), 3);
+    /// assert_eq!(predictions[0].len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, this function will panic.
+    pub fn predict<X: IntoFeatures>(&self, x: X) -> Vec<Vec<f32>> {
+        let predictions = self
+            .final_model
+            .as_ref()
+            .expect("The model has not been trained.")
+            .predict(&x.to_dense_matrix(), &self.settings);
+        (0..predictions.shape().0)
+            .map(|row| predictions.get_row_as_vec(row))
+            .collect()
+    }
+
+    /// Save the multi-output model to a file for later use
+    /// ```
+    /// # use automl::{MultiOutputModel, Settings};
+    /// # let x: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32, (i % 3) as f32]).collect();
+    /// # let y: Vec<Vec<f32>> = x.iter().map(|row| vec![row[0] + row[1], 2.0 * row[0]]).collect();
+    /// let model = MultiOutputModel::new((x, y), Settings::default_regression());
+    /// model.save("tests/save_that_multi_output_model.aml");
+    /// # std::fs::remove_file("tests/save_that_multi_output_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = bincode::serialize(&self).expect("Cannot serialize model.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(&serial))
+            .expect("Cannot write model to file.");
+    }
+}
+
+/// Private functions go here
+impl MultiOutputModel {
+    /// The regression algorithms that are not skipped and can be used with every target.
+    fn candidate_algorithms(&self) -> Vec<Algorithm> {
+        let per_target: Vec<Vec<Algorithm>> = (0..self.y_train.shape().1)
+            .map(|target| {
+                SupervisedModel::build(
+                    self.x_train.clone(),
+                    self.y_train.get_col_as_vec(target),
+                    self.settings.clone(),
+                )
+                .candidate_algorithms()
+            })
+            .collect();
+        per_target[0]
+            .iter()
+            .filter(|algorithm| {
+                per_target
+                    .iter()
+                    .all(|candidates| candidates.contains(algorithm))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Score a model on some of the training rows, returning the score averaged over the targets
+    /// and the score of each target.
+    fn score(&self, model: &MultiOutputRegressor, rows: &[usize]) -> (f32, Vec<f32>) {
+        let metric = self.settings.get_metric();
+        let predictions = model.predict(&self.x_train.take(rows, 0), &self.settings);
+        let y = self.y_train.take(rows, 0);
+        let scores: Vec<f32> = (0..y.shape().1)
+            .map(|target| {
+                metric(
+                    &y.get_col_as_vec(target),
+                    &predictions.get_col_as_vec(target),
+                )
+            })
+            .collect();
+        #[allow(clippy::cast_precision_loss)]
+        let mean = scores.iter().sum::<f32>() / scores.len() as f32;
+        (mean, scores)
+    }
+}
+
+impl Display for MultiOutputModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+        table.set_header(vec![
+            Cell::new("Model").add_attribute(Attribute::Bold),
+            Cell::new("Fit").add_attribute(Attribute::Bold),
+            Cell::new("Time").add_attribute(Attribute::Bold),
+            Cell::new(format!("Training {}", self.settings.sort_by)).add_attribute(Attribute::Bold),
+            Cell::new(format!("Testing {}", self.settings.sort_by)).add_attribute(Attribute::Bold),
+            Cell::new(format!("Testing {} by Target", self.settings.sort_by))
+                .add_attribute(Attribute::Bold),
+        ]);
+        for result in &self.comparison {
+            let fit = if MultiOutputRegressor::is_native(&result.algorithm) {
+                "Joint".to_string()
+            } else {
+                format!("{}", self.settings.multi_output)
+            };
+            let decider = f32::midpoint(result.mean_train_score(), result.mean_test_score()).abs();
+            let format_score = |score: f32| {
+                if decider > 0.01 && decider < 1000.0 {
+                    format!("{score:.2}")
+                } else {
+                    format!("{score:.3e}")
+                }
+            };
+            table.add_row(vec![
+                format!("{}", result.algorithm),
+                fit,
+                format!("{}", format_duration(result.duration)),
+                format_score(result.mean_train_score()),
+                format_score(result.mean_test_score()),
+                result
+                    .target_test_scores
+                    .iter()
+                    .map(|&score| format_score(score))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]);
+        }
+        write!(f, "{table}")
+    }
+}
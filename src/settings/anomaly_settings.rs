@@ -0,0 +1,292 @@
+//! Settings for the anomaly detection model comparison
+
+use comfy_table::{
+    modifiers::UTF8_SOLID_INNER_BORDERS, presets::UTF8_FULL, Attribute, Cell, Table,
+};
+
+use super::{
+    AnomalyAlgorithm, AnomalyMetric, IsolationForestParameters, LocalOutlierFactorParameters,
+    RobustCovarianceParameters,
+};
+use crate::utils::{print_knn_search_algorithm, print_option};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+
+/// Settings for anomaly detection models
+///
+/// Any algorithms in the `skiplist` member will be skipped during training.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct AnomalySettings {
+    /// The metric to sort by when a labelled validation set is available
+    pub(crate) sort_by: AnomalyMetric,
+    /// The algorithms to skip
+    pub(crate) skiplist: Vec<AnomalyAlgorithm>,
+    /// The expected fraction of anomalies in the training data, which sets the threshold
+    pub(crate) contamination: f32,
+    /// The seed used for all random number generation, if any
+    pub(crate) seed: Option<u64>,
+    /// Settings for the isolation forest
+    pub(crate) isolation_forest_settings: IsolationForestParameters,
+    /// Settings for the local outlier factor
+    pub(crate) local_outlier_factor_settings: LocalOutlierFactorParameters,
+    /// Settings for robust covariance
+    pub(crate) robust_covariance_settings: RobustCovarianceParameters,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            sort_by: AnomalyMetric::RocAuc,
+            skiplist: vec![],
+            contamination: 0.1,
+            seed: None,
+            isolation_forest_settings: IsolationForestParameters::default(),
+            local_outlier_factor_settings: LocalOutlierFactorParameters::default(),
+            robust_covariance_settings: RobustCovarianceParameters::default(),
+        }
+    }
+}
+
+impl AnomalySettings {
+    /// Load settings from a settings file
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// # let settings = AnomalySettings::default();
+    /// # settings.save("tests/load_those_anomaly_settings.yaml");
+    /// let settings = AnomalySettings::new_from_file("tests/load_those_anomaly_settings.yaml");
+    /// # std::fs::remove_file("tests/load_those_anomaly_settings.yaml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold anomaly settings, this function will panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot read settings file.");
+        serde_yaml::from_slice(&buf).expect("Cannot deserialize settings file.")
+    }
+
+    /// Save the current settings to a file for later use
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// let settings = AnomalySettings::default();
+    /// settings.save("tests/save_those_anomaly_settings.yaml");
+    /// # std::fs::remove_file("tests/save_those_anomaly_settings.yaml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the settings cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = serde_yaml::to_string(&self).expect("Cannot serialize settings.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(serial.as_ref()))
+            .expect("Cannot write settings to file.");
+    }
+
+    /// Specify algorithms that shouldn't be included in comparison
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// use automl::settings::AnomalyAlgorithm;
+    /// let settings = AnomalySettings::default().skip(AnomalyAlgorithm::RobustCovariance);
+    /// ```
+    #[must_use]
+    pub fn skip(mut self, skip: AnomalyAlgorithm) -> Self {
+        self.skiplist.push(skip);
+        self
+    }
+
+    /// Specify only one algorithm to train
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// use automl::settings::AnomalyAlgorithm;
+    /// let settings = AnomalySettings::default().only(AnomalyAlgorithm::IsolationForest);
+    /// ```
+    #[must_use]
+    pub fn only(mut self, only: AnomalyAlgorithm) -> Self {
+        self.skiplist = vec![
+            AnomalyAlgorithm::IsolationForest,
+            AnomalyAlgorithm::LocalOutlierFactor,
+            AnomalyAlgorithm::RobustCovariance,
+        ];
+        self.skiplist.retain(|&algorithm| algorithm != only);
+        self
+    }
+
+    /// Adds a specific sorting metric to the settings, used when a labelled validation set is
+    /// available
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// use automl::settings::AnomalyMetric;
+    /// let settings = AnomalySettings::default().sorted_by(AnomalyMetric::AveragePrecision);
+    /// ```
+    #[must_use]
+    pub const fn sorted_by(mut self, sort_by: AnomalyMetric) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    /// Specify the expected fraction of anomalies in the training data. Rows scored above all but
+    /// this fraction of the training rows are flagged as anomalies.
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// let settings = AnomalySettings::default().with_contamination(0.02);
+    /// ```
+    #[must_use]
+    pub const fn with_contamination(mut self, contamination: f32) -> Self {
+        self.contamination = contamination;
+        self
+    }
+
+    /// Specify a seed that makes every run with these settings reproducible
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// let settings = AnomalySettings::default().with_seed(42);
+    /// ```
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Specify settings for the isolation forest
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// use automl::settings::IsolationForestParameters;
+    /// let settings = AnomalySettings::default().with_isolation_forest_settings(
+    ///     IsolationForestParameters::default()
+    ///         .with_n_trees(200)
+    ///         .with_max_samples(128),
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_isolation_forest_settings(
+        mut self,
+        settings: IsolationForestParameters,
+    ) -> Self {
+        self.isolation_forest_settings = settings;
+        self
+    }
+
+    /// Specify settings for the local outlier factor
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// use automl::settings::{Distance, KNNAlgorithmName, LocalOutlierFactorParameters};
+    /// let settings = AnomalySettings::default().with_local_outlier_factor_settings(
+    ///     LocalOutlierFactorParameters::default()
+    ///         .with_k(10)
+    ///         .with_algorithm(KNNAlgorithmName::LinearSearch)
+    ///         .with_distance(Distance::Manhattan),
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_local_outlier_factor_settings(
+        mut self,
+        settings: LocalOutlierFactorParameters,
+    ) -> Self {
+        self.local_outlier_factor_settings = settings;
+        self
+    }
+
+    /// Specify settings for robust covariance
+    /// ```
+    /// # use automl::AnomalySettings;
+    /// use automl::settings::RobustCovarianceParameters;
+    /// let settings = AnomalySettings::default().with_robust_covariance_settings(
+    ///     RobustCovarianceParameters::default()
+    ///         .with_support_fraction(0.75)
+    ///         .with_n_starts(20),
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_robust_covariance_settings(
+        mut self,
+        settings: RobustCovarianceParameters,
+    ) -> Self {
+        self.robust_covariance_settings = settings;
+        self
+    }
+}
+
+impl Display for AnomalySettings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        let skiplist = if self.skiplist.is_empty() {
+            "None".to_string()
+        } else {
+            self.skiplist
+                .iter()
+                .map(|algorithm| format!("{algorithm}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec![
+                Cell::new("Settings").add_attribute(Attribute::Bold),
+                Cell::new("Value").add_attribute(Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("General").add_attribute(Attribute::Italic)])
+            .add_row(vec!["    Sorting Metric", &*format!("{}", self.sort_by)])
+            .add_row(vec![
+                "    Contamination",
+                &*format!("{}", self.contamination),
+            ])
+            .add_row(vec!["    Seed", &*print_option(self.seed)])
+            .add_row(vec!["    Skipped Algorithms", &skiplist]);
+        if !self.skiplist.contains(&AnomalyAlgorithm::IsolationForest) {
+            table
+                .add_row(vec![
+                    Cell::new(AnomalyAlgorithm::IsolationForest).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Number of Trees",
+                    &*format!("{}", self.isolation_forest_settings.n_trees),
+                ])
+                .add_row(vec![
+                    "    Maximum Samples",
+                    &*format!("{}", self.isolation_forest_settings.max_samples),
+                ]);
+        }
+        if !self
+            .skiplist
+            .contains(&AnomalyAlgorithm::LocalOutlierFactor)
+        {
+            table
+                .add_row(vec![Cell::new(AnomalyAlgorithm::LocalOutlierFactor)
+                    .add_attribute(Attribute::Italic)])
+                .add_row(vec![
+                    "    Number of Neighbors",
+                    &*format!("{}", self.local_outlier_factor_settings.k),
+                ])
+                .add_row(vec![
+                    "    Search Algorithm",
+                    &print_knn_search_algorithm(&self.local_outlier_factor_settings.algorithm),
+                ])
+                .add_row(vec![
+                    "    Distance Metric",
+                    &*format!("{}", self.local_outlier_factor_settings.distance),
+                ]);
+        }
+        if !self.skiplist.contains(&AnomalyAlgorithm::RobustCovariance) {
+            table
+                .add_row(vec![
+                    Cell::new(AnomalyAlgorithm::RobustCovariance).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Support Fraction",
+                    &*print_option(self.robust_covariance_settings.support_fraction),
+                ])
+                .add_row(vec![
+                    "    Number of Starts",
+                    &*format!("{}", self.robust_covariance_settings.n_starts),
+                ]);
+        }
+        writeln!(f, "{table}")
+    }
+}
@@ -0,0 +1,250 @@
+//! Settings for the clustering model comparison
+
+use comfy_table::{
+    modifiers::UTF8_SOLID_INNER_BORDERS, presets::UTF8_FULL, Attribute, Cell, Table,
+};
+
+use super::{
+    ClusteringAlgorithm, ClusteringMetric, DBSCANParameters, HierarchicalParameters,
+    KMeansParameters,
+};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::ops::RangeInclusive;
+
+/// Settings for unsupervised models
+///
+/// Any algorithms in the `skiplist` member will be skipped during training.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClusteringSettings {
+    /// The metric to sort by
+    pub(crate) sort_by: ClusteringMetric,
+    /// The algorithms to skip
+    pub(crate) skiplist: Vec<ClusteringAlgorithm>,
+    /// The numbers of clusters to try with k-means and hierarchical clustering
+    pub(crate) k_range: RangeInclusive<usize>,
+    /// Settings for k-means
+    pub(crate) kmeans_settings: KMeansParameters,
+    /// Settings for DBSCAN
+    pub(crate) dbscan_settings: DBSCANParameters,
+    /// Settings for hierarchical clustering
+    pub(crate) hierarchical_settings: HierarchicalParameters,
+}
+
+impl Default for ClusteringSettings {
+    fn default() -> Self {
+        Self {
+            sort_by: ClusteringMetric::Silhouette,
+            skiplist: vec![],
+            k_range: 2..=8,
+            kmeans_settings: KMeansParameters::default(),
+            dbscan_settings: DBSCANParameters::default(),
+            hierarchical_settings: HierarchicalParameters::default(),
+        }
+    }
+}
+
+impl ClusteringSettings {
+    /// Load settings from a settings file
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// # let settings = ClusteringSettings::default();
+    /// # settings.save("tests/load_those_clustering_settings.yaml");
+    /// let settings = ClusteringSettings::new_from_file("tests/load_those_clustering_settings.yaml");
+    /// # std::fs::remove_file("tests/load_those_clustering_settings.yaml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold clustering settings, this function will panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot read settings file.");
+        serde_yaml::from_slice(&buf).expect("Cannot deserialize settings file.")
+    }
+
+    /// Save the current settings to a file for later use
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// let settings = ClusteringSettings::default();
+    /// settings.save("tests/save_those_clustering_settings.yaml");
+    /// # std::fs::remove_file("tests/save_those_clustering_settings.yaml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the settings cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = serde_yaml::to_string(&self).expect("Cannot serialize settings.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(serial.as_ref()))
+            .expect("Cannot write settings to file.");
+    }
+
+    /// Specify algorithms that shouldn't be included in comparison
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// use automl::settings::ClusteringAlgorithm;
+    /// let settings = ClusteringSettings::default().skip(ClusteringAlgorithm::DBSCAN);
+    /// ```
+    #[must_use]
+    pub fn skip(mut self, skip: ClusteringAlgorithm) -> Self {
+        self.skiplist.push(skip);
+        self
+    }
+
+    /// Specify only one algorithm to train
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// use automl::settings::ClusteringAlgorithm;
+    /// let settings = ClusteringSettings::default().only(ClusteringAlgorithm::KMeans);
+    /// ```
+    #[must_use]
+    pub fn only(mut self, only: ClusteringAlgorithm) -> Self {
+        self.skiplist = vec![
+            ClusteringAlgorithm::KMeans,
+            ClusteringAlgorithm::DBSCAN,
+            ClusteringAlgorithm::Hierarchical,
+        ];
+        self.skiplist.retain(|&algorithm| algorithm != only);
+        self
+    }
+
+    /// Adds a specific sorting metric to the settings
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// use automl::settings::ClusteringMetric;
+    /// let settings = ClusteringSettings::default().sorted_by(ClusteringMetric::DaviesBouldin);
+    /// ```
+    #[must_use]
+    pub const fn sorted_by(mut self, sort_by: ClusteringMetric) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    /// Specify the numbers of clusters to try with k-means and hierarchical clustering
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// let settings = ClusteringSettings::default().with_k_range(2..=5);
+    /// ```
+    #[must_use]
+    pub const fn with_k_range(mut self, k_range: RangeInclusive<usize>) -> Self {
+        self.k_range = k_range;
+        self
+    }
+
+    /// Specify settings for k-means
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// use automl::settings::KMeansParameters;
+    /// let settings = ClusteringSettings::default()
+    ///     .with_kmeans_settings(KMeansParameters::default().with_max_iter(200));
+    /// ```
+    #[must_use]
+    pub const fn with_kmeans_settings(mut self, settings: KMeansParameters) -> Self {
+        self.kmeans_settings = settings;
+        self
+    }
+
+    /// Specify settings for DBSCAN
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// use automl::settings::DBSCANParameters;
+    /// let settings = ClusteringSettings::default().with_dbscan_settings(
+    ///     DBSCANParameters::default()
+    ///         .with_eps(0.3)
+    ///         .with_min_samples(10),
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_dbscan_settings(mut self, settings: DBSCANParameters) -> Self {
+        self.dbscan_settings = settings;
+        self
+    }
+
+    /// Specify settings for hierarchical clustering
+    /// ```
+    /// # use automl::ClusteringSettings;
+    /// use automl::settings::{HierarchicalParameters, Linkage};
+    /// let settings = ClusteringSettings::default().with_hierarchical_settings(
+    ///     HierarchicalParameters::default().with_linkage(Linkage::Average),
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_hierarchical_settings(mut self, settings: HierarchicalParameters) -> Self {
+        self.hierarchical_settings = settings;
+        self
+    }
+}
+
+impl Display for ClusteringSettings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        let skiplist = if self.skiplist.is_empty() {
+            "None".to_string()
+        } else {
+            self.skiplist
+                .iter()
+                .map(|algorithm| format!("{algorithm}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec![
+                Cell::new("Settings").add_attribute(Attribute::Bold),
+                Cell::new("Value").add_attribute(Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("General").add_attribute(Attribute::Italic)])
+            .add_row(vec!["    Sorting Metric", &*format!("{}", self.sort_by)])
+            .add_row(vec![
+                "    Numbers of Clusters",
+                &*format!("{} to {}", self.k_range.start(), self.k_range.end()),
+            ])
+            .add_row(vec!["    Skipped Algorithms", &skiplist]);
+        if !self.skiplist.contains(&ClusteringAlgorithm::KMeans) {
+            table
+                .add_row(vec![
+                    Cell::new(ClusteringAlgorithm::KMeans).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", self.kmeans_settings.max_iter),
+                ]);
+        }
+        if !self.skiplist.contains(&ClusteringAlgorithm::DBSCAN) {
+            table
+                .add_row(vec![
+                    Cell::new(ClusteringAlgorithm::DBSCAN).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Radius",
+                    &*format!("{}", self.dbscan_settings.eps),
+                ])
+                .add_row(vec![
+                    "    Minimum Samples",
+                    &*format!("{}", self.dbscan_settings.min_samples),
+                ]);
+        }
+        if !self.skiplist.contains(&ClusteringAlgorithm::Hierarchical) {
+            table
+                .add_row(vec![
+                    Cell::new(ClusteringAlgorithm::Hierarchical).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Linkage",
+                    &*format!("{}", self.hierarchical_settings.linkage),
+                ])
+                .add_row(vec![
+                    "    Maximum Samples",
+                    &*format!("{}", self.hierarchical_settings.max_samples),
+                ]);
+        }
+        writeln!(f, "{table}")
+    }
+}
@@ -0,0 +1,38 @@
+//! DBSCAN clustering parameters
+
+/// Parameters for DBSCAN, which grows clusters from rows with enough neighbors within a radius
+/// and marks rows that belong to no cluster as noise.
+///
+/// The radius is a Euclidean distance, so it depends on the scale of the features.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct DBSCANParameters {
+    /// The radius of the neighborhood of a row
+    pub(crate) eps: f32,
+    /// The number of rows in a neighborhood, including the row itself, for a row to be a core row
+    pub(crate) min_samples: usize,
+}
+
+impl DBSCANParameters {
+    /// Define the radius of the neighborhood of a row
+    #[must_use]
+    pub const fn with_eps(mut self, eps: f32) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Define the number of rows in a neighborhood for a row to be a core row
+    #[must_use]
+    pub const fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+}
+
+impl Default for DBSCANParameters {
+    fn default() -> Self {
+        Self {
+            eps: 0.5,
+            min_samples: 5,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Dummy classifier parameters
+
+use std::fmt::{Display, Formatter};
+
+/// Strategies for the predictions of the dummy classifier
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum DummyClassifierStrategy {
+    /// Always predict the most frequent class in the training data
+    MostFrequent,
+    /// Predict random classes drawn with the class frequencies of the training data
+    Stratified,
+    /// Always predict the class with the largest prior, taking class weights into account
+    Prior,
+}
+
+impl Display for DummyClassifierStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MostFrequent => write!(f, "Most Frequent"),
+            Self::Stratified => write!(f, "Stratified"),
+            Self::Prior => write!(f, "Prior"),
+        }
+    }
+}
+
+/// Parameters for the dummy classifier, a baseline that ignores the features
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct DummyClassifierParameters {
+    /// How the classes are predicted
+    pub(crate) strategy: DummyClassifierStrategy,
+}
+
+impl DummyClassifierParameters {
+    /// Define how the classes are predicted
+    #[must_use]
+    pub const fn with_strategy(mut self, strategy: DummyClassifierStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+impl Default for DummyClassifierParameters {
+    fn default() -> Self {
+        Self {
+            strategy: DummyClassifierStrategy::MostFrequent,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Dummy regressor parameters
+
+use std::fmt::{Display, Formatter};
+
+/// Strategies for the constant prediction of the dummy regressor
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum DummyRegressorStrategy {
+    /// Predict the mean of the training targets
+    Mean,
+    /// Predict the median of the training targets
+    Median,
+    /// Predict the given quantile (between 0 and 1) of the training targets
+    Quantile(f32),
+}
+
+impl Display for DummyRegressorStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mean => write!(f, "Mean"),
+            Self::Median => write!(f, "Median"),
+            Self::Quantile(quantile) => write!(f, "Quantile ({quantile})"),
+        }
+    }
+}
+
+/// Parameters for the dummy regressor, a baseline that ignores the features
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct DummyRegressorParameters {
+    /// The constant to predict
+    pub(crate) strategy: DummyRegressorStrategy,
+}
+
+impl DummyRegressorParameters {
+    /// Define the constant to predict
+    #[must_use]
+    pub const fn with_strategy(mut self, strategy: DummyRegressorStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+impl Default for DummyRegressorParameters {
+    fn default() -> Self {
+        Self {
+            strategy: DummyRegressorStrategy::Mean,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Extra trees regressor parameters
+
+/// Parameters for extremely randomized trees regression. Unlike a random forest, each tree is fit
+/// to the full training set and split thresholds are drawn at random instead of optimized.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct ExtraTreesRegressorParameters {
+    /// The number of trees in the ensemble
+    pub(crate) n_trees: u16,
+    /// The maximum depth of each tree
+    pub(crate) max_depth: Option<u16>,
+    /// The minimum number of rows in each leaf
+    pub(crate) min_samples_leaf: usize,
+    /// The minimum number of rows needed to split a node
+    pub(crate) min_samples_split: usize,
+    /// The number of features considered at each split, defaulting to one third of the features
+    pub(crate) m: Option<usize>,
+}
+
+impl ExtraTreesRegressorParameters {
+    /// Define the number of trees in the ensemble
+    #[must_use]
+    pub const fn with_n_trees(mut self, n_trees: u16) -> Self {
+        self.n_trees = n_trees;
+        self
+    }
+
+    /// Define the maximum depth of each tree
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: u16) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Define the minimum number of rows in each leaf
+    #[must_use]
+    pub const fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Self {
+        self.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    /// Define the minimum number of rows needed to split a node
+    #[must_use]
+    pub const fn with_min_samples_split(mut self, min_samples_split: usize) -> Self {
+        self.min_samples_split = min_samples_split;
+        self
+    }
+
+    /// Define the number of features considered at each split
+    #[must_use]
+    pub const fn with_m(mut self, m: usize) -> Self {
+        self.m = Some(m);
+        self
+    }
+}
+
+impl Default for ExtraTreesRegressorParameters {
+    fn default() -> Self {
+        Self {
+            n_trees: 100,
+            max_depth: None,
+            min_samples_leaf: 1,
+            min_samples_split: 2,
+            m: None,
+        }
+    }
+}
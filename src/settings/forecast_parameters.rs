@@ -0,0 +1,98 @@
+//! Forecasting feature parameters
+
+use std::fmt::{Display, Formatter};
+
+/// Parameters for the features a `ForecastModel` builds from the history of the target.
+///
+/// Every feature of a time step only uses the target at earlier time steps, so the first rows of
+/// the series, which lack the history the features need, are only used as history.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct ForecastParameters {
+    /// How many steps back to take the target from, one feature per lag
+    pub(crate) lags: Vec<usize>,
+    /// The lengths of the windows to average the most recent targets over, one feature per window
+    pub(crate) rolling_windows: Vec<usize>,
+    /// The number of most recent changes between consecutive targets, one feature per change
+    pub(crate) deltas: usize,
+}
+
+impl ForecastParameters {
+    /// Define how many steps back to take the target from
+    #[must_use]
+    pub fn with_lags(mut self, lags: Vec<usize>) -> Self {
+        self.lags = lags;
+        self
+    }
+
+    /// Define the lengths of the windows to average the most recent targets over
+    #[must_use]
+    pub fn with_rolling_windows(mut self, rolling_windows: Vec<usize>) -> Self {
+        self.rolling_windows = rolling_windows;
+        self
+    }
+
+    /// Define the number of most recent changes between consecutive targets
+    #[must_use]
+    pub const fn with_deltas(mut self, deltas: usize) -> Self {
+        self.deltas = deltas;
+        self
+    }
+
+    /// The number of earlier time steps the features of a time step need
+    pub(crate) fn history(&self) -> usize {
+        self.lags
+            .iter()
+            .chain(&self.rolling_windows)
+            .copied()
+            .chain(std::iter::once(self.deltas + 1))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The features of the next time step from the targets up to it, oldest first
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn features(&self, history: &[f32]) -> Vec<f32> {
+        let n = history.len();
+        let mut features: Vec<f32> = self.lags.iter().map(|&lag| history[n - lag]).collect();
+        features.extend(
+            self.rolling_windows
+                .iter()
+                .map(|&window| history[n - window..].iter().sum::<f32>() / window as f32),
+        );
+        features.extend((1..=self.deltas).map(|delta| history[n - delta] - history[n - delta - 1]));
+        features
+    }
+}
+
+impl Default for ForecastParameters {
+    fn default() -> Self {
+        Self {
+            lags: vec![1, 2, 3, 7],
+            rolling_windows: vec![3, 7],
+            deltas: 1,
+        }
+    }
+}
+
+impl Display for ForecastParameters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let list = |values: &[usize]| {
+            if values.is_empty() {
+                "None".to_string()
+            } else {
+                values
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        };
+        write!(
+            f,
+            "lags = {}\nrolling means = {}\ndeltas = {}",
+            list(&self.lags),
+            list(&self.rolling_windows),
+            self.deltas
+        )
+    }
+}
@@ -0,0 +1,112 @@
+//! Gaussian process regressor parameters
+
+use std::fmt::{Display, Formatter};
+
+/// Covariance functions for Gaussian process regression. Each is scaled by a signal variance,
+/// and the stationary kernels depend on the distance between (standardized) inputs through a
+/// length scale.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum GaussianProcessKernel {
+    /// Radial basis function (squared exponential) kernel, for very smooth functions
+    RBF,
+    /// Matérn kernel, whose smoothness is set by `nu`: 0.5 (exponential), 1.5 or 2.5
+    Matern {
+        /// The smoothness of the kernel, one of 0.5, 1.5 or 2.5
+        nu: f32,
+    },
+    /// Linear (dot product) kernel, equivalent to Bayesian linear regression
+    Linear,
+}
+
+impl Display for GaussianProcessKernel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RBF => write!(f, "RBF"),
+            Self::Matern { nu } => write!(f, "Matérn (nu = {nu})"),
+            Self::Linear => write!(f, "Linear"),
+        }
+    }
+}
+
+/// Parameters for Gaussian process regression.
+///
+/// The inputs and targets are standardized before fitting, and the kernel hyperparameters and
+/// noise variance are tuned by maximizing the log marginal likelihood, starting from the values
+/// given here.
+///
+/// Fitting takes time and memory that grow with the cube and square of the number of rows, so the
+/// algorithm is only a candidate for datasets with at most `max_samples` rows.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GaussianProcessRegressorParameters {
+    /// The covariance function
+    pub(crate) kernel: GaussianProcessKernel,
+    /// The initial length scale of the stationary kernels
+    pub(crate) length_scale: f32,
+    /// The initial signal variance of the kernel
+    pub(crate) signal_variance: f32,
+    /// The initial variance of the observation noise
+    pub(crate) noise: f32,
+    /// The maximum number of steps taken to maximize the marginal likelihood, zero to keep the
+    /// initial hyperparameters
+    pub(crate) max_iter: usize,
+    /// The largest number of training rows for which the algorithm is a candidate
+    pub(crate) max_samples: usize,
+}
+
+impl GaussianProcessRegressorParameters {
+    /// Define the covariance function
+    #[must_use]
+    pub const fn with_kernel(mut self, kernel: GaussianProcessKernel) -> Self {
+        self.kernel = kernel;
+        self
+    }
+
+    /// Define the initial length scale of the stationary kernels
+    #[must_use]
+    pub const fn with_length_scale(mut self, length_scale: f32) -> Self {
+        self.length_scale = length_scale;
+        self
+    }
+
+    /// Define the initial signal variance of the kernel
+    #[must_use]
+    pub const fn with_signal_variance(mut self, signal_variance: f32) -> Self {
+        self.signal_variance = signal_variance;
+        self
+    }
+
+    /// Define the initial variance of the observation noise
+    #[must_use]
+    pub const fn with_noise(mut self, noise: f32) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Define the maximum number of steps taken to maximize the marginal likelihood, zero to keep
+    /// the initial hyperparameters
+    #[must_use]
+    pub const fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the largest number of training rows for which the algorithm is a candidate
+    #[must_use]
+    pub const fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+}
+
+impl Default for GaussianProcessRegressorParameters {
+    fn default() -> Self {
+        Self {
+            kernel: GaussianProcessKernel::RBF,
+            length_scale: 1.0,
+            signal_variance: 1.0,
+            noise: 0.1,
+            max_iter: 50,
+            max_samples: 300,
+        }
+    }
+}
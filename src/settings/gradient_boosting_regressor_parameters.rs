@@ -0,0 +1,109 @@
+//! Gradient boosting regressor parameters
+
+use std::fmt::{Display, Formatter};
+
+/// Loss functions for gradient boosting regression
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum GradientBoostingLoss {
+    /// Squared error
+    SquaredError,
+    /// Absolute error, which is less sensitive to outliers
+    AbsoluteError,
+}
+
+impl Display for GradientBoostingLoss {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SquaredError => write!(f, "Squared Error"),
+            Self::AbsoluteError => write!(f, "Absolute Error"),
+        }
+    }
+}
+
+/// Parameters for gradient boosting regression
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GradientBoostingRegressorParameters {
+    /// The loss function to minimize
+    pub(crate) loss: GradientBoostingLoss,
+    /// The maximum number of boosting iterations
+    pub(crate) n_estimators: usize,
+    /// The shrinkage applied to each tree
+    pub(crate) learning_rate: f32,
+    /// The maximum depth of each tree
+    pub(crate) max_depth: u16,
+    /// The minimum number of rows in each leaf
+    pub(crate) min_samples_leaf: usize,
+    /// The fraction of rows used to fit each tree
+    pub(crate) subsample: f32,
+    /// The fraction of rows set aside to decide when to stop early
+    pub(crate) validation_fraction: f32,
+    /// Stop once the validation loss has not improved for this many iterations
+    pub(crate) early_stopping_rounds: Option<usize>,
+}
+
+impl GradientBoostingRegressorParameters {
+    /// Define the loss function to minimize
+    #[must_use]
+    pub const fn with_loss(mut self, loss: GradientBoostingLoss) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    /// Define the maximum number of boosting iterations
+    #[must_use]
+    pub const fn with_n_estimators(mut self, n_estimators: usize) -> Self {
+        self.n_estimators = n_estimators;
+        self
+    }
+
+    /// Define the shrinkage applied to each tree
+    #[must_use]
+    pub const fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Define the maximum depth of each tree
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: u16) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Define the minimum number of rows in each leaf
+    #[must_use]
+    pub const fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Self {
+        self.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    /// Define the fraction of rows used to fit each tree
+    #[must_use]
+    pub const fn with_subsample(mut self, subsample: f32) -> Self {
+        self.subsample = subsample;
+        self
+    }
+
+    /// Stop early once the loss on a validation split has not improved for `rounds` iterations
+    #[must_use]
+    pub const fn with_early_stopping(mut self, rounds: usize, validation_fraction: f32) -> Self {
+        self.early_stopping_rounds = Some(rounds);
+        self.validation_fraction = validation_fraction;
+        self
+    }
+}
+
+impl Default for GradientBoostingRegressorParameters {
+    fn default() -> Self {
+        Self {
+            loss: GradientBoostingLoss::SquaredError,
+            n_estimators: 100,
+            learning_rate: 0.1,
+            max_depth: 3,
+            min_samples_leaf: 1,
+            subsample: 1.0,
+            validation_fraction: 0.1,
+            early_stopping_rounds: None,
+        }
+    }
+}
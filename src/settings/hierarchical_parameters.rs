@@ -0,0 +1,38 @@
+//! Hierarchical clustering parameters
+
+use super::Linkage;
+
+/// Parameters for agglomerative hierarchical clustering, which repeatedly merges the closest pair
+/// of clusters and is cut at each number of clusters in the range of k.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct HierarchicalParameters {
+    /// The criterion for the distance between clusters
+    pub(crate) linkage: Linkage,
+    /// The largest number of rows to cluster, since the pairwise distances take quadratic memory
+    pub(crate) max_samples: usize,
+}
+
+impl HierarchicalParameters {
+    /// Define the criterion for the distance between clusters
+    #[must_use]
+    pub const fn with_linkage(mut self, linkage: Linkage) -> Self {
+        self.linkage = linkage;
+        self
+    }
+
+    /// Define the largest number of rows to cluster
+    #[must_use]
+    pub const fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+}
+
+impl Default for HierarchicalParameters {
+    fn default() -> Self {
+        Self {
+            linkage: Linkage::Ward,
+            max_samples: 2000,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Huber regression parameters
+
+/// Parameters for Huber regression, a linear model that is robust to outliers.
+///
+/// The loss is quadratic for residuals within `epsilon` robust standard deviations and linear
+/// beyond, so outliers pull on the fit less than with squared error.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct HuberParameters {
+    /// The number of robust standard deviations where the loss turns linear
+    pub(crate) epsilon: f32,
+    /// The strength of the L2 penalty on the standardized coefficients
+    pub(crate) alpha: f32,
+    /// The maximum number of reweighting iterations
+    pub(crate) max_iter: usize,
+    /// Stop once no coefficient changes by more than this relative amount
+    pub(crate) tol: f32,
+}
+
+impl HuberParameters {
+    /// Define the number of robust standard deviations where the loss turns linear
+    #[must_use]
+    pub const fn with_epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Define the strength of the L2 penalty on the standardized coefficients
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the maximum number of reweighting iterations
+    #[must_use]
+    pub const fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the relative change in coefficients below which fitting stops
+    #[must_use]
+    pub const fn with_tol(mut self, tol: f32) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for HuberParameters {
+    fn default() -> Self {
+        Self {
+            epsilon: 1.35,
+            alpha: 0.0001,
+            max_iter: 100,
+            tol: 1e-5,
+        }
+    }
+}
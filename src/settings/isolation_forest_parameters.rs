@@ -0,0 +1,36 @@
+//! Isolation forest parameters
+
+/// Parameters for an isolation forest, which scores rows by how few random splits it takes to
+/// isolate them from the rest of the data.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct IsolationForestParameters {
+    /// The number of isolation trees in the forest
+    pub(crate) n_trees: usize,
+    /// The number of rows drawn without replacement to grow each tree
+    pub(crate) max_samples: usize,
+}
+
+impl IsolationForestParameters {
+    /// Define the number of isolation trees in the forest
+    #[must_use]
+    pub const fn with_n_trees(mut self, n_trees: usize) -> Self {
+        self.n_trees = n_trees;
+        self
+    }
+
+    /// Define the number of rows drawn to grow each tree
+    #[must_use]
+    pub const fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+}
+
+impl Default for IsolationForestParameters {
+    fn default() -> Self {
+        Self {
+            n_trees: 100,
+            max_samples: 256,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! K-means clustering parameters
+
+/// Parameters for k-means clustering. The number of clusters comes from the range of k in the
+/// clustering settings.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct KMeansParameters {
+    /// The maximum number of iterations of a single run
+    pub(crate) max_iter: usize,
+}
+
+impl KMeansParameters {
+    /// Define the maximum number of iterations of a single run
+    #[must_use]
+    pub const fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+}
+
+impl Default for KMeansParameters {
+    fn default() -> Self {
+        Self { max_iter: 100 }
+    }
+}
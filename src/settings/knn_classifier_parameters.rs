@@ -4,7 +4,7 @@ use crate::utils::Distance;
 pub use smartcore::{algorithm::neighbour::KNNAlgorithmName, neighbors::KNNWeightFunction};
 
 /// Parameters for k-nearest neighbors (KNN) classification
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct KNNClassifierParameters {
     /// Number of nearest neighbors to use
     pub(crate) k: usize,
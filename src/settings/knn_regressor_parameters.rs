@@ -4,7 +4,7 @@ use crate::utils::Distance;
 pub use smartcore::{algorithm::neighbour::KNNAlgorithmName, neighbors::KNNWeightFunction};
 
 /// Parameters for k-nearest neighbor (KNN) regression
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct KNNRegressorParameters {
     /// Number of nearest neighbors to use
     pub(crate) k: usize,
@@ -0,0 +1,49 @@
+//! Local outlier factor parameters
+
+use crate::utils::Distance;
+pub use smartcore::algorithm::neighbour::KNNAlgorithmName;
+
+/// Parameters for the local outlier factor, which scores rows by how much sparser their
+/// neighborhood is than the neighborhoods of their nearest neighbors.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct LocalOutlierFactorParameters {
+    /// Number of nearest neighbors to use
+    pub(crate) k: usize,
+    /// Search algorithm to use to find the nearest neighbors
+    pub(crate) algorithm: KNNAlgorithmName,
+    /// Distance metric to use to find the nearest neighbors
+    pub(crate) distance: Distance,
+}
+
+impl LocalOutlierFactorParameters {
+    /// Define the number of nearest neighbors to use
+    #[must_use]
+    pub const fn with_k(mut self, k: usize) -> Self {
+        self.k = k;
+        self
+    }
+
+    /// Define the search algorithm to use to find the nearest neighbors
+    #[must_use]
+    pub const fn with_algorithm(mut self, algorithm: KNNAlgorithmName) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Define the distance metric to use to find the nearest neighbors
+    #[must_use]
+    pub const fn with_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+}
+
+impl Default for LocalOutlierFactorParameters {
+    fn default() -> Self {
+        Self {
+            k: 20,
+            algorithm: KNNAlgorithmName::CoverTree,
+            distance: Distance::Euclidean,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Multilayer perceptron classifier parameters
+
+use super::{Activation, Optimizer};
+
+/// Parameters for multilayer perceptron classification. The inputs are standardized before
+/// training, and the output layer is a softmax with a cross-entropy loss.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct MLPClassifierParameters {
+    /// The number of neurons in each hidden layer
+    pub(crate) hidden_layer_sizes: Vec<usize>,
+    /// The activation function of the hidden layers
+    pub(crate) activation: Activation,
+    /// The optimizer used to update the weights
+    pub(crate) optimizer: Optimizer,
+    /// The step size of the optimizer
+    pub(crate) learning_rate: f32,
+    /// The strength of the L2 penalty on the weights
+    pub(crate) alpha: f32,
+    /// The number of rows in each minibatch
+    pub(crate) batch_size: usize,
+    /// The maximum number of passes over the training data
+    pub(crate) max_epochs: usize,
+    /// The fraction of rows set aside to decide when to stop early
+    pub(crate) validation_fraction: f32,
+    /// Stop once the validation loss has not improved for this many epochs
+    pub(crate) early_stopping_rounds: Option<usize>,
+}
+
+impl MLPClassifierParameters {
+    /// Define the number of neurons in each hidden layer
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_hidden_layer_sizes(mut self, hidden_layer_sizes: Vec<usize>) -> Self {
+        self.hidden_layer_sizes = hidden_layer_sizes;
+        self
+    }
+
+    /// Define the activation function of the hidden layers
+    #[must_use]
+    pub const fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    /// Define the optimizer used to update the weights
+    #[must_use]
+    pub const fn with_optimizer(mut self, optimizer: Optimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
+    /// Define the step size of the optimizer
+    #[must_use]
+    pub const fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Define the strength of the L2 penalty on the weights
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the number of rows in each minibatch
+    #[must_use]
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Define the maximum number of passes over the training data
+    #[must_use]
+    pub const fn with_max_epochs(mut self, max_epochs: usize) -> Self {
+        self.max_epochs = max_epochs;
+        self
+    }
+
+    /// Stop early once the loss on a validation split has not improved for `rounds` epochs
+    #[must_use]
+    pub const fn with_early_stopping(mut self, rounds: usize, validation_fraction: f32) -> Self {
+        self.early_stopping_rounds = Some(rounds);
+        self.validation_fraction = validation_fraction;
+        self
+    }
+}
+
+impl Default for MLPClassifierParameters {
+    fn default() -> Self {
+        Self {
+            hidden_layer_sizes: vec![100],
+            activation: Activation::ReLU,
+            optimizer: Optimizer::default_adam(),
+            learning_rate: 0.001,
+            alpha: 0.0001,
+            batch_size: 200,
+            max_epochs: 200,
+            validation_fraction: 0.1,
+            early_stopping_rounds: None,
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Multilayer perceptron regressor parameters
+
+use std::fmt::{Display, Formatter};
+
+/// Activation functions for the hidden layers of a multilayer perceptron
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Activation {
+    /// No activation, f(x) = x
+    Identity,
+    /// The logistic sigmoid, f(x) = 1 / (1 + exp(-x))
+    Logistic,
+    /// The hyperbolic tangent, f(x) = tanh(x)
+    Tanh,
+    /// The rectified linear unit, f(x) = max(0, x)
+    ReLU,
+}
+
+impl Display for Activation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Identity => write!(f, "Identity"),
+            Self::Logistic => write!(f, "Logistic"),
+            Self::Tanh => write!(f, "Tanh"),
+            Self::ReLU => write!(f, "ReLU"),
+        }
+    }
+}
+
+/// Optimizers for training a multilayer perceptron
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Optimizer {
+    /// Stochastic gradient descent with momentum
+    SGD {
+        /// The fraction of the previous update carried into the next one
+        momentum: f32,
+    },
+    /// Adam, as described by Kingma and Ba (2014)
+    Adam {
+        /// The decay rate of the first moment estimates
+        beta_1: f32,
+        /// The decay rate of the second moment estimates
+        beta_2: f32,
+    },
+}
+
+impl Optimizer {
+    /// Stochastic gradient descent with a momentum of 0.9
+    #[must_use]
+    pub const fn default_sgd() -> Self {
+        Self::SGD { momentum: 0.9 }
+    }
+
+    /// Adam with the decay rates suggested by Kingma and Ba (2014)
+    #[must_use]
+    pub const fn default_adam() -> Self {
+        Self::Adam {
+            beta_1: 0.9,
+            beta_2: 0.999,
+        }
+    }
+}
+
+impl Display for Optimizer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SGD { momentum } => write!(f, "SGD (momentum = {momentum})"),
+            Self::Adam { beta_1, beta_2 } => {
+                write!(f, "Adam (beta_1 = {beta_1}, beta_2 = {beta_2})")
+            }
+        }
+    }
+}
+
+/// Parameters for multilayer perceptron regression. The inputs and targets are standardized
+/// before training, and the output layer is linear with a squared error loss.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct MLPRegressorParameters {
+    /// The number of neurons in each hidden layer
+    pub(crate) hidden_layer_sizes: Vec<usize>,
+    /// The activation function of the hidden layers
+    pub(crate) activation: Activation,
+    /// The optimizer used to update the weights
+    pub(crate) optimizer: Optimizer,
+    /// The step size of the optimizer
+    pub(crate) learning_rate: f32,
+    /// The strength of the L2 penalty on the weights
+    pub(crate) alpha: f32,
+    /// The number of rows in each minibatch
+    pub(crate) batch_size: usize,
+    /// The maximum number of passes over the training data
+    pub(crate) max_epochs: usize,
+    /// The fraction of rows set aside to decide when to stop early
+    pub(crate) validation_fraction: f32,
+    /// Stop once the validation loss has not improved for this many epochs
+    pub(crate) early_stopping_rounds: Option<usize>,
+}
+
+impl MLPRegressorParameters {
+    /// Define the number of neurons in each hidden layer
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_hidden_layer_sizes(mut self, hidden_layer_sizes: Vec<usize>) -> Self {
+        self.hidden_layer_sizes = hidden_layer_sizes;
+        self
+    }
+
+    /// Define the activation function of the hidden layers
+    #[must_use]
+    pub const fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    /// Define the optimizer used to update the weights
+    #[must_use]
+    pub const fn with_optimizer(mut self, optimizer: Optimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
+    /// Define the step size of the optimizer
+    #[must_use]
+    pub const fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Define the strength of the L2 penalty on the weights
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the number of rows in each minibatch
+    #[must_use]
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Define the maximum number of passes over the training data
+    #[must_use]
+    pub const fn with_max_epochs(mut self, max_epochs: usize) -> Self {
+        self.max_epochs = max_epochs;
+        self
+    }
+
+    /// Stop early once the loss on a validation split has not improved for `rounds` epochs
+    #[must_use]
+    pub const fn with_early_stopping(mut self, rounds: usize, validation_fraction: f32) -> Self {
+        self.early_stopping_rounds = Some(rounds);
+        self.validation_fraction = validation_fraction;
+        self
+    }
+}
+
+impl Default for MLPRegressorParameters {
+    fn default() -> Self {
+        Self {
+            hidden_layer_sizes: vec![100],
+            activation: Activation::ReLU,
+            optimizer: Optimizer::default_adam(),
+            learning_rate: 0.001,
+            alpha: 0.0001,
+            batch_size: 200,
+            max_epochs: 200,
+            validation_fraction: 0.1,
+            early_stopping_rounds: None,
+        }
+    }
+}
@@ -72,16 +72,19 @@
 //! ## Complete classification customization
 //! ```
 //! use automl::settings::{
-//!     Algorithm, CategoricalNBParameters, DecisionTreeClassifierParameters, Distance,
-//!     GaussianNBParameters, KNNAlgorithmName, KNNClassifierParameters, KNNWeightFunction, Kernel,
-//!     LogisticRegressionParameters, LogisticRegressionSolverName, Metric,
-//!     RandomForestClassifierParameters, SVCParameters,
+//!     Algorithm, BernoulliNBParameters, CategoricalNBParameters, ClassWeight,
+//!     DecisionTreeClassifierParameters, Distance, GaussianNBParameters, KNNAlgorithmName,
+//!     KNNClassifierParameters, KNNWeightFunction, Kernel, LogisticRegressionParameters,
+//!     LogisticRegressionSolverName, Metric, MultinomialNBParameters,
+//!     RandomForestClassifierParameters, Resampling, SVCParameters,
 //! };
 //!
 //! let settings = automl::Settings::default_classification()
 //!     .with_number_of_folds(3)
 //!     .shuffle_data(true)
 //!     .verbose(true)
+//!     .with_resampling(Resampling::SMOTE { k_neighbors: 5 })
+//!     .with_class_weight(ClassWeight::Balanced)
 //!     .skip(Algorithm::RandomForestClassifier)
 //!     .sorted_by(Metric::Accuracy)
 //!     .with_random_forest_classifier_settings(
@@ -118,7 +121,9 @@
 //!             .with_weight(KNNWeightFunction::Uniform),
 //!     )
 //!     .with_gaussian_nb_settings(GaussianNBParameters::default().with_priors(vec![1.0, 1.0]))
-//!     .with_categorical_nb_settings(CategoricalNBParameters::default().with_alpha(1.0));
+//!     .with_categorical_nb_settings(CategoricalNBParameters::default().with_alpha(1.0))
+//!     .with_bernoulli_nb_settings(BernoulliNBParameters::default().with_binarize(0.5))
+//!     .with_multinomial_nb_settings(MultinomialNBParameters::default().with_alpha(0.5));
 //! ```
 
 pub use crate::utils::{Distance, Kernel};
@@ -159,6 +164,12 @@ pub use smartcore::naive_bayes::gaussian::GaussianNBParameters;
 /// Parameters for categorical naive bayes (re-export from [Smartcore](https://docs.rs/smartcore/))
 pub use smartcore::naive_bayes::categorical::CategoricalNBParameters;
 
+/// Parameters for Bernoulli naive bayes (re-export from [Smartcore](https://docs.rs/smartcore/))
+pub use smartcore::naive_bayes::bernoulli::BernoulliNBParameters;
+
+/// Parameters for multinomial naive bayes (re-export from [Smartcore](https://docs.rs/smartcore/))
+pub use smartcore::naive_bayes::multinomial::MultinomialNBParameters;
+
 /// Parameters for random forest classification (re-export from [Smartcore](https://docs.rs/smartcore/))
 pub use smartcore::ensemble::random_forest_classifier::RandomForestClassifierParameters;
 
@@ -181,27 +192,117 @@ mod knn_classifier_parameters;
 pub use knn_classifier_parameters::KNNClassifierParameters;
 
 mod svc_parameters;
-pub use svc_parameters::SVCParameters;
+pub use svc_parameters::{MulticlassStrategy, SVCParameters};
+
+mod gradient_boosting_regressor_parameters;
+pub use gradient_boosting_regressor_parameters::{
+    GradientBoostingLoss, GradientBoostingRegressorParameters,
+};
+
+mod gradient_boosting_classifier_parameters;
+pub use gradient_boosting_classifier_parameters::GradientBoostingClassifierParameters;
+
+mod extra_trees_regressor_parameters;
+pub use extra_trees_regressor_parameters::ExtraTreesRegressorParameters;
+
+mod extra_trees_classifier_parameters;
+pub use extra_trees_classifier_parameters::ExtraTreesClassifierParameters;
+
+mod mlp_regressor_parameters;
+pub use mlp_regressor_parameters::{Activation, MLPRegressorParameters, Optimizer};
+
+mod mlp_classifier_parameters;
+pub use mlp_classifier_parameters::MLPClassifierParameters;
+
+mod huber_parameters;
+pub use huber_parameters::HuberParameters;
+
+mod quantile_parameters;
+pub use quantile_parameters::QuantileParameters;
+
+mod poisson_parameters;
+pub use poisson_parameters::PoissonParameters;
+
+mod gamma_parameters;
+pub use gamma_parameters::GammaParameters;
+
+mod tweedie_parameters;
+pub use tweedie_parameters::TweedieParameters;
+
+mod gaussian_process_regressor_parameters;
+pub use gaussian_process_regressor_parameters::{
+    GaussianProcessKernel, GaussianProcessRegressorParameters,
+};
+
+mod dummy_regressor_parameters;
+pub use dummy_regressor_parameters::{DummyRegressorParameters, DummyRegressorStrategy};
+
+mod dummy_classifier_parameters;
+pub use dummy_classifier_parameters::{DummyClassifierParameters, DummyClassifierStrategy};
+
+mod kmeans_parameters;
+pub use kmeans_parameters::KMeansParameters;
+
+mod dbscan_parameters;
+pub use dbscan_parameters::DBSCANParameters;
+
+mod hierarchical_parameters;
+pub use hierarchical_parameters::HierarchicalParameters;
+
+mod isolation_forest_parameters;
+pub use isolation_forest_parameters::IsolationForestParameters;
+
+mod local_outlier_factor_parameters;
+pub use local_outlier_factor_parameters::LocalOutlierFactorParameters;
 
+mod robust_covariance_parameters;
+pub use robust_covariance_parameters::RobustCovarianceParameters;
+
+mod forecast_parameters;
+pub use forecast_parameters::ForecastParameters;
+
+mod splitter;
+pub(crate) use splitter::Folds;
+pub use splitter::Splitter;
+
+use smartcore::error::Failed;
 use smartcore::linalg::naive::dense_matrix::DenseMatrix;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 use super::algorithms::{
-    CategoricalNaiveBayesClassifierWrapper, DecisionTreeClassifierWrapper,
-    DecisionTreeRegressorWrapper, ElasticNetRegressorWrapper, GaussianNaiveBayesClassifierWrapper,
+    BaggingWrapper, BernoulliNaiveBayesClassifierWrapper, CategoricalNaiveBayesClassifierWrapper,
+    CrossValidation, DecisionTreeClassifierWrapper, DecisionTreeRegressorWrapper,
+    DummyClassifierWrapper, DummyRegressorWrapper, ElasticNetRegressorWrapper,
+    ExtraTreesClassifierWrapper, ExtraTreesRegressorWrapper, GammaRegressorWrapper,
+    GaussianNaiveBayesClassifierWrapper, GaussianProcessRegressorWrapper,
+    GradientBoostingClassifierWrapper, GradientBoostingRegressorWrapper, HuberRegressorWrapper,
     KNNClassifierWrapper, KNNRegressorWrapper, LassoRegressorWrapper, LinearRegressorWrapper,
-    LogisticRegressionWrapper, ModelWrapper, RandomForestClassifierWrapper,
-    RandomForestRegressorWrapper, RidgeRegressorWrapper, SupportVectorClassifierWrapper,
-    SupportVectorRegressorWrapper,
+    LogisticRegressionWrapper, MLPClassifierWrapper, MLPRegressorWrapper, ModelWrapper,
+    MultinomialNaiveBayesClassifierWrapper, PoissonRegressorWrapper, QuantileRegressorWrapper,
+    RandomForestClassifierWrapper, RandomForestRegressorWrapper, RidgeRegressorWrapper,
+    SupportVectorClassifierWrapper, SupportVectorRegressorWrapper, TweedieRegressorWrapper,
+};
+use crate::utils::{
+    average_precision, binary_f1, calinski_harabasz_score, davies_bouldin_score, hamming_loss,
+    macro_f1, micro_f1, roc_auc, silhouette_score, subset_accuracy,
 };
 
 mod settings_struct;
 #[doc(no_inline)]
 pub use settings_struct::Settings;
 
+mod clustering_settings;
+#[doc(no_inline)]
+pub use clustering_settings::ClusteringSettings;
+
+mod anomaly_settings;
+#[doc(no_inline)]
+pub use anomaly_settings::AnomalySettings;
+
 /// Metrics for evaluating algorithms
 #[non_exhaustive]
-#[derive(PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Metric {
     /// Sort by R^2
     RSquared,
@@ -211,6 +312,10 @@ pub enum Metric {
     MeanSquaredError,
     /// Sort by Accuracy
     Accuracy,
+    /// Sort by mean Poisson deviance, for count targets
+    PoissonDeviance,
+    /// Sort by mean Gamma deviance, for positive targets
+    GammaDeviance,
     /// Sort by none
     None,
 }
@@ -222,13 +327,15 @@ impl Display for Metric {
             Self::MeanAbsoluteError => write!(f, "MAE"),
             Self::MeanSquaredError => write!(f, "MSE"),
             Self::Accuracy => write!(f, "Accuracy"),
+            Self::PoissonDeviance => write!(f, "Poisson Deviance"),
+            Self::GammaDeviance => write!(f, "Gamma Deviance"),
             Self::None => panic!("A metric must be set."),
         }
     }
 }
 
 /// Algorithm options
-#[derive(PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Algorithm {
     /// Decision tree regressor
     DecisionTreeRegressor,
@@ -260,11 +367,163 @@ pub enum Algorithm {
     GaussianNaiveBayes,
     /// Categorical Naive Bayes classifier
     CategoricalNaiveBayes,
+    /// Gradient boosting regressor
+    GradientBoostingRegressor,
+    /// Gradient boosting classifier
+    GradientBoostingClassifier,
+    /// Extra trees regressor
+    ExtraTreesRegressor,
+    /// Extra trees classifier
+    ExtraTreesClassifier,
+    /// Bagging ensemble of another algorithm, which fits copies of the base algorithm to bootstrap
+    /// samples of the rows and random subsets of the features, then averages (regression) or votes
+    /// on (classification) their predictions. The base algorithm is trained with its own settings.
+    Bagging {
+        /// The algorithm fit to each sample
+        base: Box<Self>,
+        /// The number of models in the ensemble
+        n_estimators: usize,
+        /// The number of rows drawn (with replacement) for each model, as a fraction of the data
+        max_samples: f32,
+        /// The number of features drawn (without replacement) for each model, as a fraction of
+        /// the features
+        max_features: f32,
+    },
+    /// Baseline regressor that ignores the features
+    DummyRegressor,
+    /// Baseline classifier that ignores the features
+    DummyClassifier,
+    /// Bernoulli Naive Bayes classifier, for binary features
+    BernoulliNaiveBayes,
+    /// Multinomial Naive Bayes classifier, for non-negative count features
+    MultinomialNaiveBayes,
+    /// Multilayer perceptron regressor
+    MLPRegressor,
+    /// Multilayer perceptron classifier
+    MLPClassifier,
+    /// Huber regressor, robust to outliers
+    Huber,
+    /// Quantile regressor
+    Quantile,
+    /// Poisson regressor, for counts
+    Poisson,
+    /// Gamma regressor, for positive targets
+    Gamma,
+    /// Tweedie regressor
+    Tweedie,
+    /// Gaussian process regressor, with predictive uncertainty
+    GaussianProcessRegressor,
 }
 
 impl Algorithm {
+    /// Every algorithm, in the order they are compared
+    pub(crate) const ALL: [Self; 31] = [
+        Self::LogisticRegression,
+        Self::RandomForestClassifier,
+        Self::KNNClassifier,
+        Self::DecisionTreeClassifier,
+        Self::GaussianNaiveBayes,
+        Self::CategoricalNaiveBayes,
+        Self::SVC,
+        Self::Linear,
+        Self::SVR,
+        Self::Lasso,
+        Self::Ridge,
+        Self::ElasticNet,
+        Self::DecisionTreeRegressor,
+        Self::RandomForestRegressor,
+        Self::KNNRegressor,
+        Self::GradientBoostingRegressor,
+        Self::GradientBoostingClassifier,
+        Self::ExtraTreesRegressor,
+        Self::ExtraTreesClassifier,
+        Self::DummyRegressor,
+        Self::DummyClassifier,
+        Self::BernoulliNaiveBayes,
+        Self::MultinomialNaiveBayes,
+        Self::MLPRegressor,
+        Self::MLPClassifier,
+        Self::Huber,
+        Self::Quantile,
+        Self::Poisson,
+        Self::Gamma,
+        Self::Tweedie,
+        Self::GaussianProcessRegressor,
+    ];
+
+    /// A bagging ensemble of 10 models around the base algorithm, each fit to as many rows as the
+    /// data and all of the features
+    /// ```
+    /// use automl::settings::Algorithm;
+    /// let bagged_knn = Algorithm::bagging(Algorithm::KNNRegressor);
+    /// ```
+    #[must_use]
+    pub fn bagging(base: Self) -> Self {
+        Self::Bagging {
+            base: Box::new(base),
+            n_estimators: 10,
+            max_samples: 1.0,
+            max_features: 1.0,
+        }
+    }
+
+    /// Whether the algorithm is a baseline that ignores the features
+    pub(crate) const fn is_baseline(&self) -> bool {
+        matches!(self, Self::DummyRegressor | Self::DummyClassifier)
+    }
+
+    /// Get the `cv_model` method for the underlying algorithm.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn get_cross_validator(
+        &self,
+    ) -> Box<
+        dyn Fn(
+                &DenseMatrix<f32>,
+                &Vec<f32>,
+                &Settings,
+            ) -> Result<(CrossValidation, Self, Duration, Vec<u8>), Failed>
+            + '_,
+    > {
+        Box::new(match self {
+            Self::Linear => LinearRegressorWrapper::cv_model,
+            Self::Lasso => LassoRegressorWrapper::cv_model,
+            Self::Ridge => RidgeRegressorWrapper::cv_model,
+            Self::ElasticNet => ElasticNetRegressorWrapper::cv_model,
+            Self::RandomForestRegressor => RandomForestRegressorWrapper::cv_model,
+            Self::KNNRegressor => KNNRegressorWrapper::cv_model,
+            Self::SVR => SupportVectorRegressorWrapper::cv_model,
+            Self::DecisionTreeRegressor => DecisionTreeRegressorWrapper::cv_model,
+            Self::LogisticRegression => LogisticRegressionWrapper::cv_model,
+            Self::RandomForestClassifier => RandomForestClassifierWrapper::cv_model,
+            Self::DecisionTreeClassifier => DecisionTreeClassifierWrapper::cv_model,
+            Self::KNNClassifier => KNNClassifierWrapper::cv_model,
+            Self::SVC => SupportVectorClassifierWrapper::cv_model,
+            Self::GaussianNaiveBayes => GaussianNaiveBayesClassifierWrapper::cv_model,
+            Self::CategoricalNaiveBayes => CategoricalNaiveBayesClassifierWrapper::cv_model,
+            Self::GradientBoostingRegressor => GradientBoostingRegressorWrapper::cv_model,
+            Self::GradientBoostingClassifier => GradientBoostingClassifierWrapper::cv_model,
+            Self::ExtraTreesRegressor => ExtraTreesRegressorWrapper::cv_model,
+            Self::ExtraTreesClassifier => ExtraTreesClassifierWrapper::cv_model,
+            Self::Bagging { .. } => {
+                return Box::new(|x, y, settings| BaggingWrapper::cv_model(self, x, y, settings))
+            }
+            Self::DummyRegressor => DummyRegressorWrapper::cv_model,
+            Self::DummyClassifier => DummyClassifierWrapper::cv_model,
+            Self::BernoulliNaiveBayes => BernoulliNaiveBayesClassifierWrapper::cv_model,
+            Self::MultinomialNaiveBayes => MultinomialNaiveBayesClassifierWrapper::cv_model,
+            Self::MLPRegressor => MLPRegressorWrapper::cv_model,
+            Self::MLPClassifier => MLPClassifierWrapper::cv_model,
+            Self::Huber => HuberRegressorWrapper::cv_model,
+            Self::Quantile => QuantileRegressorWrapper::cv_model,
+            Self::Poisson => PoissonRegressorWrapper::cv_model,
+            Self::Gamma => GammaRegressorWrapper::cv_model,
+            Self::Tweedie => TweedieRegressorWrapper::cv_model,
+            Self::GaussianProcessRegressor => GaussianProcessRegressorWrapper::cv_model,
+        })
+    }
+
     /// Get the `predict` method for the underlying algorithm.
-    pub(crate) fn get_predictor(self) -> fn(&DenseMatrix<f32>, &Vec<u8>, &Settings) -> Vec<f32> {
+    pub(crate) fn get_predictor(&self) -> fn(&DenseMatrix<f32>, &Vec<u8>, &Settings) -> Vec<f32> {
         match self {
             Self::Linear => LinearRegressorWrapper::predict,
             Self::Lasso => LassoRegressorWrapper::predict,
@@ -281,12 +540,71 @@ impl Algorithm {
             Self::SVC => SupportVectorClassifierWrapper::predict,
             Self::GaussianNaiveBayes => GaussianNaiveBayesClassifierWrapper::predict,
             Self::CategoricalNaiveBayes => CategoricalNaiveBayesClassifierWrapper::predict,
+            Self::GradientBoostingRegressor => GradientBoostingRegressorWrapper::predict,
+            Self::GradientBoostingClassifier => GradientBoostingClassifierWrapper::predict,
+            Self::ExtraTreesRegressor => ExtraTreesRegressorWrapper::predict,
+            Self::ExtraTreesClassifier => ExtraTreesClassifierWrapper::predict,
+            Self::Bagging { .. } => BaggingWrapper::predict,
+            Self::DummyRegressor => DummyRegressorWrapper::predict,
+            Self::DummyClassifier => DummyClassifierWrapper::predict,
+            Self::BernoulliNaiveBayes => BernoulliNaiveBayesClassifierWrapper::predict,
+            Self::MultinomialNaiveBayes => MultinomialNaiveBayesClassifierWrapper::predict,
+            Self::MLPRegressor => MLPRegressorWrapper::predict,
+            Self::MLPClassifier => MLPClassifierWrapper::predict,
+            Self::Huber => HuberRegressorWrapper::predict,
+            Self::Quantile => QuantileRegressorWrapper::predict,
+            Self::Poisson => PoissonRegressorWrapper::predict,
+            Self::Gamma => GammaRegressorWrapper::predict,
+            Self::Tweedie => TweedieRegressorWrapper::predict,
+            Self::GaussianProcessRegressor => GaussianProcessRegressorWrapper::predict,
         }
     }
 
-    /// Get the `train` method for the underlying algorithm.
-    pub(crate) fn get_trainer(self) -> fn(&DenseMatrix<f32>, &Vec<f32>, &Settings) -> Vec<u8> {
+    /// Get the `predict_with_std` method for the underlying algorithm, if it has predictive
+    /// uncertainty.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn get_std_predictor(
+        &self,
+    ) -> Option<fn(&DenseMatrix<f32>, &Vec<u8>, &Settings) -> (Vec<f32>, Vec<f32>)> {
+        match self {
+            Self::GaussianProcessRegressor => {
+                Some(GaussianProcessRegressorWrapper::predict_with_std)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the `predict_scores` method for the underlying algorithm, if it has a score for each
+    /// class rather than only a label. Class probabilities can only be calibrated from scores.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn get_score_predictor(
+        &self,
+    ) -> Option<fn(&DenseMatrix<f32>, &Vec<u8>, &Settings) -> Vec<Vec<f32>>> {
         match self {
+            Self::LogisticRegression => Some(LogisticRegressionWrapper::predict_scores),
+            Self::GaussianNaiveBayes => Some(GaussianNaiveBayesClassifierWrapper::predict_scores),
+            Self::GradientBoostingClassifier => {
+                Some(GradientBoostingClassifierWrapper::predict_scores)
+            }
+            Self::ExtraTreesClassifier => Some(ExtraTreesClassifierWrapper::predict_scores),
+            Self::MLPClassifier => Some(MLPClassifierWrapper::predict_scores),
+            Self::DummyClassifier => Some(DummyClassifierWrapper::predict_scores),
+            _ => None,
+        }
+    }
+
+    /// Whether the algorithm has a score for each class, so that its class probabilities can be
+    /// calibrated
+    pub(crate) fn has_scores(&self) -> bool {
+        self.get_score_predictor().is_some()
+    }
+
+    /// Get the `train` method for the underlying algorithm.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn get_trainer(
+        &self,
+    ) -> Box<dyn Fn(&DenseMatrix<f32>, &Vec<f32>, &Settings) -> Result<Vec<u8>, Failed> + '_> {
+        Box::new(match self {
             Self::Linear => LinearRegressorWrapper::train,
             Self::Lasso => LassoRegressorWrapper::train,
             Self::Ridge => RidgeRegressorWrapper::train,
@@ -302,7 +620,26 @@ impl Algorithm {
             Self::SVC => SupportVectorClassifierWrapper::train,
             Self::GaussianNaiveBayes => GaussianNaiveBayesClassifierWrapper::train,
             Self::CategoricalNaiveBayes => CategoricalNaiveBayesClassifierWrapper::train,
-        }
+            Self::GradientBoostingRegressor => GradientBoostingRegressorWrapper::train,
+            Self::GradientBoostingClassifier => GradientBoostingClassifierWrapper::train,
+            Self::ExtraTreesRegressor => ExtraTreesRegressorWrapper::train,
+            Self::ExtraTreesClassifier => ExtraTreesClassifierWrapper::train,
+            Self::Bagging { .. } => {
+                return Box::new(|x, y, settings| BaggingWrapper::train(self, x, y, settings))
+            }
+            Self::DummyRegressor => DummyRegressorWrapper::train,
+            Self::DummyClassifier => DummyClassifierWrapper::train,
+            Self::BernoulliNaiveBayes => BernoulliNaiveBayesClassifierWrapper::train,
+            Self::MultinomialNaiveBayes => MultinomialNaiveBayesClassifierWrapper::train,
+            Self::MLPRegressor => MLPRegressorWrapper::train,
+            Self::MLPClassifier => MLPClassifierWrapper::train,
+            Self::Huber => HuberRegressorWrapper::train,
+            Self::Quantile => QuantileRegressorWrapper::train,
+            Self::Poisson => PoissonRegressorWrapper::train,
+            Self::Gamma => GammaRegressorWrapper::train,
+            Self::Tweedie => TweedieRegressorWrapper::train,
+            Self::GaussianProcessRegressor => GaussianProcessRegressorWrapper::train,
+        })
     }
 }
 
@@ -324,12 +661,29 @@ impl Display for Algorithm {
             Self::SVC => write!(f, "Support Vector Classifier"),
             Self::GaussianNaiveBayes => write!(f, "Gaussian Naive Bayes"),
             Self::CategoricalNaiveBayes => write!(f, "Categorical Naive Bayes"),
+            Self::GradientBoostingRegressor => write!(f, "Gradient Boosting Regressor"),
+            Self::GradientBoostingClassifier => write!(f, "Gradient Boosting Classifier"),
+            Self::ExtraTreesRegressor => write!(f, "Extra Trees Regressor"),
+            Self::ExtraTreesClassifier => write!(f, "Extra Trees Classifier"),
+            Self::Bagging { base, .. } => write!(f, "Bagged {base}"),
+            Self::DummyRegressor => write!(f, "Dummy Regressor"),
+            Self::DummyClassifier => write!(f, "Dummy Classifier"),
+            Self::BernoulliNaiveBayes => write!(f, "Bernoulli Naive Bayes"),
+            Self::MultinomialNaiveBayes => write!(f, "Multinomial Naive Bayes"),
+            Self::MLPRegressor => write!(f, "Multilayer Perceptron Regressor"),
+            Self::MLPClassifier => write!(f, "Multilayer Perceptron Classifier"),
+            Self::Huber => write!(f, "Huber Regressor"),
+            Self::Quantile => write!(f, "Quantile Regressor"),
+            Self::Poisson => write!(f, "Poisson Regressor"),
+            Self::Gamma => write!(f, "Gamma Regressor"),
+            Self::Tweedie => write!(f, "Tweedie Regressor"),
+            Self::GaussianProcessRegressor => write!(f, "Gaussian Process Regressor"),
         }
     }
 }
 
 /// Options for pre-processing the data
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum PreProcessing {
     /// Don't do any preprocessing
     None,
@@ -371,12 +725,511 @@ impl Display for PreProcessing {
     }
 }
 
+/// Options for rebalancing the classes in each training fold
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub enum Resampling {
+    /// Don't do any resampling
+    None,
+    /// Randomly duplicate rows of the minority classes until every class matches the majority class
+    RandomOversampling,
+    /// Randomly drop rows of the majority classes until every class matches the minority class
+    RandomUndersampling,
+    /// Synthesize new minority class rows by interpolating between nearest neighbors (SMOTE)
+    SMOTE {
+        /// The number of nearest neighbors to interpolate between
+        k_neighbors: usize,
+    },
+}
+
+impl Display for Resampling {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::RandomOversampling => write!(f, "Random oversampling"),
+            Self::RandomUndersampling => write!(f, "Random undersampling"),
+            Self::SMOTE { k_neighbors } => write!(f, "SMOTE (k = {k_neighbors})"),
+        }
+    }
+}
+
+/// Options for weighting classes in classification, see `Settings::with_class_weight` for how each
+/// algorithm applies them
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub enum ClassWeight {
+    /// Weight every row equally
+    None,
+    /// Weight classes inversely proportional to their frequency
+    Balanced,
+    /// Use custom weights, one per class in ascending order of class label
+    Custom(Vec<f32>),
+}
+
+impl Display for ClassWeight {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Balanced => write!(f, "Balanced"),
+            Self::Custom(weights) => write!(f, "Custom {weights:?}"),
+        }
+    }
+}
+
+/// Options for eliminating weak candidate algorithms early, before they get a full
+/// cross-validation on all of the data
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Halving {
+    /// Give every candidate a full cross-validation on all of the data
+    None,
+    /// Successive halving: score all candidates on a small subsample of the data, keep the best
+    /// `1 / reduction_factor` of them, and repeat on `reduction_factor` times more data until
+    /// the survivors reach the full data
+    Successive {
+        /// The fraction of the data used in the first round
+        min_fraction: f32,
+        /// How many times more data each round gets, and how many times fewer candidates survive
+        reduction_factor: usize,
+    },
+    /// Hyperband: run successive halving several times, each starting from a larger fraction of
+    /// the data, and keep every algorithm that survives any of those runs
+    Hyperband {
+        /// The smallest fraction of the data used in the first round of any run
+        min_fraction: f32,
+        /// How many times more data each round gets, and how many times fewer candidates survive
+        reduction_factor: usize,
+    },
+}
+
+impl Display for Halving {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Successive {
+                min_fraction,
+                reduction_factor,
+            } => write!(
+                f,
+                "Successive halving\n    min fraction = {min_fraction}\n    reduction factor = {reduction_factor}"
+            ),
+            Self::Hyperband {
+                min_fraction,
+                reduction_factor,
+            } => write!(
+                f,
+                "Hyperband\n    min fraction = {min_fraction}\n    reduction factor = {reduction_factor}"
+            ),
+        }
+    }
+}
+
+/// Options for calibrating conformal prediction intervals or sets around the final model
+///
+/// Intervals (for regression) and sets (for classification) cover the true value with the target
+/// coverage set with `Settings::with_coverage`. Sets are built from calibrated class probabilities,
+/// so only algorithms with scores are compared for them.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Conformal {
+    /// Don't calibrate any intervals or sets
+    None,
+    /// Split conformal: set aside a fraction of the training data, train on the rest, and
+    /// calibrate on the errors of the final model on the data set aside
+    Split {
+        /// The fraction of the training data set aside for calibration
+        calibration_fraction: f32,
+    },
+    /// CV+: repeat the whole training procedure once without each of the folds of the splitter
+    /// set with `Settings::with_splitter`, and calibrate on the errors of each of those models on
+    /// the fold it left out (Barber et al., 2021)
+    CVPlus {
+        /// The number of folds
+        folds: usize,
+    },
+    /// Jackknife+: CV+ with one fold for every row, so training is repeated once per row
+    JackknifePlus,
+}
+
+impl Display for Conformal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Split {
+                calibration_fraction,
+            } => write!(
+                f,
+                "Split conformal\n    calibration fraction = {calibration_fraction}"
+            ),
+            Self::CVPlus { folds } => write!(f, "CV+\n    folds = {folds}"),
+            Self::JackknifePlus => write!(f, "Jackknife+"),
+        }
+    }
+}
+
+/// Methods for calibrating the class probabilities of classifiers
+///
+/// Each model in the comparison is refit on all but one of the folds, and a calibrator is fit
+/// for each class to its scores on the fold left out. Calibrated probabilities average the
+/// calibrated outputs of the fold models, and are used by `SupervisedModel::predict_proba`.
+///
+/// Only algorithms with continuous scores can be calibrated: logistic regression, Gaussian naive
+/// Bayes, gradient boosting, extra trees, the multilayer perceptron and the dummy classifier. The
+/// others only predict labels, so they have no probabilities.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Calibration {
+    /// Don't calibrate probabilities
+    None,
+    /// Platt scaling: fit a sigmoid to the predictions of each fold model
+    Sigmoid {
+        /// The number of folds
+        folds: usize,
+    },
+    /// Isotonic regression: fit a non-decreasing function to the predictions of each fold
+    /// model, which needs more data than a sigmoid but makes no assumption about its shape
+    Isotonic {
+        /// The number of folds
+        folds: usize,
+    },
+}
+
+impl Display for Calibration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Sigmoid { folds } => write!(f, "Sigmoid (Platt)\n    folds = {folds}"),
+            Self::Isotonic { folds } => write!(f, "Isotonic\n    folds = {folds}"),
+        }
+    }
+}
+
+/// Objectives for tuning the decision threshold of a binary classifier
+///
+/// The threshold is applied to the calibrated probability of the positive class (the larger of the
+/// two class labels), and is tuned on the out-of-fold probabilities from calibration. Without a
+/// calibration method in the settings, sigmoid calibration is used with the number of folds of
+/// the model comparison.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum ThresholdObjective {
+    /// Predict the class given by the final model, without a threshold
+    None,
+    /// Minimize the total cost of the predictions, with `matrix[actual][predicted]` the cost of
+    /// each outcome and index one the positive class
+    Cost {
+        /// The cost of each combination of actual and predicted class
+        matrix: [[f32; 2]; 2],
+    },
+    /// Maximize the F1 score of the positive class
+    F1,
+    /// Maximize recall while keeping at least the given precision, or maximize precision if no
+    /// threshold reaches it
+    RecallAtPrecision {
+        /// The smallest acceptable precision
+        precision: f32,
+    },
+    /// Maximize Youden's J statistic, the true positive rate minus the false positive rate
+    YoudensJ,
+}
+
+impl Display for ThresholdObjective {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Cost { matrix } => write!(f, "Cost\n    matrix = {matrix:?}"),
+            Self::F1 => write!(f, "F1"),
+            Self::RecallAtPrecision { precision } => {
+                write!(f, "Recall at precision\n    precision = {precision}")
+            }
+            Self::YoudensJ => write!(f, "Youden's J"),
+        }
+    }
+}
+
+/// Options for self-training a classifier on the unlabeled rows given to
+/// `SupervisedModel::new_with_unlabeled`
+///
+/// Each iteration runs the whole model comparison, labels the unlabeled rows whose most likely
+/// class has a calibrated probability of at least the confidence threshold with that class, and
+/// adds them to the training data. Pseudo-labeled rows are added to the training rows of every
+/// cross-validation fold but never tested on, so scores are only measured against real labels.
+/// The final model is trained on the labeled and pseudo-labeled rows. Only algorithms with scores
+/// to calibrate (see `Calibration`) are compared. Without a calibration method in the settings,
+/// sigmoid calibration is used with the number of folds of the model comparison.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum SelfTraining {
+    /// Only train on the labeled rows
+    None,
+    /// Pseudo-label confident predictions until no row is confident enough or the iterations run
+    /// out
+    ConfidenceThreshold {
+        /// The smallest calibrated probability of a pseudo-label
+        threshold: f32,
+        /// The largest number of pseudo-labeling iterations
+        max_iterations: usize,
+    },
+}
+
+impl Display for SelfTraining {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::ConfidenceThreshold {
+                threshold,
+                max_iterations,
+            } => write!(
+                f,
+                "Confidence threshold\n    threshold = {threshold}\n    max iterations = {max_iterations}"
+            ),
+        }
+    }
+}
+
+/// Strategies for fitting algorithms without native multi-output support to several targets
+///
+/// Linear regression, decision tree regression and KNN regression are always fit jointly to all
+/// of the targets, and use neither strategy.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum MultiOutput {
+    /// Fit an independent model to each target
+    PerTarget,
+    /// Fit a regressor chain: the model of each target also sees the targets before it, which are
+    /// predicted by the earlier models of the chain at prediction time
+    Chain,
+}
+
+impl Display for MultiOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PerTarget => write!(f, "Per target"),
+            Self::Chain => write!(f, "Regressor chain"),
+        }
+    }
+}
+
+/// Strategies for fitting classifiers to several binary labels at once
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum MultiLabel {
+    /// Binary relevance: fit an independent classifier to each label
+    BinaryRelevance,
+    /// Fit a classifier chain: the classifier of each label also sees the labels before it, which
+    /// are predicted by the earlier classifiers of the chain at prediction time
+    Chain,
+}
+
+impl Display for MultiLabel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BinaryRelevance => write!(f, "Binary relevance"),
+            Self::Chain => write!(f, "Classifier chain"),
+        }
+    }
+}
+
+/// Metrics for comparing predicted and true label-indicator matrices
+#[derive(PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum MultiLabelMetric {
+    /// The fraction of individual labels predicted wrongly
+    HammingLoss,
+    /// The fraction of rows with every label predicted correctly
+    SubsetAccuracy,
+    /// F1 score over the true and false positives and negatives of all labels pooled
+    MicroF1,
+    /// Mean of the F1 score of each label
+    MacroF1,
+}
+
+impl MultiLabelMetric {
+    /// Every multi-label metric, in the order they are displayed
+    pub(crate) const ALL: [Self; 4] = [
+        Self::HammingLoss,
+        Self::SubsetAccuracy,
+        Self::MicroF1,
+        Self::MacroF1,
+    ];
+
+    /// Whether a higher value of the metric is better
+    pub(crate) const fn higher_is_better(self) -> bool {
+        !matches!(self, Self::HammingLoss)
+    }
+
+    /// Get the function computing the metric from true and predicted label-indicator matrices
+    pub(crate) fn get_function(self) -> fn(&DenseMatrix<f32>, &DenseMatrix<f32>) -> f32 {
+        match self {
+            Self::HammingLoss => hamming_loss,
+            Self::SubsetAccuracy => subset_accuracy,
+            Self::MicroF1 => micro_f1,
+            Self::MacroF1 => macro_f1,
+        }
+    }
+}
+
+impl Display for MultiLabelMetric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HammingLoss => write!(f, "Hamming Loss"),
+            Self::SubsetAccuracy => write!(f, "Subset Accuracy"),
+            Self::MicroF1 => write!(f, "Micro F1"),
+            Self::MacroF1 => write!(f, "Macro F1"),
+        }
+    }
+}
+
+/// Clustering algorithm options
+#[derive(PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ClusteringAlgorithm {
+    /// K-means, fit once for every number of clusters in the range
+    KMeans,
+    /// Density-based clustering, which finds its own number of clusters and marks outliers as noise
+    DBSCAN,
+    /// Agglomerative hierarchical clustering, cut once for every number of clusters in the range
+    Hierarchical,
+}
+
+impl Display for ClusteringAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KMeans => write!(f, "KMeans"),
+            Self::DBSCAN => write!(f, "DBSCAN"),
+            Self::Hierarchical => write!(f, "Hierarchical"),
+        }
+    }
+}
+
+/// Internal validity metrics for ranking clusterings
+#[derive(PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum ClusteringMetric {
+    /// Mean silhouette coefficient, from -1 to 1, higher is better
+    Silhouette,
+    /// Ratio of between-cluster to within-cluster dispersion, higher is better
+    CalinskiHarabasz,
+    /// Mean similarity of each cluster to its most similar cluster, lower is better
+    DaviesBouldin,
+}
+
+impl ClusteringMetric {
+    /// Every clustering metric, in the order they are displayed
+    pub(crate) const ALL: [Self; 3] = [
+        Self::Silhouette,
+        Self::CalinskiHarabasz,
+        Self::DaviesBouldin,
+    ];
+
+    /// Whether a higher value of the metric is better
+    pub(crate) const fn higher_is_better(self) -> bool {
+        !matches!(self, Self::DaviesBouldin)
+    }
+
+    /// Get the function computing the metric from the data and the cluster of each row
+    pub(crate) fn get_function(self) -> fn(&DenseMatrix<f32>, &[f32]) -> Option<f32> {
+        match self {
+            Self::Silhouette => silhouette_score,
+            Self::CalinskiHarabasz => calinski_harabasz_score,
+            Self::DaviesBouldin => davies_bouldin_score,
+        }
+    }
+}
+
+impl Display for ClusteringMetric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Silhouette => write!(f, "Silhouette"),
+            Self::CalinskiHarabasz => write!(f, "Calinski-Harabasz"),
+            Self::DaviesBouldin => write!(f, "Davies-Bouldin"),
+        }
+    }
+}
+
+/// Linkage criteria for agglomerative hierarchical clustering
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Linkage {
+    /// Merge the pair of clusters that least increases the total within-cluster variance
+    Ward,
+    /// Merge the pair of clusters with the smallest mean distance between their rows
+    Average,
+    /// Merge the pair of clusters with the smallest maximum distance between their rows
+    Complete,
+    /// Merge the pair of clusters with the smallest minimum distance between their rows
+    Single,
+}
+
+impl Display for Linkage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ward => write!(f, "Ward"),
+            Self::Average => write!(f, "Average"),
+            Self::Complete => write!(f, "Complete"),
+            Self::Single => write!(f, "Single"),
+        }
+    }
+}
+
+/// Anomaly detection algorithm options
+#[derive(PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum AnomalyAlgorithm {
+    /// Isolation forest, which isolates anomalies with fewer random splits than normal rows
+    IsolationForest,
+    /// Local outlier factor, which finds rows in sparser neighborhoods than their neighbors'
+    LocalOutlierFactor,
+    /// Robust covariance, which finds rows far from the bulk of the data by Mahalanobis distance
+    RobustCovariance,
+}
+
+impl Display for AnomalyAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IsolationForest => write!(f, "Isolation Forest"),
+            Self::LocalOutlierFactor => write!(f, "Local Outlier Factor"),
+            Self::RobustCovariance => write!(f, "Robust Covariance"),
+        }
+    }
+}
+
+/// Metrics for ranking anomaly detectors on a labelled validation set, where a label of 1 marks
+/// an anomaly. All of them are better when higher.
+#[derive(PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum AnomalyMetric {
+    /// Area under the ROC curve of the anomaly scores
+    RocAuc,
+    /// Average precision of the anomaly scores, which focuses on the top of the ranking
+    AveragePrecision,
+    /// F1 score of the anomalies flagged by the contamination threshold
+    F1,
+}
+
+impl AnomalyMetric {
+    /// Every anomaly metric, in the order they are displayed
+    pub(crate) const ALL: [Self; 3] = [Self::RocAuc, Self::AveragePrecision, Self::F1];
+
+    /// Compute the metric from the labels, the anomaly scores and the threshold above which rows
+    /// are flagged
+    pub(crate) fn compute(self, y: &[f32], scores: &[f32], threshold: f32) -> f32 {
+        match self {
+            Self::RocAuc => roc_auc(y, scores),
+            Self::AveragePrecision => average_precision(y, scores),
+            Self::F1 => {
+                let flagged: Vec<f32> = scores
+                    .iter()
+                    .map(|&score| if score > threshold { 1.0 } else { 0.0 })
+                    .collect();
+                binary_f1(y, &flagged)
+            }
+        }
+    }
+}
+
+impl Display for AnomalyMetric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RocAuc => write!(f, "ROC AUC"),
+            Self::AveragePrecision => write!(f, "Average Precision"),
+            Self::F1 => write!(f, "F1"),
+        }
+    }
+}
+
 /// Final model approach
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum FinalModel {
     /// Do not train a final model
     None,
-    /// Select the best model from the comparison set as the final model
+    /// Select the best model from the comparison set as the final model, passing over the
+    /// baselines unless every model is one
     Best,
     /// Use a blending approach to produce a final model
     Blending {
@@ -387,6 +1240,26 @@ pub enum FinalModel {
         /// How much data to retain to test the blending model
         meta_testing_fraction: f32,
     },
+    /// Combine the top models of the comparison, averaging their predictions for regression or
+    /// taking a vote for classification. Baseline models never take part.
+    Voting {
+        /// How many of the best models take part
+        top_k: usize,
+        /// The weight of each of the best models, from best to worst, or `None` for a plain
+        /// average or majority vote. Models without a weight get a weight of one.
+        weights: Option<Vec<f32>>,
+        /// Whether to average the calibrated class probabilities of the models and predict the
+        /// most probable class instead of counting their votes (classification only, and only
+        /// algorithms with scores are compared)
+        soft: bool,
+    },
+    /// Greedily build a weighted ensemble from the out-of-fold predictions of every model but the
+    /// baselines, adding (with replacement) whichever model most improves the ensemble at each
+    /// step (Caruana et al., 2004)
+    EnsembleSelection {
+        /// How many models to add to the ensemble
+        iterations: usize,
+    },
     // /// Use a stacking approach to produce a final model (not implemented)
     // Stacking {
     //     /// How much data to retain to train the blending model
@@ -404,4 +1277,20 @@ impl FinalModel {
             meta_testing_fraction: 0.15,
         }
     }
+
+    /// Default values for a voting model (equally weighted vote of the three best models)
+    #[must_use]
+    pub const fn default_voting() -> Self {
+        Self::Voting {
+            top_k: 3,
+            weights: None,
+            soft: false,
+        }
+    }
+
+    /// Default values for ensemble selection (20 greedy selection steps)
+    #[must_use]
+    pub const fn default_ensemble_selection() -> Self {
+        Self::EnsembleSelection { iterations: 20 }
+    }
 }
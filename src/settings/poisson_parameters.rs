@@ -0,0 +1,46 @@
+//! Poisson regression parameters
+
+/// Parameters for Poisson regression, a generalized linear model with a log link for count
+/// targets. The targets must be non-negative.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct PoissonParameters {
+    /// The strength of the L2 penalty on the standardized coefficients
+    pub(crate) alpha: f32,
+    /// The maximum number of reweighting iterations
+    pub(crate) max_iter: usize,
+    /// Stop once no coefficient changes by more than this relative amount
+    pub(crate) tol: f32,
+}
+
+impl PoissonParameters {
+    /// Define the strength of the L2 penalty on the standardized coefficients
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the maximum number of reweighting iterations
+    #[must_use]
+    pub const fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the relative change in coefficients below which fitting stops
+    #[must_use]
+    pub const fn with_tol(mut self, tol: f32) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for PoissonParameters {
+    fn default() -> Self {
+        Self {
+            alpha: 1.0,
+            max_iter: 100,
+            tol: 1e-4,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Quantile regression parameters
+
+/// Parameters for quantile regression, a linear model that predicts a chosen quantile of the
+/// target by minimizing the pinball loss. The median (0.5) is the default.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct QuantileParameters {
+    /// The quantile to predict, between zero and one
+    pub(crate) quantile: f32,
+    /// The strength of the L2 penalty on the standardized coefficients
+    pub(crate) alpha: f32,
+    /// The maximum number of reweighting iterations
+    pub(crate) max_iter: usize,
+    /// Stop once no coefficient changes by more than this relative amount
+    pub(crate) tol: f32,
+}
+
+impl QuantileParameters {
+    /// Define the quantile to predict, between zero and one
+    #[must_use]
+    pub const fn with_quantile(mut self, quantile: f32) -> Self {
+        self.quantile = quantile;
+        self
+    }
+
+    /// Define the strength of the L2 penalty on the standardized coefficients
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the maximum number of reweighting iterations
+    #[must_use]
+    pub const fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the relative change in coefficients below which fitting stops
+    #[must_use]
+    pub const fn with_tol(mut self, tol: f32) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for QuantileParameters {
+    fn default() -> Self {
+        Self {
+            quantile: 0.5,
+            alpha: 0.0001,
+            max_iter: 100,
+            tol: 1e-5,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Robust covariance parameters
+
+/// Parameters for robust covariance, which scores rows by their Mahalanobis distance from a robust
+/// mean.
+///
+/// The mean and covariance are fit to the most central rows with the minimum covariance
+/// determinant estimator.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct RobustCovarianceParameters {
+    /// The fraction of rows the estimate is fit to, or `None` for just over half of them
+    pub(crate) support_fraction: Option<f32>,
+    /// The number of random starting subsets to refine
+    pub(crate) n_starts: usize,
+}
+
+impl RobustCovarianceParameters {
+    /// Define the fraction of rows the estimate is fit to
+    #[must_use]
+    pub const fn with_support_fraction(mut self, support_fraction: f32) -> Self {
+        self.support_fraction = Some(support_fraction);
+        self
+    }
+
+    /// Define the number of random starting subsets to refine
+    #[must_use]
+    pub const fn with_n_starts(mut self, n_starts: usize) -> Self {
+        self.n_starts = n_starts;
+        self
+    }
+}
+
+impl Default for RobustCovarianceParameters {
+    fn default() -> Self {
+        Self {
+            support_fraction: None,
+            n_starts: 10,
+        }
+    }
+}
@@ -5,21 +5,32 @@ use comfy_table::{
 };
 
 use super::{
-    Algorithm, CategoricalNBParameters, DecisionTreeClassifierParameters,
-    DecisionTreeRegressorParameters, ElasticNetParameters, FinalModel, GaussianNBParameters,
-    KNNClassifierParameters, KNNRegressorParameters, LassoParameters, LinearRegressionParameters,
-    LinearRegressionSolverName, LogisticRegressionParameters, Metric, PreProcessing,
-    RandomForestClassifierParameters, RandomForestRegressorParameters, RidgeRegressionParameters,
-    RidgeRegressionSolverName, SVCParameters, SVRParameters,
+    Algorithm, BernoulliNBParameters, Calibration, CategoricalNBParameters, ClassWeight, Conformal,
+    DecisionTreeClassifierParameters, DecisionTreeRegressorParameters, DummyClassifierParameters,
+    DummyRegressorParameters, ElasticNetParameters, ExtraTreesClassifierParameters,
+    ExtraTreesRegressorParameters, FinalModel, Folds, ForecastParameters, GammaParameters,
+    GaussianNBParameters, GaussianProcessRegressorParameters, GradientBoostingClassifierParameters,
+    GradientBoostingRegressorParameters, Halving, HuberParameters, KNNClassifierParameters,
+    KNNRegressorParameters, LassoParameters, LinearRegressionParameters,
+    LinearRegressionSolverName, LogisticRegressionParameters, MLPClassifierParameters,
+    MLPRegressorParameters, Metric, MultiLabel, MultiLabelMetric, MultiOutput,
+    MultinomialNBParameters, PoissonParameters, PreProcessing, QuantileParameters,
+    RandomForestClassifierParameters, RandomForestRegressorParameters, Resampling,
+    RidgeRegressionParameters, RidgeRegressionSolverName, SVCParameters, SVRParameters,
+    SelfTraining, Splitter, ThresholdObjective, TweedieParameters,
 };
 
 use crate::utils::{
-    debug_option, print_knn_search_algorithm, print_knn_weight_function, print_option,
+    class_indices, debug_option, mean_gamma_deviance, mean_poisson_deviance, mix_seed,
+    print_knn_search_algorithm, print_knn_weight_function, print_option, random_oversample,
+    random_undersample, smote, trailing_split, train_test_split, weighted_oversample,
 };
 
+use rand::{rngs::StdRng, SeedableRng};
 use smartcore::{
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
     metrics::{accuracy, mean_absolute_error, mean_squared_error, r2},
-    model_selection::KFold,
     tree::decision_tree_classifier::SplitCriterion,
 };
 
@@ -29,7 +40,8 @@ use std::io::{Read, Write};
 /// Settings for supervised models
 ///
 /// Any algorithms in the `skiplist` member will be skipped during training.
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[allow(clippy::struct_field_names)]
 pub struct Settings {
     /// The metric to sort by
     pub(crate) sort_by: Metric,
@@ -39,14 +51,49 @@ pub struct Settings {
     pub(crate) skiplist: Vec<Algorithm>,
     /// The number of folds for cross-validation
     number_of_folds: usize,
+    /// The strategy used to split the data for cross-validation
+    splitter: Splitter,
+    /// The cross-validation splits fixed for the current training data, so that every algorithm
+    /// is compared on the same folds
+    pub(crate) folds: Option<Folds>,
+    /// The fraction of the data to hold out from model selection for a final, unbiased evaluation
+    pub(crate) holdout_fraction: Option<f32>,
+    /// The number of outer folds for nested cross-validation, if any
+    pub(crate) outer_folds: Option<usize>,
+    /// The schedule for eliminating weak candidates early
+    pub(crate) halving: Halving,
+    /// The conformal method used to calibrate prediction intervals or sets
+    pub(crate) conformal: Conformal,
+    /// The target coverage of conformal prediction intervals or sets
+    pub(crate) coverage: f32,
+    /// The method used to calibrate the class probabilities of classifiers
+    pub(crate) calibration: Calibration,
+    /// The objective used to tune the decision threshold of binary classifiers
+    pub(crate) threshold_objective: ThresholdObjective,
+    /// How classifiers are self-trained on unlabeled rows
+    pub(crate) self_training: SelfTraining,
+    /// The strategy for fitting single-output algorithms to several targets
+    pub(crate) multi_output: MultiOutput,
+    /// The strategy for fitting classifiers to several labels
+    pub(crate) multi_label: MultiLabel,
+    /// The metric to sort multi-label models by
+    pub(crate) multi_label_metric: MultiLabelMetric,
+    /// The features forecasting models build from the history of the target
+    pub(crate) forecast_settings: ForecastParameters,
     /// Whether or not to shuffle the data
     pub(crate) shuffle: bool,
+    /// The seed used for all random number generation, if any
+    pub(crate) seed: Option<u64>,
     /// Whether or not to be verbose
     verbose: bool,
     /// The approach to use for the final model
     pub(crate) final_model_approach: FinalModel,
     /// The kind of preprocessing to perform
     pub(crate) preprocessing: PreProcessing,
+    /// The resampling used to rebalance classes in each training fold
+    pub(crate) resampling: Resampling,
+    /// The class weights used by algorithms that support them
+    pub(crate) class_weight: ClassWeight,
     /// Optional settings for linear regression
     pub(crate) linear_settings: Option<LinearRegressionParameters>,
     /// Optional settings for support vector regressor
@@ -57,12 +104,30 @@ pub struct Settings {
     pub(crate) ridge_settings: Option<RidgeRegressionParameters<f32>>,
     /// Optional settings for elastic net
     pub(crate) elastic_net_settings: Option<ElasticNetParameters<f32>>,
+    /// Optional settings for Huber regression
+    pub(crate) huber_settings: Option<HuberParameters>,
+    /// Optional settings for quantile regression
+    pub(crate) quantile_settings: Option<QuantileParameters>,
+    /// Optional settings for Poisson regression
+    pub(crate) poisson_settings: Option<PoissonParameters>,
+    /// Optional settings for Gamma regression
+    pub(crate) gamma_settings: Option<GammaParameters>,
+    /// Optional settings for Tweedie regression
+    pub(crate) tweedie_settings: Option<TweedieParameters>,
+    /// Optional settings for Gaussian process regression
+    pub(crate) gaussian_process_regressor_settings: Option<GaussianProcessRegressorParameters>,
     /// Optional settings for decision tree regressor
     pub(crate) decision_tree_regressor_settings: Option<DecisionTreeRegressorParameters>,
     /// Optional settings for random forest regressor
     pub(crate) random_forest_regressor_settings: Option<RandomForestRegressorParameters>,
     /// Optional settings for KNN regressor
     pub(crate) knn_regressor_settings: Option<KNNRegressorParameters>,
+    /// Optional settings for gradient boosting regressor
+    pub(crate) gradient_boosting_regressor_settings: Option<GradientBoostingRegressorParameters>,
+    /// Optional settings for extra trees regressor
+    pub(crate) extra_trees_regressor_settings: Option<ExtraTreesRegressorParameters>,
+    /// Optional settings for the baseline dummy regressor
+    pub(crate) dummy_regressor_settings: Option<DummyRegressorParameters>,
     /// Optional settings for logistic regression
     pub(crate) logistic_settings: Option<LogisticRegressionParameters<f32>>,
     /// Optional settings for random forest
@@ -77,6 +142,22 @@ pub struct Settings {
     pub(crate) gaussian_nb_settings: Option<GaussianNBParameters<f32>>,
     /// Optional settings for Categorical Naive Bayes
     pub(crate) categorical_nb_settings: Option<CategoricalNBParameters<f32>>,
+    /// Optional settings for Bernoulli Naive Bayes
+    pub(crate) bernoulli_nb_settings: Option<BernoulliNBParameters<f32>>,
+    /// Optional settings for Multinomial Naive Bayes
+    pub(crate) multinomial_nb_settings: Option<MultinomialNBParameters<f32>>,
+    /// Optional settings for gradient boosting classifier
+    pub(crate) gradient_boosting_classifier_settings: Option<GradientBoostingClassifierParameters>,
+    /// Optional settings for extra trees classifier
+    pub(crate) extra_trees_classifier_settings: Option<ExtraTreesClassifierParameters>,
+    /// Optional settings for the baseline dummy classifier
+    pub(crate) dummy_classifier_settings: Option<DummyClassifierParameters>,
+    /// The bagging ensembles to compare along with the algorithms that are not skipped
+    pub(crate) bagging: Vec<Algorithm>,
+    /// Optional settings for the multilayer perceptron regressor
+    pub(crate) mlp_regressor_settings: Option<MLPRegressorParameters>,
+    /// Optional settings for the multilayer perceptron classifier
+    pub(crate) mlp_classifier_settings: Option<MLPClassifierParameters>,
 }
 
 impl Default for Settings {
@@ -97,23 +178,65 @@ impl Default for Settings {
                 Algorithm::Lasso,
                 Algorithm::Ridge,
                 Algorithm::ElasticNet,
+                Algorithm::Huber,
+                Algorithm::Quantile,
+                Algorithm::Poisson,
+                Algorithm::Gamma,
+                Algorithm::Tweedie,
+                Algorithm::GaussianProcessRegressor,
                 Algorithm::SVR,
                 Algorithm::DecisionTreeRegressor,
                 Algorithm::RandomForestRegressor,
                 Algorithm::KNNRegressor,
+                Algorithm::GradientBoostingRegressor,
+                Algorithm::GradientBoostingClassifier,
+                Algorithm::ExtraTreesRegressor,
+                Algorithm::ExtraTreesClassifier,
+                Algorithm::DummyRegressor,
+                Algorithm::DummyClassifier,
+                Algorithm::BernoulliNaiveBayes,
+                Algorithm::MultinomialNaiveBayes,
+                Algorithm::MLPRegressor,
+                Algorithm::MLPClassifier,
             ],
             preprocessing: PreProcessing::None,
+            resampling: Resampling::None,
+            class_weight: ClassWeight::None,
             number_of_folds: 10,
+            splitter: Splitter::KFold,
+            folds: None,
+            holdout_fraction: None,
+            outer_folds: None,
+            halving: Halving::None,
+            conformal: Conformal::None,
+            coverage: 0.9,
+            calibration: Calibration::None,
+            threshold_objective: ThresholdObjective::None,
+            self_training: SelfTraining::None,
+            multi_output: MultiOutput::PerTarget,
+            multi_label: MultiLabel::BinaryRelevance,
+            multi_label_metric: MultiLabelMetric::MicroF1,
+            forecast_settings: ForecastParameters::default(),
             shuffle: false,
+            seed: None,
             verbose: false,
             linear_settings: None,
             svr_settings: None,
             lasso_settings: None,
             ridge_settings: None,
             elastic_net_settings: None,
+            huber_settings: None,
+            quantile_settings: None,
+            poisson_settings: None,
+            gamma_settings: None,
+            tweedie_settings: None,
+            gaussian_process_regressor_settings: None,
             decision_tree_regressor_settings: None,
             random_forest_regressor_settings: None,
             knn_regressor_settings: None,
+            gradient_boosting_regressor_settings: None,
+            extra_trees_regressor_settings: None,
+            dummy_regressor_settings: None,
             logistic_settings: None,
             random_forest_classifier_settings: None,
             knn_classifier_settings: None,
@@ -121,16 +244,119 @@ impl Default for Settings {
             decision_tree_classifier_settings: None,
             gaussian_nb_settings: None,
             categorical_nb_settings: None,
+            bernoulli_nb_settings: None,
+            multinomial_nb_settings: None,
+            gradient_boosting_classifier_settings: None,
+            extra_trees_classifier_settings: None,
+            dummy_classifier_settings: None,
+            bagging: vec![],
+            mlp_regressor_settings: None,
+            mlp_classifier_settings: None,
         }
     }
 }
 
 impl Settings {
-    /// Get the k-fold cross-validator
-    pub(crate) fn get_kfolds(&self) -> KFold {
-        KFold::default()
-            .with_n_splits(self.number_of_folds)
-            .with_shuffle(self.shuffle)
+    /// Get the cross-validation splits for the data, unless splits are fixed for it
+    pub(crate) fn get_kfolds(&self, x: &DenseMatrix<f32>, y: &[f32]) -> Folds {
+        self.folds.clone().unwrap_or_else(|| {
+            self.splitter.split(
+                x,
+                y,
+                self.number_of_folds,
+                self.shuffle,
+                &mut self.rng("folds", y),
+            )
+        })
+    }
+
+    /// Split off a fraction of the data for testing, which is the latest rows for time series
+    /// splits, so they are never predicted from the future
+    pub(crate) fn train_test_split(
+        &self,
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        test_size: f32,
+    ) -> (DenseMatrix<f32>, DenseMatrix<f32>, Vec<f32>, Vec<f32>) {
+        match self.splitter {
+            Splitter::TimeSeriesSplit => trailing_split(x, y, test_size),
+            _ => train_test_split(x, y, test_size, self.shuffle, &mut self.rng("split", y)),
+        }
+    }
+
+    /// Drop the group column of group k-fold cross-validation from the data, so that it only
+    /// splits the data and is never a feature
+    pub(crate) fn without_group_column(&self, x: DenseMatrix<f32>) -> DenseMatrix<f32> {
+        match self.splitter {
+            Splitter::GroupKFold { group_column } => {
+                let columns: Vec<usize> = (0..x.shape().1).filter(|&c| c != group_column).collect();
+                x.take(&columns, 1)
+            }
+            _ => x,
+        }
+    }
+
+    /// Get the splits for CV+ conformal prediction, into the given number of folds
+    pub(crate) fn get_conformal_folds(
+        &self,
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        number_of_folds: usize,
+    ) -> Folds {
+        self.splitter.split(
+            x,
+            y,
+            number_of_folds,
+            self.shuffle,
+            &mut self.rng("conformal folds", y),
+        )
+    }
+
+    /// Get the outer cross-validation splits for nested cross-validation, if enabled
+    pub(crate) fn get_outer_folds(&self, x: &DenseMatrix<f32>, y: &[f32]) -> Option<Folds> {
+        self.outer_folds.map(|number_of_folds| {
+            self.splitter.split(
+                x,
+                y,
+                number_of_folds,
+                self.shuffle,
+                &mut self.rng("outer folds", y),
+            )
+        })
+    }
+
+    /// The method used to calibrate class probabilities, falling back to sigmoid calibration
+    /// when probabilities are needed without one
+    pub(crate) const fn probability_calibration(&self) -> Calibration {
+        match self.calibration {
+            Calibration::None if self.needs_probabilities() => Calibration::Sigmoid {
+                folds: self.number_of_folds,
+            },
+            calibration => calibration,
+        }
+    }
+
+    /// Whether every model in the comparison needs calibrated probabilities, because the
+    /// decision threshold is tuned on them, self-training keeps confident pseudo-labels, the best
+    /// models take a soft vote or conformal sets are built from them.
+    pub(crate) const fn needs_probabilities(&self) -> bool {
+        !matches!(self.threshold_objective, ThresholdObjective::None)
+            || !matches!(self.self_training, SelfTraining::None)
+            || self.is_soft_voting()
+            || (self.is_classification() && !matches!(self.conformal, Conformal::None))
+    }
+
+    /// Whether the final model is a vote on the class probabilities of the best models
+    pub(crate) const fn is_soft_voting(&self) -> bool {
+        matches!(
+            self.final_model_approach,
+            FinalModel::Voting { soft: true, .. }
+        )
+    }
+
+    /// Whether the settings are for a classification problem
+    pub(crate) const fn is_classification(&self) -> bool {
+        matches!(self.model_type, ModelType::Classification)
     }
 
     /// Get the metric to sort by
@@ -140,10 +366,115 @@ impl Settings {
             Metric::MeanAbsoluteError => mean_absolute_error,
             Metric::MeanSquaredError => mean_squared_error,
             Metric::Accuracy => accuracy,
+            Metric::PoissonDeviance => mean_poisson_deviance,
+            Metric::GammaDeviance => mean_gamma_deviance,
             Metric::None => panic!("A metric must be set."),
         }
     }
 
+    /// Get a random number generator, seeded if a seed has been set. The seed is mixed with what
+    /// the numbers are for and the labels they are drawn over, so that, for instance, each training
+    /// fold is resampled with its own draws instead of repeating those of the others, while the
+    /// same seed and data still give the same draws.
+    pub(crate) fn rng(&self, purpose: &str, y: &[f32]) -> StdRng {
+        self.seed.map_or_else(StdRng::from_entropy, |seed| {
+            let seed = purpose
+                .bytes()
+                .map(u64::from)
+                .chain([y.len() as u64])
+                .chain(y.iter().map(|value| u64::from(value.to_bits())))
+                .fold(seed, mix_seed);
+            StdRng::seed_from_u64(seed)
+        })
+    }
+
+    /// Rebalance the classes of the training data using the selected resampling approach, then
+    /// apply the class weights by duplicating rows of the more heavily weighted classes
+    pub(crate) fn rebalance(
+        &self,
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+    ) -> (DenseMatrix<f32>, Vec<f32>) {
+        let mut rng = self.rng("rebalancing", y);
+        let (x, y) = self.resample(x, y, &mut rng);
+        match self.class_weights(&y) {
+            Some(weights) => weighted_oversample(&x, &y, &weights, &mut rng),
+            None => (x, y),
+        }
+    }
+
+    /// Rebalance the classes of the training data using the selected resampling approach only,
+    /// for algorithms that apply the class weights themselves
+    pub(crate) fn resample(
+        &self,
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        rng: &mut StdRng,
+    ) -> (DenseMatrix<f32>, Vec<f32>) {
+        match self.resampling {
+            Resampling::None => (x.clone(), y.to_vec()),
+            Resampling::RandomOversampling => random_oversample(x, y, rng),
+            Resampling::RandomUndersampling => random_undersample(x, y, rng),
+            Resampling::SMOTE { k_neighbors } => smote(x, y, k_neighbors, rng),
+        }
+    }
+
+    /// Wrap a `fit` function so that each training set is rebalanced before fitting
+    pub(crate) fn rebalanced<'a, H, E, F>(
+        &'a self,
+        fit: F,
+    ) -> impl Fn(&DenseMatrix<f32>, &Vec<f32>, H) -> Result<E, Failed> + 'a
+    where
+        F: Fn(&DenseMatrix<f32>, &Vec<f32>, H) -> Result<E, Failed> + 'a,
+    {
+        move |x, y, parameters| {
+            let (x, y) = self.rebalance(x, y);
+            fit(&x, &y, parameters)
+        }
+    }
+
+    /// Check that custom class weights have one positive weight per class
+    pub(crate) fn check_class_weights(&self, number_of_classes: usize) {
+        if let ClassWeight::Custom(weights) = &self.class_weight {
+            assert!(
+                weights.len() == number_of_classes,
+                "Custom class weights need one weight per class, but there are {} weights for {} \
+                 classes.",
+                weights.len(),
+                number_of_classes
+            );
+            assert!(
+                weights.iter().all(|&weight| weight > 0.0),
+                "Custom class weights must be positive."
+            );
+        }
+    }
+
+    /// Get the weight of a row of each class, in ascending order of class label, or `None` if
+    /// every row weighs the same
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn class_weights(&self, y: &[f32]) -> Option<Vec<f32>> {
+        let counts: Vec<f32> = class_indices(y).iter().map(|c| c.len() as f32).collect();
+        self.check_class_weights(counts.len());
+        match &self.class_weight {
+            ClassWeight::None => None,
+            ClassWeight::Balanced => Some(counts.iter().map(|c| 1.0 / c).collect()),
+            ClassWeight::Custom(weights) => Some(weights.clone()),
+        }
+    }
+
+    /// Get class priors implied by the class weights, or `None` if priors should be estimated from the data
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn class_priors(&self, y: &[f32]) -> Option<Vec<f32>> {
+        let counts = class_indices(y).into_iter().map(|c| c.len() as f32);
+        let weighted: Vec<f32> = counts
+            .zip(self.class_weights(y)?)
+            .map(|(c, w)| c * w)
+            .collect();
+        let total: f32 = weighted.iter().sum();
+        Some(weighted.iter().map(|w| w / total).collect())
+    }
+
     /// Creates default settings for regression
     /// ```
     /// # use automl::Settings;
@@ -163,19 +494,53 @@ impl Settings {
                 Algorithm::DecisionTreeClassifier,
                 Algorithm::CategoricalNaiveBayes,
                 Algorithm::GaussianNaiveBayes,
+                Algorithm::GradientBoostingClassifier,
+                Algorithm::ExtraTreesClassifier,
+                Algorithm::DummyClassifier,
+                Algorithm::BernoulliNaiveBayes,
+                Algorithm::MultinomialNaiveBayes,
+                Algorithm::MLPClassifier,
             ],
             preprocessing: PreProcessing::None,
+            resampling: Resampling::None,
+            class_weight: ClassWeight::None,
             number_of_folds: 10,
+            splitter: Splitter::KFold,
+            folds: None,
+            holdout_fraction: None,
+            outer_folds: None,
+            halving: Halving::None,
+            conformal: Conformal::None,
+            coverage: 0.9,
+            calibration: Calibration::None,
+            threshold_objective: ThresholdObjective::None,
+            self_training: SelfTraining::None,
+            multi_output: MultiOutput::PerTarget,
+            multi_label: MultiLabel::BinaryRelevance,
+            multi_label_metric: MultiLabelMetric::MicroF1,
+            forecast_settings: ForecastParameters::default(),
             shuffle: false,
+            seed: None,
             verbose: false,
             linear_settings: Some(LinearRegressionParameters::default()),
             svr_settings: Some(SVRParameters::default()),
             lasso_settings: Some(LassoParameters::default()),
             ridge_settings: Some(RidgeRegressionParameters::default()),
             elastic_net_settings: Some(ElasticNetParameters::default()),
+            huber_settings: Some(HuberParameters::default()),
+            quantile_settings: Some(QuantileParameters::default()),
+            poisson_settings: Some(PoissonParameters::default()),
+            gamma_settings: Some(GammaParameters::default()),
+            tweedie_settings: Some(TweedieParameters::default()),
+            gaussian_process_regressor_settings: Some(GaussianProcessRegressorParameters::default()),
             decision_tree_regressor_settings: Some(DecisionTreeRegressorParameters::default()),
             random_forest_regressor_settings: Some(RandomForestRegressorParameters::default()),
             knn_regressor_settings: Some(KNNRegressorParameters::default()),
+            gradient_boosting_regressor_settings: Some(
+                GradientBoostingRegressorParameters::default(),
+            ),
+            extra_trees_regressor_settings: Some(ExtraTreesRegressorParameters::default()),
+            dummy_regressor_settings: Some(DummyRegressorParameters::default()),
             logistic_settings: None,
             random_forest_classifier_settings: None,
             knn_classifier_settings: None,
@@ -183,6 +548,14 @@ impl Settings {
             decision_tree_classifier_settings: None,
             gaussian_nb_settings: None,
             categorical_nb_settings: None,
+            bernoulli_nb_settings: None,
+            multinomial_nb_settings: None,
+            gradient_boosting_classifier_settings: None,
+            extra_trees_classifier_settings: None,
+            dummy_classifier_settings: None,
+            bagging: vec![Algorithm::bagging(Algorithm::DecisionTreeRegressor)],
+            mlp_regressor_settings: Some(MLPRegressorParameters::default()),
+            mlp_classifier_settings: None,
         }
     }
 
@@ -202,23 +575,59 @@ impl Settings {
                 Algorithm::Lasso,
                 Algorithm::Ridge,
                 Algorithm::ElasticNet,
+                Algorithm::Huber,
+                Algorithm::Quantile,
+                Algorithm::Poisson,
+                Algorithm::Gamma,
+                Algorithm::Tweedie,
+                Algorithm::GaussianProcessRegressor,
                 Algorithm::SVR,
                 Algorithm::DecisionTreeRegressor,
                 Algorithm::RandomForestRegressor,
                 Algorithm::KNNRegressor,
+                Algorithm::GradientBoostingRegressor,
+                Algorithm::ExtraTreesRegressor,
+                Algorithm::DummyRegressor,
+                Algorithm::MLPRegressor,
             ],
             preprocessing: PreProcessing::None,
+            resampling: Resampling::None,
+            class_weight: ClassWeight::None,
             number_of_folds: 10,
+            splitter: Splitter::KFold,
+            folds: None,
+            holdout_fraction: None,
+            outer_folds: None,
+            halving: Halving::None,
+            conformal: Conformal::None,
+            coverage: 0.9,
+            calibration: Calibration::None,
+            threshold_objective: ThresholdObjective::None,
+            self_training: SelfTraining::None,
+            multi_output: MultiOutput::PerTarget,
+            multi_label: MultiLabel::BinaryRelevance,
+            multi_label_metric: MultiLabelMetric::MicroF1,
+            forecast_settings: ForecastParameters::default(),
             shuffle: false,
+            seed: None,
             verbose: false,
             linear_settings: None,
             svr_settings: None,
             lasso_settings: None,
             ridge_settings: None,
             elastic_net_settings: None,
+            huber_settings: None,
+            quantile_settings: None,
+            poisson_settings: None,
+            gamma_settings: None,
+            tweedie_settings: None,
+            gaussian_process_regressor_settings: None,
             decision_tree_regressor_settings: None,
             random_forest_regressor_settings: None,
             knn_regressor_settings: None,
+            gradient_boosting_regressor_settings: None,
+            extra_trees_regressor_settings: None,
+            dummy_regressor_settings: None,
             logistic_settings: Some(LogisticRegressionParameters::default()),
             random_forest_classifier_settings: Some(RandomForestClassifierParameters::default()),
             knn_classifier_settings: Some(KNNClassifierParameters::default()),
@@ -226,6 +635,16 @@ impl Settings {
             decision_tree_classifier_settings: Some(DecisionTreeClassifierParameters::default()),
             gaussian_nb_settings: Some(GaussianNBParameters::default()),
             categorical_nb_settings: Some(CategoricalNBParameters::default()),
+            bernoulli_nb_settings: Some(BernoulliNBParameters::default()),
+            multinomial_nb_settings: Some(MultinomialNBParameters::default()),
+            gradient_boosting_classifier_settings: Some(
+                GradientBoostingClassifierParameters::default(),
+            ),
+            extra_trees_classifier_settings: Some(ExtraTreesClassifierParameters::default()),
+            dummy_classifier_settings: Some(DummyClassifierParameters::default()),
+            bagging: vec![Algorithm::bagging(Algorithm::DecisionTreeClassifier)],
+            mlp_regressor_settings: None,
+            mlp_classifier_settings: Some(MLPClassifierParameters::default()),
         }
     }
 
@@ -271,6 +690,196 @@ impl Settings {
         self
     }
 
+    /// Specify a fraction of the data to hold out from model selection. Once training is done,
+    /// the final model is evaluated on this holdout set to give an unbiased score. With
+    /// `Splitter::TimeSeriesSplit`, the holdout set is the last rows.
+    /// ```
+    /// # use automl::Settings;
+    /// let settings = Settings::default_regression().with_holdout_fraction(0.2);
+    /// ```
+    #[must_use]
+    pub const fn with_holdout_fraction(mut self, fraction: f32) -> Self {
+        self.holdout_fraction = Some(fraction);
+        self
+    }
+
+    /// Run nested cross-validation with the given number of outer folds. The whole model
+    /// selection procedure is repeated inside each outer fold and the winner is scored on the
+    /// outer testing fold, giving an honest estimate of the performance of the procedure.
+    /// ```
+    /// # use automl::Settings;
+    /// let settings = Settings::default_regression().with_nested_cv(5);
+    /// ```
+    #[must_use]
+    pub const fn with_nested_cv(mut self, number_of_outer_folds: usize) -> Self {
+        self.outer_folds = Some(number_of_outer_folds);
+        self
+    }
+
+    /// Calibrate conformal prediction intervals (for regression) or prediction sets (for
+    /// classification) around the final model, to be used with `SupervisedModel::predict_interval`
+    /// or `SupervisedModel::predict_set`
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::Conformal;
+    /// let settings = Settings::default_regression()
+    ///     .with_conformal(Conformal::CVPlus { folds: 5 })
+    ///     .with_coverage(0.95);
+    /// ```
+    #[must_use]
+    pub const fn with_conformal(mut self, conformal: Conformal) -> Self {
+        self.conformal = conformal;
+        self
+    }
+
+    /// Specify the target coverage of conformal prediction intervals or sets, the probability
+    /// that they contain the true value
+    /// ```
+    /// # use automl::Settings;
+    /// let settings = Settings::default_regression().with_coverage(0.8);
+    /// ```
+    #[must_use]
+    pub const fn with_coverage(mut self, coverage: f32) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    /// Calibrate the class probabilities of every classifier in the comparison that has scores
+    /// (see `Calibration`), to be used with `SupervisedModel::predict_proba`. The Brier score of
+    /// the calibrated probabilities is reported in the comparison.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::Calibration;
+    /// let settings = Settings::default_classification()
+    ///     .with_calibration(Calibration::Isotonic { folds: 5 });
+    /// ```
+    #[must_use]
+    pub const fn with_calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Tune the decision threshold of a binary classifier on its calibrated probabilities, so
+    /// that `SupervisedModel::predict` favors the outcomes that matter most. Only algorithms with
+    /// scores to calibrate (see `Calibration`) are compared.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ThresholdObjective;
+    /// // False negatives cost twenty times as much as false positives
+    /// let settings = Settings::default_classification().with_threshold_objective(
+    ///     ThresholdObjective::Cost {
+    ///         matrix: [[0.0, 1.0], [20.0, 0.0]],
+    ///     },
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_threshold_objective(
+        mut self,
+        threshold_objective: ThresholdObjective,
+    ) -> Self {
+        self.threshold_objective = threshold_objective;
+        self
+    }
+
+    /// Self-train classifiers on the unlabeled rows given to `SupervisedModel::new_with_unlabeled`,
+    /// adding confident predictions to the training data as pseudo-labels
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::SelfTraining;
+    /// let settings = Settings::default_classification().with_self_training(
+    ///     SelfTraining::ConfidenceThreshold {
+    ///         threshold: 0.95,
+    ///         max_iterations: 5,
+    ///     },
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_self_training(mut self, self_training: SelfTraining) -> Self {
+        self.self_training = self_training;
+        self
+    }
+
+    /// Specify how a `MultiOutputModel` fits algorithms without native multi-output support
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::MultiOutput;
+    /// let settings = Settings::default_regression().with_multi_output(MultiOutput::Chain);
+    /// ```
+    #[must_use]
+    pub const fn with_multi_output(mut self, multi_output: MultiOutput) -> Self {
+        self.multi_output = multi_output;
+        self
+    }
+
+    /// Specify how a `MultiLabelModel` fits classifiers to several labels
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::MultiLabel;
+    /// let settings = Settings::default_classification().with_multi_label(MultiLabel::Chain);
+    /// ```
+    #[must_use]
+    pub const fn with_multi_label(mut self, multi_label: MultiLabel) -> Self {
+        self.multi_label = multi_label;
+        self
+    }
+
+    /// Specify the metric used to sort the models of a `MultiLabelModel`
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::MultiLabelMetric;
+    /// let settings = Settings::default_classification()
+    ///     .with_multi_label_metric(MultiLabelMetric::HammingLoss);
+    /// ```
+    #[must_use]
+    pub const fn with_multi_label_metric(mut self, metric: MultiLabelMetric) -> Self {
+        self.multi_label_metric = metric;
+        self
+    }
+
+    /// Specify the features a `ForecastModel` builds from the history of the target
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ForecastParameters;
+    /// let settings = Settings::default_regression().with_forecast_settings(
+    ///     ForecastParameters::default()
+    ///         .with_lags(vec![1, 2, 12])
+    ///         .with_rolling_windows(vec![4, 12])
+    ///         .with_deltas(2),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_forecast_settings(mut self, settings: ForecastParameters) -> Self {
+        self.forecast_settings = settings;
+        self
+    }
+
+    /// Eliminate weak candidates early by scoring them on subsamples of the data first
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::Halving;
+    /// let settings = Settings::default_regression().with_halving(Halving::Successive {
+    ///     min_fraction: 0.25,
+    ///     reduction_factor: 2,
+    /// });
+    /// ```
+    #[must_use]
+    pub const fn with_halving(mut self, halving: Halving) -> Self {
+        self.halving = halving;
+        self
+    }
+
+    /// Specify how the data should be split for cross-validation
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::Splitter;
+    /// let settings = Settings::default_classification().with_splitter(Splitter::StratifiedKFold);
+    /// ```
+    #[must_use]
+    pub const fn with_splitter(mut self, splitter: Splitter) -> Self {
+        self.splitter = splitter;
+        self
+    }
+
     /// Specify whether or not data should be shuffled
     /// ```
     /// # use automl::Settings;
@@ -282,6 +891,18 @@ impl Settings {
         self
     }
 
+    /// Specify a seed that makes every run with these settings reproducible. The seed drives
+    /// fold shuffling, blending splits and resampling, and replaces the seed of the random forests.
+    /// ```
+    /// # use automl::Settings;
+    /// let settings = Settings::default().shuffle_data(true).with_seed(42);
+    /// ```
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Specify whether or not to be verbose
     /// ```
     /// # use automl::Settings;
@@ -305,14 +926,46 @@ impl Settings {
         self
     }
 
+    /// Specify how classes should be rebalanced in each training fold
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::Resampling;
+    /// let settings = Settings::default_classification()
+    ///     .with_resampling(Resampling::SMOTE { k_neighbors: 5 });
+    /// ```
+    #[must_use]
+    pub const fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// Specify class weights for classification. Gaussian naive Bayes (unless its priors have been
+    /// set explicitly) and the dummy classifier with the prior strategy take them as class priors.
+    /// Every other classifier is trained on each training fold with the rows of each class
+    /// duplicated at random in proportion to its weight, after any resampling from
+    /// `Settings::with_resampling`. The other strategies of the dummy classifier ignore them, since
+    /// the baseline reflects the classes actually observed.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ClassWeight;
+    /// let settings = Settings::default_classification().with_class_weight(ClassWeight::Balanced);
+    /// ```
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_class_weight(mut self, class_weight: ClassWeight) -> Self {
+        self.class_weight = class_weight;
+        self
+    }
+
     /// Specify what type of final model to use
     /// ```
     /// # use automl::Settings;
     /// use automl::settings::FinalModel;
     /// let settings = Settings::default().with_final_model(FinalModel::Best);
     /// ```
+    #[allow(clippy::missing_const_for_fn)]
     #[must_use]
-    pub const fn with_final_model(mut self, approach: FinalModel) -> Self {
+    pub fn with_final_model(mut self, approach: FinalModel) -> Self {
         self.final_model_approach = approach;
         self
     }
@@ -338,7 +991,12 @@ impl Settings {
     #[must_use]
     pub fn only(mut self, only: Algorithm) -> Self {
         self.skiplist = Self::default().skiplist;
-        self.skiplist.retain(|&algo| algo != only);
+        self.skiplist.retain(|algo| *algo != only);
+        self.bagging = if matches!(only, Algorithm::Bagging { .. }) {
+            vec![only]
+        } else {
+            vec![]
+        };
         self
     }
 
@@ -395,13 +1053,14 @@ impl Settings {
     /// Specify settings for support vector classifier
     /// ```
     /// # use automl::Settings;
-    /// use automl::settings::{SVCParameters, Kernel};
+    /// use automl::settings::{SVCParameters, Kernel, MulticlassStrategy};
     /// let settings = Settings::default()    
     ///     .with_svc_settings(SVCParameters::default()
     ///         .with_epoch(10)
     ///         .with_tol(1e-10)
     ///         .with_c(1.0)
     ///         .with_kernel(Kernel::Linear)
+    ///         .with_multiclass(MulticlassStrategy::OneVsRest)
     ///     );
     /// ```
     #[must_use]
@@ -483,6 +1142,39 @@ impl Settings {
         self
     }
 
+    /// Specify settings for Bernoulli Naive Bayes
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::BernoulliNBParameters;
+    /// let settings = Settings::default()
+    ///     .with_bernoulli_nb_settings(BernoulliNBParameters::default()
+    ///         .with_alpha(1.0)
+    ///         .with_binarize(0.5)
+    ///     );
+    /// ```
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_bernoulli_nb_settings(mut self, settings: BernoulliNBParameters<f32>) -> Self {
+        self.bernoulli_nb_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Multinomial Naive Bayes
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::MultinomialNBParameters;
+    /// let settings = Settings::default()
+    ///     .with_multinomial_nb_settings(MultinomialNBParameters::default()
+    ///         .with_alpha(0.5)
+    ///     );
+    /// ```
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_multinomial_nb_settings(mut self, settings: MultinomialNBParameters<f32>) -> Self {
+        self.multinomial_nb_settings = Some(settings);
+        self
+    }
+
     /// Specify settings for linear regression
     /// ```
     /// # use automl::Settings;
@@ -552,6 +1244,117 @@ impl Settings {
         self
     }
 
+    /// Specify settings for Huber regression
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::HuberParameters;
+    /// let settings = Settings::default()
+    ///     .with_huber_settings(HuberParameters::default()
+    ///         .with_epsilon(1.5)
+    ///         .with_alpha(0.01)
+    ///         .with_max_iter(50)
+    ///         .with_tol(1e-6)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_huber_settings(mut self, settings: HuberParameters) -> Self {
+        self.huber_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for quantile regression
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::QuantileParameters;
+    /// let settings = Settings::default()
+    ///     .with_quantile_settings(QuantileParameters::default()
+    ///         .with_quantile(0.9)
+    ///         .with_alpha(0.01)
+    ///         .with_max_iter(50)
+    ///         .with_tol(1e-6)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_quantile_settings(mut self, settings: QuantileParameters) -> Self {
+        self.quantile_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Poisson regression
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::PoissonParameters;
+    /// let settings = Settings::default()
+    ///     .with_poisson_settings(PoissonParameters::default()
+    ///         .with_alpha(0.01)
+    ///         .with_max_iter(50)
+    ///         .with_tol(1e-6)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_poisson_settings(mut self, settings: PoissonParameters) -> Self {
+        self.poisson_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Gamma regression
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::GammaParameters;
+    /// let settings = Settings::default()
+    ///     .with_gamma_settings(GammaParameters::default()
+    ///         .with_alpha(0.01)
+    ///         .with_max_iter(50)
+    ///         .with_tol(1e-6)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_gamma_settings(mut self, settings: GammaParameters) -> Self {
+        self.gamma_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Tweedie regression
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::TweedieParameters;
+    /// let settings = Settings::default()
+    ///     .with_tweedie_settings(TweedieParameters::default()
+    ///         .with_power(1.2)
+    ///         .with_alpha(0.01)
+    ///         .with_max_iter(50)
+    ///         .with_tol(1e-6)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_tweedie_settings(mut self, settings: TweedieParameters) -> Self {
+        self.tweedie_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for Gaussian process regression
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{GaussianProcessKernel, GaussianProcessRegressorParameters};
+    /// let settings = Settings::default()
+    ///     .with_gaussian_process_regressor_settings(GaussianProcessRegressorParameters::default()
+    ///         .with_kernel(GaussianProcessKernel::Matern { nu: 2.5 })
+    ///         .with_length_scale(2.0)
+    ///         .with_signal_variance(1.0)
+    ///         .with_noise(0.01)
+    ///         .with_max_iter(100)
+    ///         .with_max_samples(500)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_gaussian_process_regressor_settings(
+        mut self,
+        settings: GaussianProcessRegressorParameters,
+    ) -> Self {
+        self.gaussian_process_regressor_settings = Some(settings);
+        self
+    }
+
     /// Specify settings for KNN regressor
     /// ```
     /// # use automl::Settings;
@@ -630,21 +1433,215 @@ impl Settings {
         self.decision_tree_regressor_settings = Some(settings);
         self
     }
-}
 
-impl Display for Settings {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // Prep new table
-        let mut table = Table::new();
+    /// Specify settings for gradient boosting regressor
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{GradientBoostingLoss, GradientBoostingRegressorParameters};
+    /// let settings = Settings::default()
+    ///     .with_gradient_boosting_regressor_settings(GradientBoostingRegressorParameters::default()
+    ///         .with_loss(GradientBoostingLoss::AbsoluteError)
+    ///         .with_n_estimators(200)
+    ///         .with_learning_rate(0.05)
+    ///         .with_max_depth(4)
+    ///         .with_subsample(0.8)
+    ///         .with_early_stopping(10, 0.1)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_gradient_boosting_regressor_settings(
+        mut self,
+        settings: GradientBoostingRegressorParameters,
+    ) -> Self {
+        self.gradient_boosting_regressor_settings = Some(settings);
+        self
+    }
 
-        // Get list of algorithms to skip
-        let mut skiplist = String::new();
-        if self.skiplist.is_empty() {
-            skiplist.push_str("None ");
-        } else {
-            for algorithm_to_skip in &self.skiplist {
-                skiplist.push_str(&format!("{algorithm_to_skip}\n"));
-            }
+    /// Specify settings for gradient boosting classifier
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::GradientBoostingClassifierParameters;
+    /// let settings = Settings::default()
+    ///     .with_gradient_boosting_classifier_settings(GradientBoostingClassifierParameters::default()
+    ///         .with_n_estimators(200)
+    ///         .with_learning_rate(0.05)
+    ///         .with_max_depth(4)
+    ///         .with_min_samples_leaf(5)
+    ///         .with_early_stopping(10, 0.1)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_gradient_boosting_classifier_settings(
+        mut self,
+        settings: GradientBoostingClassifierParameters,
+    ) -> Self {
+        self.gradient_boosting_classifier_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for extra trees regressor
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ExtraTreesRegressorParameters;
+    /// let settings = Settings::default()
+    ///     .with_extra_trees_regressor_settings(ExtraTreesRegressorParameters::default()
+    ///         .with_n_trees(200)
+    ///         .with_max_depth(8)
+    ///         .with_min_samples_leaf(2)
+    ///         .with_min_samples_split(4)
+    ///         .with_m(3)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_extra_trees_regressor_settings(
+        mut self,
+        settings: ExtraTreesRegressorParameters,
+    ) -> Self {
+        self.extra_trees_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for extra trees classifier
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::ExtraTreesClassifierParameters;
+    /// let settings = Settings::default()
+    ///     .with_extra_trees_classifier_settings(ExtraTreesClassifierParameters::default()
+    ///         .with_n_trees(200)
+    ///         .with_max_depth(8)
+    ///         .with_min_samples_leaf(2)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_extra_trees_classifier_settings(
+        mut self,
+        settings: ExtraTreesClassifierParameters,
+    ) -> Self {
+        self.extra_trees_classifier_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for the baseline dummy regressor
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{DummyRegressorParameters, DummyRegressorStrategy};
+    /// let settings = Settings::default_regression()
+    ///     .with_dummy_regressor_settings(DummyRegressorParameters::default()
+    ///         .with_strategy(DummyRegressorStrategy::Quantile(0.9))
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_dummy_regressor_settings(
+        mut self,
+        settings: DummyRegressorParameters,
+    ) -> Self {
+        self.dummy_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for the baseline dummy classifier
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{DummyClassifierParameters, DummyClassifierStrategy};
+    /// let settings = Settings::default_classification()
+    ///     .with_dummy_classifier_settings(DummyClassifierParameters::default()
+    ///         .with_strategy(DummyClassifierStrategy::Stratified)
+    ///     );
+    /// ```
+    #[must_use]
+    pub const fn with_dummy_classifier_settings(
+        mut self,
+        settings: DummyClassifierParameters,
+    ) -> Self {
+        self.dummy_classifier_settings = Some(settings);
+        self
+    }
+
+    /// Add a bagging ensemble to the comparison. Ensembles around several base algorithms can be
+    /// compared in one run. The base algorithm is trained with its own settings, so they must be
+    /// set as well.
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::Algorithm;
+    /// let settings = Settings::default_regression()
+    ///     .with_bagging(Algorithm::Bagging {
+    ///         base: Box::new(Algorithm::KNNRegressor),
+    ///         n_estimators: 20,
+    ///         max_samples: 0.8,
+    ///         max_features: 0.5,
+    ///     })
+    ///     .with_bagging(Algorithm::bagging(Algorithm::SVR));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the algorithm is not `Algorithm::Bagging`, this function will panic.
+    #[must_use]
+    pub fn with_bagging(mut self, bagging: Algorithm) -> Self {
+        assert!(
+            matches!(bagging, Algorithm::Bagging { .. }),
+            "Only bagging ensembles can be added to the comparison."
+        );
+        self.bagging.push(bagging);
+        self
+    }
+
+    /// Specify settings for the multilayer perceptron regressor
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{Activation, MLPRegressorParameters, Optimizer};
+    /// let settings = Settings::default()
+    ///     .with_mlp_regressor_settings(MLPRegressorParameters::default()
+    ///         .with_hidden_layer_sizes(vec![64, 32])
+    ///         .with_activation(Activation::Tanh)
+    ///         .with_optimizer(Optimizer::default_sgd())
+    ///         .with_learning_rate(0.01)
+    ///         .with_alpha(0.001)
+    ///         .with_batch_size(32)
+    ///         .with_max_epochs(100)
+    ///         .with_early_stopping(10, 0.1)
+    ///     );
+    /// ```
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_mlp_regressor_settings(mut self, settings: MLPRegressorParameters) -> Self {
+        self.mlp_regressor_settings = Some(settings);
+        self
+    }
+
+    /// Specify settings for the multilayer perceptron classifier
+    /// ```
+    /// # use automl::Settings;
+    /// use automl::settings::{Activation, MLPClassifierParameters, Optimizer};
+    /// let settings = Settings::default()
+    ///     .with_mlp_classifier_settings(MLPClassifierParameters::default()
+    ///         .with_hidden_layer_sizes(vec![32])
+    ///         .with_activation(Activation::Logistic)
+    ///         .with_optimizer(Optimizer::Adam { beta_1: 0.9, beta_2: 0.99 })
+    ///         .with_early_stopping(5, 0.2)
+    ///     );
+    /// ```
+    #[allow(clippy::missing_const_for_fn)]
+    #[must_use]
+    pub fn with_mlp_classifier_settings(mut self, settings: MLPClassifierParameters) -> Self {
+        self.mlp_classifier_settings = Some(settings);
+        self
+    }
+}
+
+impl Display for Settings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Prep new table
+        let mut table = Table::new();
+
+        // Get list of algorithms to skip
+        let mut skiplist = String::new();
+        if self.skiplist.is_empty() {
+            skiplist.push_str("None ");
+        } else {
+            for algorithm_to_skip in &self.skiplist {
+                skiplist.push_str(&format!("{algorithm_to_skip}\n"));
+            }
         }
 
         // Build out the table
@@ -660,14 +1657,51 @@ impl Display for Settings {
             .add_row(vec!["    Verbose", &*format!("{}", self.verbose)])
             .add_row(vec!["    Sorting Metric", &*format!("{}", self.sort_by)])
             .add_row(vec!["    Shuffle Data", &*format!("{}", self.shuffle)])
+            .add_row(vec!["    Seed", &*print_option(self.seed)])
             .add_row(vec![
                 "    Number of CV Folds",
                 &*format!("{}", self.number_of_folds),
             ])
+            .add_row(vec!["    CV Splitter", &*format!("{}", self.splitter)])
+            .add_row(vec![
+                "    Holdout Fraction",
+                &*print_option(self.holdout_fraction),
+            ])
+            .add_row(vec!["    Early Elimination", &*format!("{}", self.halving)])
+            .add_row(vec![
+                "    Nested CV Outer Folds",
+                &*print_option(self.outer_folds),
+            ])
+            .add_row(vec!["    Conformal", &*format!("{}", self.conformal)])
+            .add_row(vec!["    Target Coverage", &*format!("{}", self.coverage)])
+            .add_row(vec!["    Calibration", &*format!("{}", self.calibration)])
+            .add_row(vec![
+                "    Threshold Objective",
+                &*format!("{}", self.threshold_objective),
+            ])
+            .add_row(vec![
+                "    Self-Training",
+                &*format!("{}", self.self_training),
+            ])
+            .add_row(vec!["    Multi-Output", &*format!("{}", self.multi_output)])
+            .add_row(vec!["    Multi-Label", &*format!("{}", self.multi_label)])
+            .add_row(vec![
+                "    Multi-Label Metric",
+                &*format!("{}", self.multi_label_metric),
+            ])
+            .add_row(vec![
+                "    Forecast Features",
+                &*format!("{}", self.forecast_settings),
+            ])
             .add_row(vec![
                 "    Pre-Processing",
                 &*format!("{}", self.preprocessing),
             ])
+            .add_row(vec!["    Resampling", &*format!("{}", self.resampling)])
+            .add_row(vec![
+                "    Class Weights",
+                &*format!("{}", self.class_weight),
+            ])
             .add_row(vec![
                 "    Skipped Algorithms",
                 &skiplist[0..skiplist.len() - 1],
@@ -757,6 +1791,105 @@ impl Display for Settings {
                 ]);
         }
 
+        if !self.skiplist.contains(&Algorithm::Huber) {
+            let parameters = self.huber_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::Huber).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Epsilon", &*format!("{}", parameters.epsilon)])
+                .add_row(vec!["    Alpha", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::Quantile) {
+            let parameters = self.quantile_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::Quantile).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Quantile", &*format!("{}", parameters.quantile)])
+                .add_row(vec!["    Alpha", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::Poisson) {
+            let parameters = self.poisson_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::Poisson).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Alpha", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::Gamma) {
+            let parameters = self.gamma_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::Gamma).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Alpha", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::Tweedie) {
+            let parameters = self.tweedie_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::Tweedie).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Power", &*format!("{}", parameters.power)])
+                .add_row(vec!["    Alpha", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec!["    Tolerance", &*format!("{}", parameters.tol)]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::GaussianProcessRegressor) {
+            let parameters = self.gaussian_process_regressor_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::GaussianProcessRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec!["    Kernel", &*format!("{}", parameters.kernel)])
+                .add_row(vec![
+                    "    Length Scale",
+                    &*format!("{}", parameters.length_scale),
+                ])
+                .add_row(vec![
+                    "    Signal Variance",
+                    &*format!("{}", parameters.signal_variance),
+                ])
+                .add_row(vec!["    Noise", &*format!("{}", parameters.noise)])
+                .add_row(vec![
+                    "    Maximum Iterations",
+                    &*format!("{}", parameters.max_iter),
+                ])
+                .add_row(vec![
+                    "    Maximum Samples",
+                    &*format!("{}", parameters.max_samples),
+                ]);
+        }
+
         if !self.skiplist.contains(&Algorithm::DecisionTreeRegressor) {
             table
                 .add_row(vec![
@@ -1017,6 +2150,10 @@ impl Display for Settings {
                 .add_row(vec![
                     "    Kernel",
                     &*format!("{}", self.svc_settings.as_ref().unwrap().kernel),
+                ])
+                .add_row(vec![
+                    "    Multiclass strategy",
+                    &*format!("{}", self.svc_settings.as_ref().unwrap().multiclass),
                 ]);
         }
 
@@ -1091,12 +2228,277 @@ impl Display for Settings {
                 ]);
         }
 
+        if !self.skiplist.contains(&Algorithm::BernoulliNaiveBayes) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::BernoulliNaiveBayes).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Smoothing parameter",
+                    &*format!("{}", self.bernoulli_nb_settings.as_ref().unwrap().alpha),
+                ])
+                .add_row(vec![
+                    "    Binarize threshold",
+                    &*print_option(self.bernoulli_nb_settings.as_ref().unwrap().binarize),
+                ])
+                .add_row(vec![
+                    "    Priors",
+                    &*debug_option(self.bernoulli_nb_settings.as_ref().unwrap().clone().priors),
+                ]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::MultinomialNaiveBayes) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::MultinomialNaiveBayes).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Smoothing parameter",
+                    &*format!("{}", self.multinomial_nb_settings.as_ref().unwrap().alpha),
+                ])
+                .add_row(vec![
+                    "    Priors",
+                    &*debug_option(
+                        self.multinomial_nb_settings
+                            .as_ref()
+                            .unwrap()
+                            .clone()
+                            .priors,
+                    ),
+                ]);
+        }
+
+        if !self
+            .skiplist
+            .contains(&Algorithm::GradientBoostingRegressor)
+        {
+            let parameters = self.gradient_boosting_regressor_settings.as_ref().unwrap();
+            table
+                .add_row(vec![Cell::new(Algorithm::GradientBoostingRegressor)
+                    .add_attribute(Attribute::Italic)])
+                .add_row(vec!["    Loss", &*format!("{}", parameters.loss)])
+                .add_row(vec![
+                    "    Number of estimators",
+                    &*format!("{}", parameters.n_estimators),
+                ])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec!["    Max Depth", &*format!("{}", parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec!["    Subsample", &*format!("{}", parameters.subsample)])
+                .add_row(vec![
+                    "    Early stopping rounds",
+                    &*print_option(parameters.early_stopping_rounds),
+                ]);
+        }
+
+        if !self
+            .skiplist
+            .contains(&Algorithm::GradientBoostingClassifier)
+        {
+            let parameters = self.gradient_boosting_classifier_settings.as_ref().unwrap();
+            table
+                .add_row(vec![Cell::new(Algorithm::GradientBoostingClassifier)
+                    .add_attribute(Attribute::Italic)])
+                .add_row(vec![
+                    "    Number of estimators",
+                    &*format!("{}", parameters.n_estimators),
+                ])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec!["    Max Depth", &*format!("{}", parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec!["    Subsample", &*format!("{}", parameters.subsample)])
+                .add_row(vec![
+                    "    Early stopping rounds",
+                    &*print_option(parameters.early_stopping_rounds),
+                ]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::ExtraTreesRegressor) {
+            let parameters = self.extra_trees_regressor_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::ExtraTreesRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Number of trees",
+                    &*format!("{}", parameters.n_trees),
+                ])
+                .add_row(vec!["    Max Depth", &*print_option(parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", parameters.min_samples_split),
+                ])
+                .add_row(vec![
+                    "    Number of split candidates",
+                    &*print_option(parameters.m),
+                ]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::ExtraTreesClassifier) {
+            let parameters = self.extra_trees_classifier_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::ExtraTreesClassifier).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Number of trees",
+                    &*format!("{}", parameters.n_trees),
+                ])
+                .add_row(vec!["    Max Depth", &*print_option(parameters.max_depth)])
+                .add_row(vec![
+                    "    Min samples for leaf",
+                    &*format!("{}", parameters.min_samples_leaf),
+                ])
+                .add_row(vec![
+                    "    Min samples for split",
+                    &*format!("{}", parameters.min_samples_split),
+                ])
+                .add_row(vec![
+                    "    Number of split candidates",
+                    &*print_option(parameters.m),
+                ]);
+        }
+
+        for bagging in &self.bagging {
+            if let Algorithm::Bagging {
+                base,
+                n_estimators,
+                max_samples,
+                max_features,
+            } = bagging
+            {
+                if !self.skiplist.contains(bagging) {
+                    table
+                        .add_row(vec![Cell::new(bagging).add_attribute(Attribute::Italic)])
+                        .add_row(vec!["    Base algorithm", &*format!("{base}")])
+                        .add_row(vec![
+                            "    Number of estimators",
+                            &*format!("{n_estimators}"),
+                        ])
+                        .add_row(vec!["    Max samples", &*format!("{max_samples}")])
+                        .add_row(vec!["    Max features", &*format!("{max_features}")]);
+                }
+            }
+        }
+
+        if !self.skiplist.contains(&Algorithm::MLPRegressor) {
+            let parameters = self.mlp_regressor_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::MLPRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Hidden layer sizes",
+                    &*format!("{:?}", parameters.hidden_layer_sizes),
+                ])
+                .add_row(vec![
+                    "    Activation",
+                    &*format!("{}", parameters.activation),
+                ])
+                .add_row(vec!["    Optimizer", &*format!("{}", parameters.optimizer)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec!["    L2 penalty", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Batch size",
+                    &*format!("{}", parameters.batch_size),
+                ])
+                .add_row(vec![
+                    "    Max epochs",
+                    &*format!("{}", parameters.max_epochs),
+                ])
+                .add_row(vec![
+                    "    Early stopping rounds",
+                    &*print_option(parameters.early_stopping_rounds),
+                ]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::MLPClassifier) {
+            let parameters = self.mlp_classifier_settings.as_ref().unwrap();
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::MLPClassifier).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Hidden layer sizes",
+                    &*format!("{:?}", parameters.hidden_layer_sizes),
+                ])
+                .add_row(vec![
+                    "    Activation",
+                    &*format!("{}", parameters.activation),
+                ])
+                .add_row(vec!["    Optimizer", &*format!("{}", parameters.optimizer)])
+                .add_row(vec![
+                    "    Learning rate",
+                    &*format!("{}", parameters.learning_rate),
+                ])
+                .add_row(vec!["    L2 penalty", &*format!("{}", parameters.alpha)])
+                .add_row(vec![
+                    "    Batch size",
+                    &*format!("{}", parameters.batch_size),
+                ])
+                .add_row(vec![
+                    "    Max epochs",
+                    &*format!("{}", parameters.max_epochs),
+                ])
+                .add_row(vec![
+                    "    Early stopping rounds",
+                    &*print_option(parameters.early_stopping_rounds),
+                ]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::DummyRegressor) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::DummyRegressor).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Strategy",
+                    &*format!(
+                        "{}",
+                        self.dummy_regressor_settings.as_ref().unwrap().strategy
+                    ),
+                ]);
+        }
+
+        if !self.skiplist.contains(&Algorithm::DummyClassifier) {
+            table
+                .add_row(vec![
+                    Cell::new(Algorithm::DummyClassifier).add_attribute(Attribute::Italic)
+                ])
+                .add_row(vec![
+                    "    Strategy",
+                    &*format!(
+                        "{}",
+                        self.dummy_classifier_settings.as_ref().unwrap().strategy
+                    ),
+                ]);
+        }
+
         writeln!(f, "{table}")
     }
 }
 
 /// Model type to train
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 enum ModelType {
     /// No model type specified
     None,
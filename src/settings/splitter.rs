@@ -0,0 +1,240 @@
+//! Cross-validation splitting strategies
+
+use rand::{rngs::StdRng, seq::SliceRandom};
+use smartcore::{
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix, Matrix},
+    math::num::RealNumber,
+    model_selection::BaseKFold,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use crate::utils::class_indices;
+
+/// Strategies for splitting the data into training and testing folds during cross-validation.
+/// All strategies use the number of folds set with `Settings::with_number_of_folds`.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub enum Splitter {
+    /// Plain k-fold cross-validation
+    KFold,
+    /// K-fold cross-validation that preserves the proportion of each class in every fold
+    StratifiedKFold,
+    /// K-fold cross-validation in which all rows sharing a group never appear in both the
+    /// training and testing folds
+    GroupKFold {
+        /// The index of the input column holding the group of each row, which only splits the
+        /// data and is dropped from the features before preprocessing and training
+        group_column: usize,
+    },
+    /// K-fold cross-validation repeated several times with a different shuffle each time
+    RepeatedKFold {
+        /// The number of times to repeat k-fold cross-validation
+        n_repeats: usize,
+    },
+    /// Independent random train/test splits
+    ShuffleSplit {
+        /// The fraction of the data to use for testing in each split
+        test_fraction: f32,
+    },
+    /// Expanding-window splits for time-ordered data, always testing on rows that come after
+    /// the training rows. Holdout, blending and split conformal data are the last rows, too.
+    TimeSeriesSplit,
+}
+
+impl Display for Splitter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KFold => write!(f, "K-Fold"),
+            Self::StratifiedKFold => write!(f, "Stratified K-Fold"),
+            Self::GroupKFold { group_column } => {
+                write!(f, "Group K-Fold\n    group column = {group_column}")
+            }
+            Self::RepeatedKFold { n_repeats } => {
+                write!(f, "Repeated K-Fold\n    repeats = {n_repeats}")
+            }
+            Self::ShuffleSplit { test_fraction } => {
+                write!(f, "Shuffle Split\n    test fraction = {test_fraction}")
+            }
+            Self::TimeSeriesSplit => write!(f, "Time Series Split"),
+        }
+    }
+}
+
+impl Splitter {
+    /// Generate the training and testing indices for each split of the data.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub(crate) fn split(
+        &self,
+        x: &DenseMatrix<f32>,
+        y: &[f32],
+        number_of_folds: usize,
+        shuffle: bool,
+        rng: &mut StdRng,
+    ) -> Folds {
+        let n = y.len();
+        let test_sets = match self {
+            Self::KFold => kfold_test_sets(n, number_of_folds, shuffle, rng),
+            Self::StratifiedKFold => stratified_test_sets(y, number_of_folds, shuffle, rng),
+            Self::GroupKFold { group_column } => {
+                assert!(
+                    *group_column < x.shape().1,
+                    "The group column {group_column} is out of range for data with {} features.",
+                    x.shape().1
+                );
+                group_test_sets(&x.get_col_as_vec(*group_column), number_of_folds)
+            }
+            Self::RepeatedKFold { n_repeats } => (0..*n_repeats)
+                .flat_map(|_| kfold_test_sets(n, number_of_folds, true, rng))
+                .collect(),
+            Self::ShuffleSplit { test_fraction } => {
+                let n_test = ((n as f32) * test_fraction).round().max(1.0) as usize;
+                (0..number_of_folds)
+                    .map(|_| {
+                        let mut indices: Vec<usize> = (0..n).collect();
+                        indices.shuffle(rng);
+                        indices.truncate(n_test);
+                        indices
+                    })
+                    .collect()
+            }
+            Self::TimeSeriesSplit => {
+                let folds = kfold_test_sets(n, number_of_folds + 1, false, rng);
+                return Folds {
+                    splits: (1..folds.len())
+                        .map(|i| (folds[..i].concat(), folds[i].clone()))
+                        .collect(),
+                };
+            }
+        };
+
+        Folds {
+            splits: test_sets
+                .into_iter()
+                .map(|mut test| {
+                    test.sort_unstable();
+                    let mut is_test = vec![false; n];
+                    for &idx in &test {
+                        is_test[idx] = true;
+                    }
+                    ((0..n).filter(|&idx| !is_test[idx]).collect(), test)
+                })
+                .filter(|(train, test): &(Vec<usize>, Vec<usize>)| {
+                    !train.is_empty() && !test.is_empty()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Training and testing indices for each split, usable anywhere smartcore expects a `KFold`
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Folds {
+    /// The training and testing indices of each split
+    splits: Vec<(Vec<usize>, Vec<usize>)>,
+}
+
+impl Folds {
+    /// The splits of some of the rows, with each row indexed by its position among them. Splits
+    /// left without training or testing rows are dropped.
+    pub(crate) fn restricted_to(&self, rows: &[usize]) -> Self {
+        let positions: HashMap<usize, usize> = rows
+            .iter()
+            .enumerate()
+            .map(|(position, &row)| (row, position))
+            .collect();
+        let restrict = |indices: &[usize]| -> Vec<usize> {
+            indices
+                .iter()
+                .filter_map(|idx| positions.get(idx).copied())
+                .collect()
+        };
+        Self {
+            splits: self
+                .splits
+                .iter()
+                .map(|(train, test)| (restrict(train), restrict(test)))
+                .filter(|(train, test)| !train.is_empty() && !test.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Add rows to the training indices of every split, so they are trained on but never tested
+    pub(crate) fn with_train_only(mut self, rows: Range<usize>) -> Self {
+        for (train, _) in &mut self.splits {
+            train.extend(rows.clone());
+        }
+        self
+    }
+
+    /// The average ratio of testing rows to training rows across splits
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn test_train_ratio(&self) -> f32 {
+        self.splits
+            .iter()
+            .map(|(train, test)| test.len() as f32 / train.len() as f32)
+            .sum::<f32>()
+            / self.splits.len().max(1) as f32
+    }
+}
+
+impl BaseKFold for Folds {
+    type Output = std::vec::IntoIter<(Vec<usize>, Vec<usize>)>;
+
+    fn split<T: RealNumber, M: Matrix<T>>(&self, _x: &M) -> Self::Output {
+        self.splits.clone().into_iter()
+    }
+
+    fn n_splits(&self) -> usize {
+        self.splits.len()
+    }
+}
+
+/// Divide `n` rows into `k` contiguous (or shuffled) folds of nearly equal size.
+fn kfold_test_sets(n: usize, k: usize, shuffle: bool, rng: &mut StdRng) -> Vec<Vec<usize>> {
+    let mut indices: Vec<usize> = (0..n).collect();
+    if shuffle {
+        indices.shuffle(rng);
+    }
+    let mut start = 0;
+    (0..k)
+        .map(|fold| {
+            let size = n / k + usize::from(fold < n % k);
+            let test = indices[start..start + size].to_vec();
+            start += size;
+            test
+        })
+        .collect()
+}
+
+/// Deal the rows of each class across `k` folds so every fold has the same class proportions.
+fn stratified_test_sets(y: &[f32], k: usize, shuffle: bool, rng: &mut StdRng) -> Vec<Vec<usize>> {
+    let mut folds = vec![vec![]; k];
+    let mut next_fold = 0;
+    for mut class in class_indices(y) {
+        if shuffle {
+            class.shuffle(rng);
+        }
+        for idx in class {
+            folds[next_fold].push(idx);
+            next_fold = (next_fold + 1) % k;
+        }
+    }
+    folds
+}
+
+/// Assign whole groups to `k` folds, placing the largest remaining group in the smallest fold.
+fn group_test_sets(groups: &[f32], k: usize) -> Vec<Vec<usize>> {
+    let mut members = class_indices(groups);
+    members.sort_by_key(|group| std::cmp::Reverse(group.len()));
+    let mut folds: Vec<Vec<usize>> = vec![vec![]; k];
+    for group in members {
+        let smallest = (0..k).min_by_key(|&fold| folds[fold].len()).unwrap_or(0);
+        folds[smallest].extend(group);
+    }
+    folds
+}
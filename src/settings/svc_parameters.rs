@@ -1,9 +1,30 @@
 //! Support Vector Classification parameters
 
+use std::fmt::{Display, Formatter};
+
 pub use crate::utils::Kernel;
 
+/// Strategies for extending the binary support vector classifier to more than two classes
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum MulticlassStrategy {
+    /// Fit one classifier per class against all other classes, and predict the class whose
+    /// classifier claims the row
+    OneVsRest,
+    /// Fit one classifier per pair of classes, and predict the class that wins the most pairs
+    OneVsOne,
+}
+
+impl Display for MulticlassStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OneVsRest => write!(f, "One-vs-Rest"),
+            Self::OneVsOne => write!(f, "One-vs-One"),
+        }
+    }
+}
+
 /// Parameters for support vector classification
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct SVCParameters {
     /// Number of epochs to use in the epsilon-SVC model
     pub(crate) epoch: usize,
@@ -13,6 +34,8 @@ pub struct SVCParameters {
     pub(crate) tol: f32,
     /// Kernel to use with the SVC model
     pub(crate) kernel: Kernel,
+    /// Strategy to use when there are more than two classes
+    pub(crate) multiclass: MulticlassStrategy,
 }
 
 impl SVCParameters {
@@ -43,6 +66,13 @@ impl SVCParameters {
         self.kernel = kernel;
         self
     }
+
+    /// Define how the SVC model handles more than two classes
+    #[must_use]
+    pub const fn with_multiclass(mut self, multiclass: MulticlassStrategy) -> Self {
+        self.multiclass = multiclass;
+        self
+    }
 }
 
 impl Default for SVCParameters {
@@ -52,6 +82,7 @@ impl Default for SVCParameters {
             c: 1.0,
             tol: 1e-3,
             kernel: Kernel::Linear,
+            multiclass: MulticlassStrategy::OneVsOne,
         }
     }
 }
@@ -3,7 +3,7 @@
 pub use crate::utils::Kernel;
 
 /// Parameters for support vector regression
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct SVRParameters {
     /// Epsilon in the epsilon-SVR model.
     pub(crate) eps: f32,
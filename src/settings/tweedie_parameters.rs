@@ -0,0 +1,59 @@
+//! Tweedie regression parameters
+
+/// Parameters for Tweedie regression, a generalized linear model with a log link.
+///
+/// The variance grows as the mean to the given power. Powers between one and two model
+/// non-negative targets with exact zeros, such as insurance claims; a power of one is Poisson and
+/// two is Gamma.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct TweedieParameters {
+    /// The power of the variance function, zero or less, or at least one
+    pub(crate) power: f32,
+    /// The strength of the L2 penalty on the standardized coefficients
+    pub(crate) alpha: f32,
+    /// The maximum number of reweighting iterations
+    pub(crate) max_iter: usize,
+    /// Stop once no coefficient changes by more than this relative amount
+    pub(crate) tol: f32,
+}
+
+impl TweedieParameters {
+    /// Define the power of the variance function, zero or less, or at least one
+    #[must_use]
+    pub const fn with_power(mut self, power: f32) -> Self {
+        self.power = power;
+        self
+    }
+
+    /// Define the strength of the L2 penalty on the standardized coefficients
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Define the maximum number of reweighting iterations
+    #[must_use]
+    pub const fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Define the relative change in coefficients below which fitting stops
+    #[must_use]
+    pub const fn with_tol(mut self, tol: f32) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for TweedieParameters {
+    fn default() -> Self {
+        Self {
+            power: 1.5,
+            alpha: 1.0,
+            max_iter: 100,
+            tol: 1e-4,
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Comparison and training of clustering algorithms
+
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+    time::{Duration, Instant},
+};
+
+use comfy_table::{
+    modifiers::UTF8_SOLID_INNER_BORDERS, presets::UTF8_FULL, Attribute, Cell, Table,
+};
+use humantime::format_duration;
+use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
+
+use crate::algorithms::{Clusterer, Dendrogram};
+use crate::settings::{ClusteringAlgorithm, ClusteringMetric, ClusteringSettings};
+use crate::IntoFeatures;
+
+/// Trains and compares clustering algorithms
+///
+/// K-means and hierarchical clustering are fit once for every number of clusters in the range of
+/// k in the settings, and DBSCAN is fit once. Every clustering is scored on the training data by
+/// each internal validity metric, leaving out the rows DBSCAN marks as noise, and the comparison
+/// is sorted by the metric in the settings. Clusterings for which that metric is undefined, such
+/// as those with a single cluster, are sorted last.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct UnsupervisedModel {
+    /// Settings for the model.
+    settings: ClusteringSettings,
+    /// The training data.
+    x_train: DenseMatrix<f32>,
+    /// The results of the model comparison, from best to worst.
+    comparison: Vec<ClusteringResult>,
+    /// The best clustering, which assigns new rows to clusters.
+    final_model: Option<Clusterer>,
+}
+
+/// The results of a single clustering
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ClusteringResult {
+    /// The algorithm used
+    algorithm: ClusteringAlgorithm,
+    /// The number of clusters found
+    number_of_clusters: usize,
+    /// The number of rows marked as noise
+    noise: usize,
+    /// The score by each metric, in the order of `ClusteringMetric::ALL`, if defined
+    scores: Vec<Option<f32>>,
+    /// The time it took to fit and score the clustering
+    duration: Duration,
+}
+
+impl ClusteringResult {
+    /// The score by a metric, if defined
+    fn score(&self, metric: ClusteringMetric) -> Option<f32> {
+        ClusteringMetric::ALL
+            .iter()
+            .position(|&other| other == metric)
+            .and_then(|index| self.scores[index])
+    }
+}
+
+impl UnsupervisedModel {
+    /// Create a new unsupervised model from data.
+    /// ```
+    /// # use automl::{ClusteringSettings, UnsupervisedModel};
+    /// let x: Vec<Vec<f32>> = (0..20)
+    ///     .map(|i| vec![(i % 2) as f32 * 10.0 + i as f32 / 20.0, (i % 2) as f32])
+    ///     .collect();
+    /// let model = UnsupervisedModel::new(x, ClusteringSettings::default());
+    /// ```
+    pub fn new<X>(x: X, settings: ClusteringSettings) -> Self
+    where
+        X: IntoFeatures,
+    {
+        Self {
+            settings,
+            x_train: x.to_dense_matrix(),
+            comparison: vec![],
+            final_model: None,
+        }
+    }
+
+    /// Load an unsupervised model from a file saved with `UnsupervisedModel::save`.
+    /// ```
+    /// # use automl::{ClusteringSettings, UnsupervisedModel};
+    /// # let x: Vec<Vec<f32>> = (0..20)
+    /// #     .map(|i| vec![(i % 2) as f32 * 10.0 + i as f32 / 20.0, (i % 2) as f32])
+    /// #     .collect();
+    /// let model = UnsupervisedModel::new(x, ClusteringSettings::default());
+    /// model.save("tests/load_that_unsupervised_model.aml");
+    /// let model = UnsupervisedModel::new_from_file("tests/load_that_unsupervised_model.aml");
+    /// # std::fs::remove_file("tests/load_that_unsupervised_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the file cannot be read or does not hold an unsupervised model, this function will
+    /// panic.
+    #[must_use]
+    pub fn new_from_file(file_name: &str) -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        std::fs::File::open(file_name)
+            .and_then(|mut f| f.read_to_end(&mut buf))
+            .expect("Cannot load model from file.");
+        bincode::deserialize(&buf).expect("Can not deserialize the model")
+    }
+
+    /// Runs a comparison of clusterings and keeps the best one.
+    /// ```
+    /// # use automl::{ClusteringSettings, UnsupervisedModel};
+    /// # let x: Vec<Vec<f32>> = (0..20)
+    /// #     .map(|i| vec![(i % 2) as f32 * 10.0 + i as f32 / 20.0, (i % 2) as f32])
+    /// #     .collect();
+    /// let mut model = UnsupervisedModel::new(x, ClusteringSettings::default().with_k_range(2..=4));
+    /// model.train();
+    /// ```
+    pub fn train(&mut self) {
+        let n = self.x_train.shape().0;
+        let k_range: Vec<usize> = self
+            .settings
+            .k_range
+            .clone()
+            .filter(|&k| k >= 2 && k < n)
+            .collect();
+        let mut fitted: Vec<(ClusteringResult, Clusterer)> = vec![];
+
+        if !self
+            .settings
+            .skiplist
+            .contains(&ClusteringAlgorithm::KMeans)
+        {
+            for &k in &k_range {
+                let start = Instant::now();
+                let (model, clusters) = Clusterer::kmeans(&self.x_train, k, &self.settings);
+                fitted.push((
+                    self.score(ClusteringAlgorithm::KMeans, &clusters, start),
+                    model,
+                ));
+            }
+        }
+
+        if !self
+            .settings
+            .skiplist
+            .contains(&ClusteringAlgorithm::DBSCAN)
+        {
+            let start = Instant::now();
+            let (model, clusters) = Clusterer::dbscan(&self.x_train, &self.settings);
+            fitted.push((
+                self.score(ClusteringAlgorithm::DBSCAN, &clusters, start),
+                model,
+            ));
+        }
+
+        // The dendrogram is built once and cut for every number of clusters
+        if !self
+            .settings
+            .skiplist
+            .contains(&ClusteringAlgorithm::Hierarchical)
+            && n <= self.settings.hierarchical_settings.max_samples
+        {
+            let start = Instant::now();
+            let dendrogram =
+                Dendrogram::fit(&self.x_train, self.settings.hierarchical_settings.linkage);
+            let build_time = start.elapsed();
+            for &k in &k_range {
+                let start = Instant::now();
+                let clusters = dendrogram.cut(k);
+                let model = Clusterer::hierarchical(&self.x_train, &clusters);
+                let mut result = self.score(ClusteringAlgorithm::Hierarchical, &clusters, start);
+                result.duration += build_time;
+                fitted.push((result, model));
+            }
+        }
+
+        let metric = self.settings.sort_by;
+        fitted.sort_by(|(a, _), (b, _)| match (a.score(metric), b.score(metric)) {
+            (Some(a), Some(b)) => {
+                let order = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+                if metric.higher_is_better() {
+                    order.reverse()
+                } else {
+                    order
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        let (comparison, models): (Vec<ClusteringResult>, Vec<Clusterer>) =
+            fitted.into_iter().unzip();
+        self.comparison = comparison;
+        self.final_model = models.into_iter().next();
+    }
+
+    /// Assign rows to the clusters of the best clustering, with -1 for rows DBSCAN marks as
+    /// noise.
+    /// ```
+    /// # use automl::{ClusteringSettings, UnsupervisedModel};
+    /// # let x: Vec<Vec<f32>> = (0..20)
+    /// #     .map(|i| vec![(i % 2) as f32 * 10.0 + i as f32 / 20.0, (i % 2) as f32])
+    /// #     .collect();
+    /// let mut model = UnsupervisedModel::new(x, ClusteringSettings::default().with_k_range(2..=4));
+    /// model.train();
+    /// let clusters = model.predict(vec![vec![0.0, 0.0], vec![10.0, 1.0]]);
+    /// assert_ne!(clusters[0], clusters[1]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model has not been trained, this function will panic.
+    pub fn predict<X: IntoFeatures>(&self, x: X) -> Vec<f32> {
+        self.final_model
+            .as_ref()
+            .expect("The model has not been trained.")
+            .predict(&x.to_dense_matrix())
+    }
+
+    /// Save the unsupervised model to a file for later use
+    /// ```
+    /// # use automl::{ClusteringSettings, UnsupervisedModel};
+    /// # let x: Vec<Vec<f32>> = (0..20)
+    /// #     .map(|i| vec![(i % 2) as f32 * 10.0 + i as f32 / 20.0, (i % 2) as f32])
+    /// #     .collect();
+    /// let model = UnsupervisedModel::new(x, ClusteringSettings::default());
+    /// model.save("tests/save_that_unsupervised_model.aml");
+    /// # std::fs::remove_file("tests/save_that_unsupervised_model.aml");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the model cannot be serialized or written to the file, this function will panic.
+    pub fn save(&self, file_name: &str) {
+        let serial = bincode::serialize(&self).expect("Cannot serialize model.");
+        std::fs::File::create(file_name)
+            .and_then(|mut f| f.write_all(&serial))
+            .expect("Cannot write model to file.");
+    }
+}
+
+/// Private functions go here
+impl UnsupervisedModel {
+    /// Score a clustering of the training data by every metric.
+    fn score(
+        &self,
+        algorithm: ClusteringAlgorithm,
+        clusters: &[f32],
+        start: Instant,
+    ) -> ClusteringResult {
+        let mut labels: Vec<f32> = clusters
+            .iter()
+            .copied()
+            .filter(|&cluster| cluster >= 0.0)
+            .collect();
+        labels.sort_by(f32::total_cmp);
+        labels.dedup();
+        ClusteringResult {
+            algorithm,
+            number_of_clusters: labels.len(),
+            noise: clusters.iter().filter(|&&cluster| cluster < 0.0).count(),
+            scores: ClusteringMetric::ALL
+                .iter()
+                .map(|metric| metric.get_function()(&self.x_train, clusters))
+                .collect(),
+            duration: start.elapsed(),
+        }
+    }
+}
+
+impl Display for UnsupervisedModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.apply_modifier(UTF8_SOLID_INNER_BORDERS);
+        let mut header = vec![
+            Cell::new("Model").add_attribute(Attribute::Bold),
+            Cell::new("Clusters").add_attribute(Attribute::Bold),
+            Cell::new("Noise").add_attribute(Attribute::Bold),
+            Cell::new("Time").add_attribute(Attribute::Bold),
+        ];
+        for metric in ClusteringMetric::ALL {
+            header.push(Cell::new(format!("{metric}")).add_attribute(Attribute::Bold));
+        }
+        table.set_header(header);
+        for result in &self.comparison {
+            let mut row = vec![
+                format!("{}", result.algorithm),
+                format!("{}", result.number_of_clusters),
+                format!("{}", result.noise),
+                format!("{}", format_duration(result.duration)),
+            ];
+            row.extend(
+                result.scores.iter().map(|score| {
+                    score.map_or_else(|| "-".to_string(), |score| format!("{score:.3}"))
+                }),
+            );
+            table.add_row(row);
+        }
+        write!(f, "{table}")
+    }
+}
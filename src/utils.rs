@@ -1,6 +1,11 @@
 //! Utility functions for the crate.
 
-use smartcore::{algorithm::neighbour::KNNAlgorithmName, neighbors::KNNWeightFunction};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use smartcore::{
+    algorithm::neighbour::KNNAlgorithmName,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    neighbors::KNNWeightFunction,
+};
 use std::fmt::{Debug, Display, Formatter};
 
 /// Convert an Option<T> to a String for printing in display mode.
@@ -30,7 +35,7 @@ pub fn print_knn_search_algorithm(a: &KNNAlgorithmName) -> String {
 }
 
 /// Kernel options for use with support vector machines
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum Kernel {
     /// Linear Kernel
     Linear,
@@ -62,7 +67,7 @@ impl Display for Kernel {
 }
 
 /// Distance metrics
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum Distance {
     /// Euclidean distance
     Euclidean,
@@ -97,6 +102,344 @@ pub fn elementwise_multiply(v1: &[f32], v2: &[f32]) -> Vec<f32> {
     v1.iter().zip(v2).map(|(&i1, &i2)| i1 * i2).collect()
 }
 
+/// Sample standard deviation of a vector, or zero if it has fewer than two elements
+#[allow(clippy::cast_precision_loss)]
+pub fn standard_deviation(v: &[f32]) -> f32 {
+    if v.len() < 2 {
+        return 0.0;
+    }
+    let mean = v.iter().sum::<f32>() / v.len() as f32;
+    (v.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / (v.len() - 1) as f32).sqrt()
+}
+
+/// Whether the targets are in range for a Tweedie distribution with the given power: any value
+/// for powers of zero or less, non-negative values (not all zero) for powers from one up to two,
+/// and positive values for powers of two or more. Powers between zero and one are not valid.
+pub fn supports_tweedie_targets(power: f32, y: &[f32]) -> bool {
+    if power <= 0.0 {
+        true
+    } else if power < 1.0 {
+        false
+    } else if power < 2.0 {
+        y.iter().all(|&value| value >= 0.0) && y.iter().any(|&value| value > 0.0)
+    } else {
+        y.iter().all(|&value| value > 0.0)
+    }
+}
+
+/// Mean Poisson deviance, for non-negative targets and positive predictions
+#[allow(clippy::ptr_arg, clippy::cast_precision_loss)]
+pub fn mean_poisson_deviance(y_true: &Vec<f32>, y_pred: &Vec<f32>) -> f32 {
+    let total: f32 = y_true
+        .iter()
+        .zip(y_pred)
+        .map(|(&y, &mu)| {
+            let mu = mu.max(f32::EPSILON);
+            let ratio = if y > 0.0 { y * (y / mu).ln() } else { 0.0 };
+            2.0 * (ratio - y + mu)
+        })
+        .sum();
+    total / y_true.len().max(1) as f32
+}
+
+/// Mean Gamma deviance, for positive targets and predictions
+#[allow(clippy::ptr_arg, clippy::cast_precision_loss)]
+pub fn mean_gamma_deviance(y_true: &Vec<f32>, y_pred: &Vec<f32>) -> f32 {
+    let total: f32 = y_true
+        .iter()
+        .zip(y_pred)
+        .map(|(&y, &mu)| {
+            let (y, mu) = (y.max(f32::EPSILON), mu.max(f32::EPSILON));
+            2.0 * ((mu / y).ln() + y / mu - 1.0)
+        })
+        .sum();
+    total / y_true.len().max(1) as f32
+}
+
+/// The true positives, false positives and false negatives of each label of a label-indicator
+/// matrix, counting values above one half as present
+fn label_counts(y_true: &DenseMatrix<f32>, y_pred: &DenseMatrix<f32>) -> Vec<[f32; 3]> {
+    let (rows, labels) = y_true.shape();
+    (0..labels)
+        .map(|label| {
+            let mut counts = [0.0; 3];
+            for row in 0..rows {
+                match (y_true.get(row, label) > 0.5, y_pred.get(row, label) > 0.5) {
+                    (true, true) => counts[0] += 1.0,
+                    (false, true) => counts[1] += 1.0,
+                    (true, false) => counts[2] += 1.0,
+                    (false, false) => {}
+                }
+            }
+            counts
+        })
+        .collect()
+}
+
+/// F1 score from true positives, false positives and false negatives, taken as 1 when there are
+/// no positives to find or predict
+fn f1_score([true_positives, false_positives, false_negatives]: [f32; 3]) -> f32 {
+    let denominator = 2.0f32.mul_add(true_positives, false_positives + false_negatives);
+    if denominator > 0.0 {
+        2.0 * true_positives / denominator
+    } else {
+        1.0
+    }
+}
+
+/// The fraction of individual labels predicted wrongly in a label-indicator matrix
+#[allow(clippy::cast_precision_loss)]
+pub fn hamming_loss(y_true: &DenseMatrix<f32>, y_pred: &DenseMatrix<f32>) -> f32 {
+    let (rows, labels) = y_true.shape();
+    let wrong: f32 = label_counts(y_true, y_pred)
+        .iter()
+        .map(|counts| counts[1] + counts[2])
+        .sum();
+    wrong / (rows * labels).max(1) as f32
+}
+
+/// The fraction of rows of a label-indicator matrix with every label predicted correctly
+#[allow(clippy::cast_precision_loss)]
+pub fn subset_accuracy(y_true: &DenseMatrix<f32>, y_pred: &DenseMatrix<f32>) -> f32 {
+    let (rows, labels) = y_true.shape();
+    let correct = (0..rows)
+        .filter(|&row| {
+            (0..labels)
+                .all(|label| (y_true.get(row, label) > 0.5) == (y_pred.get(row, label) > 0.5))
+        })
+        .count();
+    correct as f32 / rows.max(1) as f32
+}
+
+/// F1 score over the counts of every label of a label-indicator matrix pooled together
+pub fn micro_f1(y_true: &DenseMatrix<f32>, y_pred: &DenseMatrix<f32>) -> f32 {
+    f1_score(
+        label_counts(y_true, y_pred)
+            .into_iter()
+            .fold([0.0; 3], |total, counts| {
+                [
+                    total[0] + counts[0],
+                    total[1] + counts[1],
+                    total[2] + counts[2],
+                ]
+            }),
+    )
+}
+
+/// The mean F1 score of the labels of a label-indicator matrix
+#[allow(clippy::cast_precision_loss)]
+pub fn macro_f1(y_true: &DenseMatrix<f32>, y_pred: &DenseMatrix<f32>) -> f32 {
+    let counts = label_counts(y_true, y_pred);
+    counts.iter().map(|&counts| f1_score(counts)).sum::<f32>() / counts.len().max(1) as f32
+}
+
+/// F1 score of the positive class of binary labels, counting values above one half as positive
+pub fn binary_f1(y_true: &[f32], y_pred: &[f32]) -> f32 {
+    f1_score(
+        y_true
+            .iter()
+            .zip(y_pred)
+            .fold([0.0; 3], |mut counts, (&truth, &prediction)| {
+                match (truth > 0.5, prediction > 0.5) {
+                    (true, true) => counts[0] += 1.0,
+                    (false, true) => counts[1] += 1.0,
+                    (true, false) => counts[2] += 1.0,
+                    (false, false) => {}
+                }
+                counts
+            }),
+    )
+}
+
+/// Area under the ROC curve of scores for binary labels, the chance that a random positive is
+/// scored above a random negative, with ties counted as half. It is one half when either class is
+/// missing.
+#[allow(clippy::cast_precision_loss)]
+pub fn roc_auc(y_true: &[f32], scores: &[f32]) -> f32 {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+
+    // Sum the ranks of the positives, giving tied scores their mean rank
+    let (mut rank_sum, mut positives, mut start) = (0.0_f64, 0.0_f64, 0);
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len()
+            && scores[order[end + 1]]
+                .total_cmp(&scores[order[start]])
+                .is_eq()
+        {
+            end += 1;
+        }
+        let mean_rank = (start + end) as f64 / 2.0 + 1.0;
+        for &row in &order[start..=end] {
+            if y_true[row] > 0.5 {
+                rank_sum += mean_rank;
+                positives += 1.0;
+            }
+        }
+        start = end + 1;
+    }
+    let negatives = scores.len() as f64 - positives;
+    if positives < 1.0 || negatives < 1.0 {
+        return 0.5;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let auc =
+        (positives.mul_add(-(positives + 1.0) / 2.0, rank_sum) / (positives * negatives)) as f32;
+    auc
+}
+
+/// Average precision of scores for binary labels, the mean precision among the highest scored rows
+/// at the rank of each positive. It is zero when there are no positives.
+#[allow(clippy::cast_precision_loss)]
+pub fn average_precision(y_true: &[f32], scores: &[f32]) -> f32 {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    let (mut found, mut total) = (0.0_f32, 0.0_f32);
+    for (rank, &row) in order.iter().enumerate() {
+        if y_true[row] > 0.5 {
+            found += 1.0;
+            total += found / (rank + 1) as f32;
+        }
+    }
+    if found > 0.0 {
+        total / found
+    } else {
+        0.0
+    }
+}
+
+/// The rows of each cluster, leaving out rows with a negative cluster, which are noise
+fn cluster_members(clusters: &[f32]) -> Vec<Vec<usize>> {
+    let mut labels: Vec<f32> = clusters
+        .iter()
+        .copied()
+        .filter(|&cluster| cluster >= 0.0)
+        .collect();
+    labels.sort_by(f32::total_cmp);
+    labels.dedup();
+    labels
+        .iter()
+        .map(|&label| {
+            (0..clusters.len())
+                .filter(|&row| (clusters[row] - label).abs() < f32::EPSILON)
+                .collect()
+        })
+        .collect()
+}
+
+/// The Euclidean distance between two rows
+pub fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    squared_distance(a, b).sqrt()
+}
+
+/// The mean of the given rows of the data
+#[allow(clippy::cast_precision_loss)]
+pub fn centroid(x: &DenseMatrix<f32>, rows: &[usize]) -> Vec<f32> {
+    let mut center = vec![0.0; x.shape().1];
+    for &row in rows {
+        for (column, total) in center.iter_mut().enumerate() {
+            *total += x.get(row, column);
+        }
+    }
+    center
+        .iter()
+        .map(|total| total / rows.len().max(1) as f32)
+        .collect()
+}
+
+/// Mean silhouette coefficient of a clustering, or `None` with fewer than two clusters or no
+/// more rows than clusters. Noise rows are left out.
+#[allow(clippy::cast_precision_loss)]
+pub fn silhouette_score(x: &DenseMatrix<f32>, clusters: &[f32]) -> Option<f32> {
+    let members = cluster_members(clusters);
+    let rows: usize = members.iter().map(Vec::len).sum();
+    if members.len() < 2 || rows <= members.len() {
+        return None;
+    }
+    let data: Vec<Vec<f32>> = (0..x.shape().0).map(|row| x.get_row_as_vec(row)).collect();
+    let mean_distance = |row: usize, cluster: &[usize]| {
+        cluster
+            .iter()
+            .map(|&other| euclidean_distance(&data[row], &data[other]))
+            .sum::<f32>()
+    };
+    let mut total = 0.0;
+    for (index, cluster) in members.iter().enumerate() {
+        // Rows alone in their cluster have a silhouette of zero
+        if cluster.len() < 2 {
+            continue;
+        }
+        for &row in cluster {
+            let within = mean_distance(row, cluster) / (cluster.len() - 1) as f32;
+            let nearest = members
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .map(|(_, other)| mean_distance(row, other) / other.len() as f32)
+                .fold(f32::INFINITY, f32::min);
+            let spread = within.max(nearest);
+            if spread > 0.0 {
+                total += (nearest - within) / spread;
+            }
+        }
+    }
+    Some(total / rows as f32)
+}
+
+/// Calinski-Harabasz index of a clustering, the ratio of between-cluster to within-cluster
+/// dispersion, or `None` when it is undefined. Noise rows are left out.
+#[allow(clippy::cast_precision_loss)]
+pub fn calinski_harabasz_score(x: &DenseMatrix<f32>, clusters: &[f32]) -> Option<f32> {
+    let members = cluster_members(clusters);
+    let all: Vec<usize> = members.concat();
+    let (rows, k) = (all.len(), members.len());
+    if k < 2 || rows <= k {
+        return None;
+    }
+    let center = centroid(x, &all);
+    let (mut between, mut within) = (0.0, 0.0);
+    for cluster in &members {
+        let cluster_center = centroid(x, cluster);
+        between += cluster.len() as f32 * euclidean_distance(&cluster_center, &center).powi(2);
+        for &row in cluster {
+            within += euclidean_distance(&x.get_row_as_vec(row), &cluster_center).powi(2);
+        }
+    }
+    (within > 0.0).then(|| (between / (k - 1) as f32) / (within / (rows - k) as f32))
+}
+
+/// Davies-Bouldin index of a clustering, the mean similarity of each cluster to its most similar
+/// cluster, or `None` when it is undefined. Noise rows are left out.
+#[allow(clippy::cast_precision_loss)]
+pub fn davies_bouldin_score(x: &DenseMatrix<f32>, clusters: &[f32]) -> Option<f32> {
+    let members = cluster_members(clusters);
+    if members.len() < 2 {
+        return None;
+    }
+    let centers: Vec<Vec<f32>> = members.iter().map(|cluster| centroid(x, cluster)).collect();
+    let scatter: Vec<f32> = members
+        .iter()
+        .zip(&centers)
+        .map(|(cluster, center)| {
+            cluster
+                .iter()
+                .map(|&row| euclidean_distance(&x.get_row_as_vec(row), center))
+                .sum::<f32>()
+                / cluster.len() as f32
+        })
+        .collect();
+    let total: f32 = (0..members.len())
+        .map(|i| {
+            (0..members.len())
+                .filter(|&j| j != i)
+                .map(|j| (scatter[i] + scatter[j]) / euclidean_distance(&centers[i], &centers[j]))
+                .fold(0.0, f32::max)
+        })
+        .sum();
+    Some(total / members.len() as f32)
+}
+
 #[cfg(any(feature = "csv"))]
 use polars::prelude::{CsvReader, DataFrame, PolarsError, SerReader};
 
@@ -175,3 +518,211 @@ impl Cleanup for DataFrame {
         Ok(self)
     }
 }
+
+/// Mix a value into a hash state with the finalizer of splitmix64, to derive independent seeds.
+pub const fn mix_seed(state: u64, value: u64) -> u64 {
+    let mut z = (state ^ value).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Group the row indices of a label vector by class, in ascending order of class label.
+#[allow(clippy::float_cmp)]
+pub fn class_indices(y: &[f32]) -> Vec<Vec<usize>> {
+    let mut classes = y.to_vec();
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    classes.dedup();
+    classes
+        .iter()
+        .map(|class| {
+            y.iter()
+                .enumerate()
+                .filter(|(_, label)| *label == class)
+                .map(|(idx, _)| idx)
+                .collect()
+        })
+        .collect()
+}
+
+/// Randomly duplicate rows of the minority classes until all classes are the size of the largest.
+pub fn random_oversample(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    rng: &mut StdRng,
+) -> (DenseMatrix<f32>, Vec<f32>) {
+    let classes = class_indices(y);
+    let largest = classes.iter().map(Vec::len).max().unwrap_or(0);
+    let mut rows: Vec<usize> = (0..y.len()).collect();
+    for class in &classes {
+        rows.extend((class.len()..largest).map(|_| class[rng.gen_range(0..class.len())]));
+    }
+    (x.take(&rows, 0), rows.iter().map(|&idx| y[idx]).collect())
+}
+
+/// Randomly duplicate rows of each class in proportion to its weight, one weight per class in
+/// ascending order of class label, so the class with the smallest weight keeps its size and every
+/// other class grows by the ratio of its weight to that one.
+pub fn weighted_oversample(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    weights: &[f32],
+    rng: &mut StdRng,
+) -> (DenseMatrix<f32>, Vec<f32>) {
+    let classes = class_indices(y);
+    let smallest = weights.iter().copied().fold(f32::INFINITY, f32::min);
+    let mut rows: Vec<usize> = (0..y.len()).collect();
+    for (class, weight) in classes.iter().zip(weights) {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let size = (class.len() as f32 * weight / smallest).round() as usize;
+        rows.extend((class.len()..size).map(|_| class[rng.gen_range(0..class.len())]));
+    }
+    (x.take(&rows, 0), rows.iter().map(|&idx| y[idx]).collect())
+}
+
+/// Randomly drop rows of the majority classes until all classes are the size of the smallest.
+pub fn random_undersample(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    rng: &mut StdRng,
+) -> (DenseMatrix<f32>, Vec<f32>) {
+    let classes = class_indices(y);
+    let smallest = classes.iter().map(Vec::len).min().unwrap_or(0);
+    let mut rows: Vec<usize> = classes
+        .iter()
+        .flat_map(|class| class.choose_multiple(rng, smallest).copied())
+        .collect();
+    rows.sort_unstable();
+    (x.take(&rows, 0), rows.iter().map(|&idx| y[idx]).collect())
+}
+
+/// Synthesize rows for the minority classes by interpolating between each row and one of its
+/// `k` nearest neighbors from the same class (SMOTE), until all classes are the size of the largest.
+pub fn smote(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    k: usize,
+    rng: &mut StdRng,
+) -> (DenseMatrix<f32>, Vec<f32>) {
+    let classes = class_indices(y);
+    let largest = classes.iter().map(Vec::len).max().unwrap_or(0);
+    let mut new_rows: Vec<Vec<f32>> = (0..y.len()).map(|idx| x.get_row_as_vec(idx)).collect();
+    let mut new_labels = y.to_vec();
+    for class in &classes {
+        if class.len() == largest {
+            continue;
+        }
+        let members: Vec<Vec<f32>> = class.iter().map(|&idx| x.get_row_as_vec(idx)).collect();
+        let neighbors: Vec<Vec<usize>> = (0..members.len())
+            .map(|i| {
+                let mut others: Vec<(usize, f32)> = (0..members.len())
+                    .filter(|&j| j != i)
+                    .map(|j| (j, squared_distance(&members[i], &members[j])))
+                    .collect();
+                others.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+                others.into_iter().take(k).map(|(j, _)| j).collect()
+            })
+            .collect();
+        for _ in class.len()..largest {
+            let i = rng.gen_range(0..members.len());
+            let synthetic = neighbors[i].choose(rng).map_or_else(
+                || members[i].clone(),
+                |&j| {
+                    let gap: f32 = rng.gen();
+                    members[i]
+                        .iter()
+                        .zip(&members[j])
+                        .map(|(a, b)| gap.mul_add(b - a, *a))
+                        .collect()
+                },
+            );
+            new_rows.push(synthetic);
+            new_labels.push(y[class[i]]);
+        }
+    }
+    (DenseMatrix::from_2d_vec(&new_rows), new_labels)
+}
+
+/// Squared euclidean distance between two vectors
+pub fn squared_distance(v1: &[f32], v2: &[f32]) -> f32 {
+    v1.iter().zip(v2).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// Split the data into training and testing sets, shuffling the rows first if requested.
+pub fn train_test_split(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    test_size: f32,
+    shuffle: bool,
+    rng: &mut StdRng,
+) -> (DenseMatrix<f32>, DenseMatrix<f32>, Vec<f32>, Vec<f32>) {
+    let mut indices: Vec<usize> = (0..y.len()).collect();
+    if shuffle {
+        indices.shuffle(rng);
+    }
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let n_test = ((y.len() as f32) * test_size) as usize;
+    let (test, train) = indices.split_at(n_test);
+    (
+        x.take(train, 0),
+        x.take(test, 0),
+        train.iter().map(|&idx| y[idx]).collect(),
+        test.iter().map(|&idx| y[idx]).collect(),
+    )
+}
+
+/// Split data into training and testing sets, keeping the row order and testing on the last rows,
+/// so time-ordered data is never trained on rows after those it is tested on
+pub fn trailing_split(
+    x: &DenseMatrix<f32>,
+    y: &[f32],
+    test_size: f32,
+) -> (DenseMatrix<f32>, DenseMatrix<f32>, Vec<f32>, Vec<f32>) {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let n_test = ((y.len() as f32) * test_size) as usize;
+    let indices: Vec<usize> = (0..y.len()).collect();
+    let (train, test) = indices.split_at(y.len() - n_test);
+    (
+        x.take(train, 0),
+        x.take(test, 0),
+        y[..train.len()].to_vec(),
+        y[train.len()..].to_vec(),
+    )
+}
+
+/// Randomly choose a fraction of the rows, returning their indices in the original row order.
+/// When `stratify` is set, the fraction is taken from each class separately so no class
+/// disappears.
+pub fn subsample(y: &[f32], fraction: f32, stratify: bool, rng: &mut StdRng) -> Vec<usize> {
+    let groups = if stratify {
+        class_indices(y)
+    } else {
+        vec![(0..y.len()).collect()]
+    };
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let mut rows: Vec<usize> = groups
+        .iter()
+        .flat_map(|group| {
+            let amount = ((group.len() as f32) * fraction).ceil() as usize;
+            group.choose_multiple(rng, amount).copied()
+        })
+        .collect();
+    rows.sort_unstable();
+    rows
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod anomaly_tests {
+    use automl::{settings::*, *};
+    use smartcore::dataset::iris::load_dataset;
+
+    /// Rows far from every iris species
+    fn outliers() -> Vec<Vec<f32>> {
+        vec![
+            vec![15.0, 15.0, 15.0, 15.0],
+            vec![-5.0, 10.0, -5.0, 10.0],
+            vec![5.0, 3.0, 20.0, 0.0],
+            vec![0.0, 0.0, 0.0, 0.0],
+            vec![12.0, -4.0, 1.0, 8.0],
+        ]
+    }
+
+    /// The iris measurements, without the species, followed by the outliers
+    fn iris_with_outliers() -> Vec<Vec<f32>> {
+        let dataset = load_dataset();
+        let mut x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        x.extend(outliers());
+        x
+    }
+
+    #[test]
+    fn test_each_detector() {
+        let x = iris_with_outliers();
+        for algorithm in [
+            AnomalyAlgorithm::IsolationForest,
+            AnomalyAlgorithm::LocalOutlierFactor,
+            AnomalyAlgorithm::RobustCovariance,
+        ] {
+            let mut model = AnomalyModel::new(
+                x.clone(),
+                AnomalySettings::default()
+                    .only(algorithm)
+                    .with_contamination(0.05)
+                    .with_seed(0),
+            );
+            model.train();
+            assert!(format!("{model}").contains(&format!("{algorithm}")));
+
+            // The outliers are flagged and typical rows of each species are not
+            assert_eq!(model.predict(outliers()), vec![1.0; 5]);
+            let typical = vec![
+                vec![5.0, 3.4, 1.5, 0.2],
+                vec![5.9, 2.8, 4.3, 1.3],
+                vec![6.5, 3.0, 5.5, 2.0],
+            ];
+            assert_eq!(model.predict(typical.clone()), vec![0.0; 3]);
+
+            // The contamination sets how many training rows are flagged
+            let flagged = model.predict(x.clone()).iter().sum::<f32>();
+            assert!((5.0..=8.0).contains(&flagged));
+
+            // The detector survives a save and load
+            model.save("tests/anomaly_model.aml");
+            let loaded = AnomalyModel::new_from_file("tests/anomaly_model.aml");
+            std::fs::remove_file("tests/anomaly_model.aml").unwrap();
+            assert_eq!(
+                model.anomaly_scores(typical.clone()),
+                loaded.anomaly_scores(typical)
+            );
+            assert!((model.threshold() - loaded.threshold()).abs() < f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_validation_selection() {
+        let validation_x = vec![
+            vec![5.1, 3.5, 1.4, 0.2],
+            vec![6.2, 2.9, 4.3, 1.3],
+            vec![6.7, 3.1, 5.6, 2.4],
+            vec![25.0, 1.0, 25.0, 1.0],
+            vec![1.0, 12.0, 1.0, 12.0],
+        ];
+        let validation_y = vec![0.0, 0.0, 0.0, 1.0, 1.0];
+        for metric in [
+            AnomalyMetric::RocAuc,
+            AnomalyMetric::AveragePrecision,
+            AnomalyMetric::F1,
+        ] {
+            let mut model = AnomalyModel::new_with_validation(
+                iris_with_outliers(),
+                (validation_x.clone(), validation_y.clone()),
+                AnomalySettings::default().sorted_by(metric).with_seed(1),
+            );
+            model.train();
+            let display = format!("{model}");
+            assert!(display.contains(&format!("Validation {metric}")));
+            assert!(display.contains("1.000"));
+            assert_eq!(model.predict(validation_x.clone()), validation_y);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Validation labels must be 1 for anomalies and 0 for normal rows.")]
+    fn test_validation_labels() {
+        AnomalyModel::new_with_validation(
+            iris_with_outliers(),
+            (vec![vec![5.1, 3.5, 1.4, 0.2]], vec![2.0]),
+            AnomalySettings::default(),
+        );
+    }
+
+    #[test]
+    fn test_settings_save_and_load() {
+        let settings = AnomalySettings::default()
+            .skip(AnomalyAlgorithm::IsolationForest)
+            .with_contamination(0.01)
+            .with_local_outlier_factor_settings(
+                LocalOutlierFactorParameters::default()
+                    .with_k(5)
+                    .with_distance(Distance::Mahalanobis),
+            );
+        settings.save("tests/anomaly_settings.yaml");
+        let loaded = AnomalySettings::new_from_file("tests/anomaly_settings.yaml");
+        std::fs::remove_file("tests/anomaly_settings.yaml").unwrap();
+        assert_eq!(format!("{settings}"), format!("{loaded}"));
+        assert!(!format!("{loaded}").contains("Number of Trees"));
+    }
+}
@@ -47,6 +47,539 @@ mod classification_tests {
         test_from_settings(settings);
     }
 
+    #[test]
+    fn test_voting_and_ensemble_selection() {
+        let settings =
+            Settings::default_classification().with_final_model(FinalModel::default_voting());
+        test_from_settings(settings);
+
+        let settings = Settings::default_classification()
+            .with_final_model(FinalModel::default_ensemble_selection());
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_soft_voting() {
+        let mut classifier = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_classification().with_final_model(FinalModel::Voting {
+                top_k: 2,
+                weights: None,
+                soft: true,
+            }),
+        );
+        classifier.train();
+        let table = format!("{classifier}");
+        let members = &table[table.find("Ensemble Member").unwrap()..];
+        assert!(!members.contains("Dummy"));
+        classifier.predict(vec![vec![5.0_f32; 30]; 10]);
+    }
+
+    #[test]
+    fn test_smote_resampling() {
+        let settings = Settings::default_classification()
+            .with_resampling(Resampling::SMOTE { k_neighbors: 5 })
+            .with_class_weight(ClassWeight::Balanced);
+        test_from_settings(settings);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Custom class weights need one weight per class, but there are 3 weights for 2 classes."
+    )]
+    fn test_custom_class_weights_per_class() {
+        let mut classifier = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_classification()
+                .only(Algorithm::GaussianNaiveBayes)
+                .with_class_weight(ClassWeight::Custom(vec![1.0, 2.0, 3.0])),
+        );
+        classifier.train();
+    }
+
+    #[test]
+    fn test_class_weights_rebalance_other_algorithms() {
+        // Weighting the second class more heavily makes KNN predict it more often
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let positives = |class_weight| {
+            let mut classifier = SupervisedModel::new(
+                load_dataset(),
+                Settings::default_classification()
+                    .only(Algorithm::KNNClassifier)
+                    .with_seed(0)
+                    .with_class_weight(class_weight),
+            );
+            classifier.train();
+            classifier
+                .predict(x.clone())
+                .into_iter()
+                .filter(|&class| class == 1.0)
+                .count()
+        };
+        assert!(positives(ClassWeight::Custom(vec![1.0, 10.0])) > positives(ClassWeight::None));
+    }
+
+    #[test]
+    fn test_undersampling() {
+        let settings =
+            Settings::default_classification().with_resampling(Resampling::RandomUndersampling);
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_stratified_kfold() {
+        let settings = Settings::default_classification()
+            .shuffle_data(true)
+            .with_splitter(Splitter::StratifiedKFold);
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_successive_halving() {
+        let settings = Settings::default_classification().with_halving(Halving::Successive {
+            min_fraction: 0.25,
+            reduction_factor: 2,
+        });
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_gradient_boosting_multiclass() {
+        let settings = Settings::default_classification()
+            .only(Algorithm::GradientBoostingClassifier)
+            .shuffle_data(true)
+            .with_seed(0)
+            .with_number_of_folds(3)
+            .with_gradient_boosting_classifier_settings(
+                GradientBoostingClassifierParameters::default()
+                    .with_n_estimators(50)
+                    .with_subsample(0.8)
+                    .with_early_stopping(5, 0.2),
+            );
+        let mut classifier =
+            SupervisedModel::new(smartcore::dataset::iris::load_dataset(), settings);
+        classifier.train();
+        assert!(classifier.leaderboard()[0].mean_test_score > 0.8);
+    }
+
+    #[test]
+    fn test_gradient_boosting_single_class_fold() {
+        // Unshuffled folds of the first two iris classes each train on a single class
+        let dataset = smartcore::dataset::iris::load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .take(100)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let y = dataset.target[..100].to_vec();
+        let mut classifier = SupervisedModel::new(
+            (x, y),
+            Settings::default_classification()
+                .only(Algorithm::GradientBoostingClassifier)
+                .with_splitter(Splitter::KFold)
+                .shuffle_data(false)
+                .with_number_of_folds(2),
+        );
+        classifier.train();
+        assert!(classifier.leaderboard()[0].mean_test_score.abs() < f32::EPSILON);
+        for prediction in classifier.predict(vec![vec![5.0_f32; 4]; 10]) {
+            assert!(prediction == 0.0 || prediction == 1.0);
+        }
+    }
+
+    #[test]
+    fn test_svc_multiclass() {
+        for strategy in [MulticlassStrategy::OneVsRest, MulticlassStrategy::OneVsOne] {
+            let settings = Settings::default_classification()
+                .only(Algorithm::SVC)
+                .shuffle_data(true)
+                .with_seed(0)
+                .with_number_of_folds(3)
+                .with_svc_settings(SVCParameters::default().with_multiclass(strategy));
+            let mut classifier =
+                SupervisedModel::new(smartcore::dataset::iris::load_dataset(), settings);
+            classifier.train();
+            assert!(classifier.leaderboard()[0].mean_test_score > 0.7);
+            classifier.predict(vec![vec![5.0_f32; 4]; 10]);
+        }
+    }
+
+    #[test]
+    fn test_extra_trees_and_bagging() {
+        let settings = Settings::default_classification()
+            .only(Algorithm::ExtraTreesClassifier)
+            .with_extra_trees_classifier_settings(
+                ExtraTreesClassifierParameters::default().with_n_trees(20),
+            );
+        test_from_settings(settings);
+
+        let settings = Settings::default_classification().only(Algorithm::Bagging {
+            base: Box::new(Algorithm::KNNClassifier),
+            n_estimators: 5,
+            max_samples: 1.0,
+            max_features: 0.5,
+        });
+        test_from_settings(settings);
+
+        // Single-row bootstrap samples hold one class, which logistic regression cannot fit
+        let mut classifier = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_classification().only(Algorithm::Bagging {
+                base: Box::new(Algorithm::LogisticRegression),
+                n_estimators: 10,
+                max_samples: 0.001,
+                max_features: 1.0,
+            }),
+        );
+        classifier.train();
+        assert!(classifier.leaderboard().is_empty());
+    }
+
+    #[test]
+    fn test_bernoulli_and_multinomial_nb() {
+        let settings = Settings::default_classification()
+            .only(Algorithm::BernoulliNaiveBayes)
+            .with_bernoulli_nb_settings(BernoulliNBParameters::default().with_binarize(10.0));
+        test_from_settings(settings);
+
+        let settings = Settings::default_classification()
+            .only(Algorithm::MultinomialNaiveBayes)
+            .with_multinomial_nb_settings(MultinomialNBParameters::default().with_alpha(0.5));
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_naive_bayes_eligibility() {
+        // PCA components can be negative, which rules out multinomial naive Bayes, and Bernoulli
+        // naive Bayes needs binary features when no threshold is given
+        let mut classifier = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_classification()
+                .skip(Algorithm::SVC)
+                .with_preprocessing(PreProcessing::ReplaceWithPCA {
+                    number_of_components: 5,
+                })
+                .with_bernoulli_nb_settings(BernoulliNBParameters {
+                    binarize: None,
+                    ..BernoulliNBParameters::default()
+                }),
+        );
+        classifier.train();
+        let algorithms: Vec<Algorithm> = classifier
+            .leaderboard()
+            .iter()
+            .map(|entry| entry.algorithm.clone())
+            .collect();
+        assert!(!algorithms.contains(&Algorithm::MultinomialNaiveBayes));
+        assert!(!algorithms.contains(&Algorithm::CategoricalNaiveBayes));
+        assert!(!algorithms.contains(&Algorithm::BernoulliNaiveBayes));
+        assert!(algorithms.contains(&Algorithm::GaussianNaiveBayes));
+    }
+
+    #[test]
+    fn test_mlp() {
+        let mut classifier = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_classification()
+                .only(Algorithm::MLPClassifier)
+                .with_seed(0)
+                .with_mlp_classifier_settings(
+                    MLPClassifierParameters::default()
+                        .with_hidden_layer_sizes(vec![16])
+                        .with_activation(Activation::Logistic)
+                        .with_early_stopping(10, 0.1),
+                ),
+        );
+        classifier.train();
+        assert!(classifier.leaderboard()[0].mean_test_score > 0.9);
+        classifier.predict(vec![vec![5.0_f32; 30]; 10]);
+    }
+
+    #[test]
+    fn test_dummy_baseline() {
+        for strategy in [
+            DummyClassifierStrategy::MostFrequent,
+            DummyClassifierStrategy::Stratified,
+            DummyClassifierStrategy::Prior,
+        ] {
+            let settings = Settings::default_classification()
+                .only(Algorithm::DummyClassifier)
+                .with_seed(0)
+                .with_dummy_classifier_settings(
+                    DummyClassifierParameters::default().with_strategy(strategy),
+                );
+            test_from_settings(settings);
+        }
+    }
+
+    #[test]
+    fn test_conformal_sets() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let (x_train, x_test) = x.split_at(400);
+        let (y_train, y_test) = dataset.target.split_at(400);
+        for (conformal, splitter) in [
+            (
+                Conformal::Split {
+                    calibration_fraction: 0.3,
+                },
+                Splitter::KFold,
+            ),
+            (Conformal::CVPlus { folds: 3 }, Splitter::StratifiedKFold),
+        ] {
+            let settings = || {
+                Settings::default_classification()
+                    .only(Algorithm::GaussianNaiveBayes)
+                    .shuffle_data(true)
+                    .with_seed(0)
+                    .with_splitter(splitter.clone())
+                    .with_conformal(conformal)
+            };
+            let mut classifier = SupervisedModel::new(
+                (x_train.to_vec(), y_train.to_vec()),
+                settings().with_coverage(0.9),
+            );
+            classifier.train();
+            let covered = classifier
+                .predict_set(x_test.to_vec())
+                .iter()
+                .zip(y_test)
+                .filter(|(set, y)| set.contains(y))
+                .count();
+            assert!(covered as f32 / y_test.len() as f32 > 0.8);
+
+            // Asking for more coverage than the calibration data allows gives every class
+            let mut classifier = SupervisedModel::new(
+                (x_train.to_vec(), y_train.to_vec()),
+                settings().with_coverage(0.999),
+            );
+            classifier.train();
+            assert!(classifier
+                .predict_set(x_test.to_vec())
+                .iter()
+                .all(|set| set.len() == 2));
+        }
+    }
+
+    #[test]
+    fn test_calibration() {
+        for (algorithm, calibration) in [
+            (
+                Algorithm::GaussianNaiveBayes,
+                Calibration::Sigmoid { folds: 3 },
+            ),
+            (
+                Algorithm::GaussianNaiveBayes,
+                Calibration::Isotonic { folds: 3 },
+            ),
+            (
+                Algorithm::GradientBoostingClassifier,
+                Calibration::Sigmoid { folds: 3 },
+            ),
+            (
+                Algorithm::ExtraTreesClassifier,
+                Calibration::Isotonic { folds: 3 },
+            ),
+        ] {
+            let mut classifier = SupervisedModel::new(
+                load_dataset(),
+                Settings::default_classification()
+                    .only(algorithm)
+                    .shuffle_data(true)
+                    .with_seed(0)
+                    .with_calibration(calibration),
+            );
+            classifier.train();
+            for probabilities in classifier.predict_proba(vec![vec![5.0_f32; 30]; 10]) {
+                assert_eq!(probabilities.len(), 2);
+                assert!(probabilities.iter().all(|p| (0.0..=1.0).contains(p)));
+                assert!((probabilities.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+            }
+            for entry in classifier.leaderboard() {
+                let brier_score = entry.brier_score.unwrap();
+                assert!((0.0..0.25).contains(&brier_score));
+                assert_eq!(
+                    entry.reliability.iter().map(|bin| bin.count).sum::<usize>(),
+                    569
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_self_training() {
+        let dataset = load_dataset();
+        let features: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+
+        // Keep the labels of every fifth row only
+        let (labeled, unlabeled): (Vec<_>, Vec<_>) = features
+            .into_iter()
+            .zip(dataset.target)
+            .enumerate()
+            .partition(|(i, _)| i % 5 == 0);
+        let (x, y): (Vec<_>, Vec<_>) = labeled.into_iter().map(|(_, row)| row).unzip();
+        let unlabeled: Vec<Vec<f32>> = unlabeled.into_iter().map(|(_, (row, _))| row).collect();
+
+        let mut classifier = SupervisedModel::new_with_unlabeled(
+            (x, y),
+            unlabeled,
+            Settings::default_classification()
+                .only(Algorithm::GaussianNaiveBayes)
+                .with_number_of_folds(3)
+                .with_holdout_fraction(0.25)
+                .shuffle_data(true)
+                .with_seed(0)
+                .with_self_training(SelfTraining::ConfidenceThreshold {
+                    threshold: 0.8,
+                    max_iterations: 2,
+                }),
+        );
+        classifier.train();
+
+        // The first round only sees labeled rows, and each later round adds pseudo-labels
+        let rounds = classifier.self_training_results();
+        assert!((2..=3).contains(&rounds.len()));
+        assert_eq!(rounds[0].pseudo_labeled, 0);
+        assert!(rounds[rounds.len() - 1].pseudo_labeled > 0);
+        assert!(rounds
+            .windows(2)
+            .all(|pair| pair[0].pseudo_labeled <= pair[1].pseudo_labeled));
+        assert!(rounds.iter().all(|round| round.holdout_score.is_some()));
+
+        // Pseudo-labeled rows are only trained on, so only labeled rows are ever scored
+        for entry in classifier.leaderboard() {
+            assert!(entry.reliability.iter().map(|bin| bin.count).sum::<usize>() < 114);
+        }
+        assert!(format!("{classifier}").contains("Self-Training Round"));
+        for prediction in classifier.predict(vec![vec![5.0_f32; 30]; 10]) {
+            assert!(prediction == 0.0 || prediction == 1.0);
+        }
+    }
+
+    #[test]
+    fn test_decision_threshold() {
+        let x = vec![vec![5.0_f32; 30]; 10];
+        let dataset = load_dataset();
+        let features: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let positives = |objective| {
+            let mut classifier = SupervisedModel::new(
+                load_dataset(),
+                Settings::default_classification()
+                    .only(Algorithm::GaussianNaiveBayes)
+                    .shuffle_data(true)
+                    .with_seed(0)
+                    .with_threshold_objective(objective),
+            );
+            classifier.train();
+            let threshold = classifier.decision_threshold().unwrap();
+            assert!((0.0..=1.0).contains(&threshold));
+            for prediction in classifier.predict(x.clone()) {
+                assert!(prediction == 0.0 || prediction == 1.0);
+            }
+            classifier
+                .predict(features.clone())
+                .iter()
+                .filter(|&&prediction| prediction == 1.0)
+                .count()
+        };
+
+        // Expensive false negatives make positive predictions more common than expensive false
+        // positives do
+        let cheap_negatives = positives(ThresholdObjective::Cost {
+            matrix: [[0.0, 20.0], [1.0, 0.0]],
+        });
+        let cheap_positives = positives(ThresholdObjective::Cost {
+            matrix: [[0.0, 1.0], [20.0, 0.0]],
+        });
+        assert!(cheap_positives > cheap_negatives);
+        positives(ThresholdObjective::F1);
+        positives(ThresholdObjective::RecallAtPrecision { precision: 0.95 });
+        positives(ThresholdObjective::YoudensJ);
+    }
+
+    #[test]
+    #[should_panic(expected = "Probabilities need an algorithm with scores")]
+    fn test_decision_threshold_needs_scores() {
+        let mut classifier = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_classification()
+                .only(Algorithm::DecisionTreeClassifier)
+                .with_threshold_objective(ThresholdObjective::F1),
+        );
+        classifier.train();
+    }
+
+    #[test]
+    fn test_multi_label() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        // The diagnosis, a large mean radius, and a label no row carries
+        let y: Vec<Vec<f32>> = x
+            .iter()
+            .zip(&dataset.target)
+            .map(|(row, &target)| vec![target, f32::from(row[0] > 14.0), 0.0])
+            .collect();
+
+        for multi_label in [MultiLabel::BinaryRelevance, MultiLabel::Chain] {
+            let mut model = MultiLabelModel::new(
+                (x.clone(), y.clone()),
+                Settings::default_classification()
+                    .only(Algorithm::DecisionTreeClassifier)
+                    .with_multi_label(multi_label)
+                    .with_multi_label_metric(MultiLabelMetric::HammingLoss),
+            );
+            model.train();
+            let display = format!("{model}");
+            for metric in ["Hamming Loss", "Subset Accuracy", "Micro F1", "Macro F1"] {
+                assert!(display.contains(metric));
+            }
+            assert!(display.contains(&format!("{multi_label}")));
+
+            // The predictions are a label-indicator matrix close to the training labels
+            let predictions = model.predict(x.clone());
+            assert_eq!(predictions.len(), x.len());
+            let wrong = predictions
+                .iter()
+                .zip(&y)
+                .flat_map(|(predicted, actual)| predicted.iter().zip(actual))
+                .filter(|(predicted, actual)| {
+                    assert!(**predicted == 0.0 || **predicted == 1.0);
+                    predicted != actual
+                })
+                .count();
+            assert!((wrong as f32) < 0.1 * (3 * x.len()) as f32);
+            assert!(predictions.iter().all(|row| row[2] == 0.0));
+
+            // The fitted model survives a save and load
+            model.save("tests/multi_label_model.aml");
+            let loaded = MultiLabelModel::new_from_file("tests/multi_label_model.aml");
+            std::fs::remove_file("tests/multi_label_model.aml").unwrap();
+            assert_eq!(predictions, loaded.predict(x.clone()));
+        }
+    }
+
     fn test_from_settings(settings: Settings) {
         // Check training
         let dataset = load_dataset();
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod clustering_tests {
+    use automl::{settings::*, *};
+    use smartcore::dataset::iris::load_dataset;
+
+    /// The iris measurements, without the species
+    fn iris() -> Vec<Vec<f32>> {
+        let dataset = load_dataset();
+        dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect()
+    }
+
+    #[test]
+    fn test_clustering_comparison() {
+        let x = iris();
+        for metric in [
+            ClusteringMetric::Silhouette,
+            ClusteringMetric::CalinskiHarabasz,
+            ClusteringMetric::DaviesBouldin,
+        ] {
+            let mut model = UnsupervisedModel::new(
+                x.clone(),
+                ClusteringSettings::default()
+                    .with_k_range(2..=4)
+                    .sorted_by(metric),
+            );
+            model.train();
+            let display = format!("{model}");
+            for algorithm in ["KMeans", "DBSCAN", "Hierarchical"] {
+                assert!(display.contains(algorithm));
+            }
+
+            // Every training row is assigned to a cluster or to noise
+            let clusters = model.predict(x.clone());
+            assert_eq!(clusters.len(), x.len());
+            assert!(clusters.iter().all(|&cluster| cluster >= -1.0));
+
+            // The chosen clustering survives a save and load
+            model.save("tests/unsupervised_model.aml");
+            let loaded = UnsupervisedModel::new_from_file("tests/unsupervised_model.aml");
+            std::fs::remove_file("tests/unsupervised_model.aml").unwrap();
+            assert_eq!(clusters, loaded.predict(x.clone()));
+        }
+    }
+
+    #[test]
+    fn test_hierarchical_linkages() {
+        let x = iris();
+        for linkage in [
+            Linkage::Ward,
+            Linkage::Average,
+            Linkage::Complete,
+            Linkage::Single,
+        ] {
+            let mut model = UnsupervisedModel::new(
+                x.clone(),
+                ClusteringSettings::default()
+                    .only(ClusteringAlgorithm::Hierarchical)
+                    .with_k_range(3..=3)
+                    .with_hierarchical_settings(
+                        HierarchicalParameters::default().with_linkage(linkage),
+                    ),
+            );
+            model.train();
+
+            // Setosa, the first 50 rows, is well separated from the other species
+            let clusters = model.predict(x.clone());
+            assert!(clusters[..50].iter().all(|&cluster| cluster == clusters[0]));
+            assert!(clusters[50..].iter().all(|&cluster| cluster != clusters[0]));
+        }
+    }
+
+    #[test]
+    fn test_dbscan_noise() {
+        let mut x = iris();
+        x.push(vec![50.0; 4]);
+        let mut model = UnsupervisedModel::new(
+            x,
+            ClusteringSettings::default()
+                .only(ClusteringAlgorithm::DBSCAN)
+                .with_dbscan_settings(DBSCANParameters::default().with_eps(0.8)),
+        );
+        model.train();
+        assert_eq!(model.predict(vec![vec![50.0; 4]]), vec![-1.0]);
+        assert!(model.predict(vec![vec![5.0, 3.4, 1.5, 0.2]])[0] >= 0.0);
+    }
+
+    #[test]
+    fn test_settings_save_and_load() {
+        let settings = ClusteringSettings::default()
+            .skip(ClusteringAlgorithm::DBSCAN)
+            .with_k_range(3..=6)
+            .with_kmeans_settings(KMeansParameters::default().with_max_iter(50));
+        settings.save("tests/clustering_settings.yaml");
+        let loaded = ClusteringSettings::new_from_file("tests/clustering_settings.yaml");
+        std::fs::remove_file("tests/clustering_settings.yaml").unwrap();
+        assert_eq!(format!("{settings}"), format!("{loaded}"));
+        assert!(!format!("{loaded}").contains("Radius"));
+    }
+}
@@ -3,6 +3,10 @@ mod regression_tests {
     use automl::{settings::*, *};
     use smartcore::dataset::diabetes::load_dataset;
 
+    // Tests that shuffle, resample or subsample the data skip the elastic net and the LASSO: on
+    // some folds, the line search of smartcore's interior point optimizer stops making progress in
+    // single precision and, since it never counts its iterations, the fit never returns.
+
     #[test]
     #[cfg(feature = "csv")]
     fn test_new_from_csv() {
@@ -65,6 +69,650 @@ mod regression_tests {
         test_from_settings(settings);
     }
 
+    #[test]
+    fn test_voting() {
+        let settings = Settings::default_regression()
+            .skip(Algorithm::ElasticNet)
+            .with_final_model(FinalModel::default_voting());
+        test_from_settings(settings);
+
+        let settings = Settings::default_regression()
+            .skip(Algorithm::ElasticNet)
+            .with_final_model(FinalModel::Voting {
+                top_k: 2,
+                weights: Some(vec![2.0, 1.0]),
+                soft: false,
+            });
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_ensemble_selection() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .skip(Algorithm::Lasso)
+                .with_holdout_fraction(0.2)
+                .with_final_model(FinalModel::EnsembleSelection { iterations: 10 }),
+        );
+        regressor.train();
+        assert!(regressor.holdout_score().is_some());
+        let table = format!("{regressor}");
+        let members = &table[table.find("Ensemble Member").unwrap()..];
+        assert!(!members.contains("Dummy"));
+        regressor.predict(vec![vec![5.0_f32; 10]; 10]);
+    }
+
+    #[test]
+    fn test_mlp() {
+        let settings = Settings::default_regression()
+            .only(Algorithm::MLPRegressor)
+            .with_seed(0);
+        test_from_settings(settings);
+
+        let settings = Settings::default_regression()
+            .only(Algorithm::MLPRegressor)
+            .with_seed(0)
+            .with_mlp_regressor_settings(
+                MLPRegressorParameters::default()
+                    .with_hidden_layer_sizes(vec![16, 8])
+                    .with_activation(Activation::Tanh)
+                    .with_optimizer(Optimizer::default_sgd())
+                    .with_learning_rate(0.01)
+                    .with_batch_size(32)
+                    .with_early_stopping(5, 0.2),
+            );
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_robust_and_generalized_linear() {
+        let settings = Settings::default_regression()
+            .only(Algorithm::Huber)
+            .with_huber_settings(HuberParameters::default().with_epsilon(1.5));
+        test_from_settings(settings);
+
+        let settings = Settings::default_regression()
+            .only(Algorithm::Quantile)
+            .with_quantile_settings(QuantileParameters::default().with_quantile(0.9));
+        test_from_settings(settings);
+
+        let settings = Settings::default_regression()
+            .only(Algorithm::Poisson)
+            .sorted_by(Metric::PoissonDeviance);
+        test_from_settings(settings);
+
+        let settings = Settings::default_regression()
+            .only(Algorithm::Gamma)
+            .sorted_by(Metric::GammaDeviance)
+            .with_gamma_settings(GammaParameters::default().with_alpha(0.1));
+        test_from_settings(settings);
+
+        let settings = Settings::default_regression()
+            .only(Algorithm::Tweedie)
+            .with_tweedie_settings(TweedieParameters::default().with_power(1.2));
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_generalized_linear_eligibility() {
+        // Centered targets go negative, which rules out the log-link models
+        let mut dataset = load_dataset();
+        let mean = dataset.target.iter().sum::<f32>() / dataset.target.len() as f32;
+        dataset.target = dataset.target.iter().map(|y| y - mean).collect();
+        let mut settings = Settings::default_regression();
+        for algorithm in [
+            Algorithm::ElasticNet,
+            Algorithm::Lasso,
+            Algorithm::SVR,
+            Algorithm::DecisionTreeRegressor,
+            Algorithm::RandomForestRegressor,
+            Algorithm::KNNRegressor,
+            Algorithm::GradientBoostingRegressor,
+            Algorithm::ExtraTreesRegressor,
+            Algorithm::bagging(Algorithm::DecisionTreeRegressor),
+            Algorithm::MLPRegressor,
+        ] {
+            settings = settings.skip(algorithm);
+        }
+        let mut regressor = SupervisedModel::new(dataset, settings);
+        regressor.train();
+        let algorithms: Vec<Algorithm> = regressor
+            .leaderboard()
+            .iter()
+            .map(|entry| entry.algorithm.clone())
+            .collect();
+        assert!(algorithms.contains(&Algorithm::Huber));
+        assert!(algorithms.contains(&Algorithm::Quantile));
+        assert!(!algorithms.contains(&Algorithm::Poisson));
+        assert!(!algorithms.contains(&Algorithm::Gamma));
+        assert!(!algorithms.contains(&Algorithm::Tweedie));
+    }
+
+    #[test]
+    fn test_gaussian_process() {
+        for kernel in [
+            GaussianProcessKernel::RBF,
+            GaussianProcessKernel::Matern { nu: 1.5 },
+            GaussianProcessKernel::Linear,
+        ] {
+            let settings = Settings::default_regression()
+                .only(Algorithm::GaussianProcessRegressor)
+                .with_number_of_folds(3)
+                .with_gaussian_process_regressor_settings(
+                    GaussianProcessRegressorParameters::default().with_kernel(kernel),
+                );
+            let mut regressor = SupervisedModel::new(small_dataset(), settings);
+            regressor.train();
+            let (means, stds) = regressor
+                .predict_with_std(vec![vec![0.01_f32; 10]; 5])
+                .unwrap();
+            assert_eq!(means, regressor.predict(vec![vec![0.01_f32; 10]; 5]));
+            assert!(stds.iter().all(|std| std.is_finite() && *std > 0.0));
+        }
+
+        // Without a Gaussian process as the final model there is no uncertainty
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression().only(Algorithm::Linear),
+        );
+        regressor.train();
+        assert!(regressor
+            .predict_with_std(vec![vec![0.01_f32; 10]; 5])
+            .is_none());
+
+        // Larger datasets leave the Gaussian process out of the comparison
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression().only(Algorithm::GaussianProcessRegressor),
+        );
+        regressor.train();
+        assert!(regressor.leaderboard().is_empty());
+    }
+
+    #[test]
+    fn test_conformal_intervals() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let (x_train, x_test) = x.split_at(350);
+        let (y_train, y_test) = dataset.target.split_at(350);
+        // Each CV+ fold model keeps the PCA it was trained with
+        for (conformal, preprocessing) in [
+            (
+                Conformal::Split {
+                    calibration_fraction: 0.3,
+                },
+                PreProcessing::None,
+            ),
+            (Conformal::CVPlus { folds: 5 }, PreProcessing::None),
+            (
+                Conformal::CVPlus { folds: 5 },
+                PreProcessing::ReplaceWithPCA {
+                    number_of_components: 8,
+                },
+            ),
+        ] {
+            let mut regressor = SupervisedModel::new(
+                (x_train.to_vec(), y_train.to_vec()),
+                Settings::default_regression()
+                    .only(Algorithm::Linear)
+                    .shuffle_data(true)
+                    .with_seed(0)
+                    .with_preprocessing(preprocessing)
+                    .with_conformal(conformal)
+                    .with_coverage(0.9),
+            );
+            regressor.train();
+            let intervals = regressor.predict_interval(x_test.to_vec());
+            let predictions = regressor.predict(x_test.to_vec());
+            for ((lower, upper), prediction) in intervals.iter().zip(&predictions) {
+                assert!(lower.is_finite() && upper.is_finite());
+                assert!(lower <= prediction && prediction <= upper);
+            }
+            let covered = intervals
+                .iter()
+                .zip(y_test)
+                .filter(|((lower, upper), &y)| *lower <= y && y <= *upper)
+                .count();
+            assert!(covered as f32 / y_test.len() as f32 > 0.75);
+
+            // The calibration survives a save and load
+            regressor.save("tests/conformal_model.aml");
+            let loaded = SupervisedModel::new_from_file("tests/conformal_model.aml");
+            std::fs::remove_file("tests/conformal_model.aml").unwrap();
+            assert_eq!(intervals, loaded.predict_interval(x_test.to_vec()));
+        }
+
+        // Jackknife+ with too little data for the target coverage gives infinite intervals
+        let mut regressor = SupervisedModel::new(
+            (x_train[..18].to_vec(), y_train[..18].to_vec()),
+            Settings::default_regression()
+                .only(Algorithm::Linear)
+                .with_number_of_folds(3)
+                .with_conformal(Conformal::JackknifePlus)
+                .with_coverage(0.95),
+        );
+        regressor.train();
+        assert!(regressor
+            .predict_interval(x_test[..5].to_vec())
+            .iter()
+            .all(|(lower, upper)| lower.is_infinite() && upper.is_infinite()));
+    }
+
+    /// The first 150 rows of the diabetes dataset
+    fn small_dataset() -> smartcore::dataset::Dataset<f32, f32> {
+        let mut dataset = load_dataset();
+        dataset.num_samples = 150;
+        dataset.data.truncate(150 * dataset.num_features);
+        dataset.target.truncate(150);
+        dataset
+    }
+
+    #[test]
+    fn test_time_series_split() {
+        let settings = Settings::default_regression().with_splitter(Splitter::TimeSeriesSplit);
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_group_kfold() {
+        for preprocessing in [
+            PreProcessing::None,
+            PreProcessing::ReplaceWithPCA {
+                number_of_components: 5,
+            },
+        ] {
+            let settings = Settings::default_regression()
+                .with_number_of_folds(3)
+                .with_splitter(Splitter::GroupKFold { group_column: 0 })
+                .with_preprocessing(preprocessing);
+            test_from_settings(settings);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The group column 10 is out of range for data with 10 features.")]
+    fn test_group_kfold_column_out_of_range() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression()
+                .only(Algorithm::Linear)
+                .with_splitter(Splitter::GroupKFold { group_column: 10 }),
+        );
+        regressor.train();
+    }
+
+    #[test]
+    fn test_shuffle_split() {
+        let settings = Settings::default_regression()
+            .with_number_of_folds(3)
+            .with_seed(0)
+            .with_splitter(Splitter::ShuffleSplit { test_fraction: 0.2 });
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_seed_is_reproducible() {
+        let settings = || {
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .shuffle_data(true)
+                .with_seed(42)
+                .with_final_model(FinalModel::default_blending())
+        };
+        let mut first = SupervisedModel::new(load_dataset(), settings());
+        let mut second = SupervisedModel::new(load_dataset(), settings());
+        first.train();
+        second.train();
+        assert_eq!(
+            first.predict(vec![vec![0.01_f32; 10]; 10]),
+            second.predict(vec![vec![0.01_f32; 10]; 10])
+        );
+    }
+
+    #[test]
+    fn test_holdout_fraction() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .with_holdout_fraction(0.2),
+        );
+        regressor.train();
+        assert!(regressor.holdout_score().is_some());
+    }
+
+    #[test]
+    fn test_time_series_holdout_is_latest() {
+        // A growing target, so the mean of the earliest steps is far off the latest steps
+        let x: Vec<Vec<f32>> = (0..100).map(|t| vec![t as f32]).collect();
+        let y: Vec<f32> = (0..100).map(|t| (t * t) as f32).collect();
+        let mut regressor = SupervisedModel::new(
+            (x, y),
+            Settings::default_regression()
+                .only(Algorithm::DummyRegressor)
+                .sorted_by(Metric::MeanAbsoluteError)
+                .with_splitter(Splitter::TimeSeriesSplit)
+                .with_holdout_fraction(0.2),
+        );
+        regressor.train();
+        assert!(regressor.holdout_score().unwrap() > 5000.0);
+    }
+
+    #[test]
+    fn test_user_supplied_holdout() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let (x_train, x_test) = x.split_at(400);
+        let (y_train, y_test) = dataset.target.split_at(400);
+        let mut regressor = SupervisedModel::new_with_holdout(
+            (x_train.to_vec(), y_train.to_vec()),
+            (x_test.to_vec(), y_test.to_vec()),
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .with_final_model(FinalModel::default_blending()),
+        );
+        regressor.train();
+        assert!(regressor.holdout_score().is_some());
+        assert!(format!("{regressor}").contains("Holdout"));
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression().skip(Algorithm::ElasticNet),
+        );
+        regressor.train();
+        let leaderboard = regressor.leaderboard();
+        assert!(leaderboard[0].p_value.is_none());
+        assert!(leaderboard[0].tied_with_best);
+        for entry in &leaderboard {
+            assert_eq!(entry.test_scores.len(), 10);
+            let (low, high) = entry.confidence_interval;
+            assert!(low <= entry.mean_test_score && entry.mean_test_score <= high);
+        }
+        assert!(leaderboard[1..]
+            .iter()
+            .all(|entry| entry.p_value.is_some_and(|p| (0.0..=1.0).contains(&p))));
+        let display = format!("{regressor}");
+        assert!(display.contains("95% CI"));
+        assert!(display.contains("p-value vs. Best"));
+    }
+
+    #[test]
+    fn test_nested_cv() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .with_nested_cv(3),
+        );
+        regressor.train();
+        assert_eq!(regressor.nested_cv_results().len(), 3);
+        assert!(regressor
+            .nested_cv_results()
+            .iter()
+            .all(|fold| fold.score.is_finite()));
+        assert!(format!("{regressor}").contains("Outer Fold"));
+    }
+
+    #[test]
+    fn test_successive_halving() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .skip(Algorithm::Lasso)
+                .skip(Algorithm::DummyRegressor)
+                .skip(Algorithm::MLPRegressor)
+                .skip(Algorithm::Huber)
+                .skip(Algorithm::Quantile)
+                .skip(Algorithm::Poisson)
+                .skip(Algorithm::Gamma)
+                .skip(Algorithm::Tweedie)
+                .with_halving(Halving::Successive {
+                    min_fraction: 0.25,
+                    reduction_factor: 3,
+                }),
+        );
+        regressor.train();
+        // Nine candidates are cut to three, then to one, before the full cross-validation
+        assert_eq!(regressor.leaderboard().len(), 1);
+    }
+
+    #[test]
+    fn test_hyperband() {
+        let settings = Settings::default_regression()
+            .skip(Algorithm::ElasticNet)
+            .skip(Algorithm::Lasso)
+            .with_halving(Halving::Hyperband {
+                min_fraction: 0.25,
+                reduction_factor: 2,
+            });
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_gradient_boosting() {
+        let settings = Settings::default_regression()
+            .only(Algorithm::GradientBoostingRegressor)
+            .with_gradient_boosting_regressor_settings(
+                GradientBoostingRegressorParameters::default()
+                    .with_loss(GradientBoostingLoss::AbsoluteError)
+                    .with_subsample(0.8)
+                    .with_early_stopping(5, 0.1),
+            );
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_absolute_error_boosting_beats_median() {
+        // A target in the hundreds of thousands, far wider than the unit steps of the gradients
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let y: Vec<f32> = dataset
+            .target
+            .iter()
+            .map(|target| 1000.0 * target)
+            .collect();
+        let mean_absolute_error = |algorithm: Algorithm| {
+            let mut regressor = SupervisedModel::new(
+                (x.clone(), y.clone()),
+                Settings::default_regression()
+                    .only(algorithm)
+                    .sorted_by(Metric::MeanAbsoluteError)
+                    .with_gradient_boosting_regressor_settings(
+                        GradientBoostingRegressorParameters::default()
+                            .with_loss(GradientBoostingLoss::AbsoluteError),
+                    )
+                    .with_dummy_regressor_settings(
+                        DummyRegressorParameters::default()
+                            .with_strategy(DummyRegressorStrategy::Median),
+                    ),
+            );
+            regressor.train();
+            regressor.leaderboard()[0].mean_test_score
+        };
+        let boosted = mean_absolute_error(Algorithm::GradientBoostingRegressor);
+        let median = mean_absolute_error(Algorithm::DummyRegressor);
+        assert!(boosted < 0.9 * median);
+    }
+
+    #[test]
+    fn test_extra_trees() {
+        let settings = Settings::default_regression()
+            .only(Algorithm::ExtraTreesRegressor)
+            .with_extra_trees_regressor_settings(
+                ExtraTreesRegressorParameters::default()
+                    .with_n_trees(20)
+                    .with_min_samples_leaf(2),
+            );
+        test_from_settings(settings);
+    }
+
+    #[test]
+    fn test_bagging() {
+        // Bagged KNN and bagged decision trees are compared in one run
+        let bagged_knn = Algorithm::Bagging {
+            base: Box::new(Algorithm::KNNRegressor),
+            n_estimators: 5,
+            max_samples: 0.8,
+            max_features: 0.5,
+        };
+        let bagged_tree = Algorithm::bagging(Algorithm::DecisionTreeRegressor);
+        let settings = Settings::default_regression()
+            .only(bagged_knn.clone())
+            .with_bagging(bagged_tree.clone())
+            .with_seed(0);
+        let mut regressor = SupervisedModel::new(load_dataset(), settings);
+        regressor.train();
+        let leaderboard = regressor.leaderboard();
+        assert_eq!(leaderboard.len(), 2);
+        assert!(leaderboard
+            .iter()
+            .any(|entry| entry.algorithm == bagged_knn));
+        assert!(leaderboard
+            .iter()
+            .any(|entry| entry.algorithm == bagged_tree));
+
+        // The fitted ensemble survives a save and load
+        regressor.save("tests/bagging_model.aml");
+        let loaded = SupervisedModel::new_from_file("tests/bagging_model.aml");
+        std::fs::remove_file("tests/bagging_model.aml").unwrap();
+        assert_eq!(
+            regressor.predict(vec![vec![0.01_f32; 10]; 5]),
+            loaded.predict(vec![vec![0.01_f32; 10]; 5])
+        );
+    }
+
+    #[test]
+    fn test_dummy_baseline() {
+        let mut regressor = SupervisedModel::new(
+            load_dataset(),
+            Settings::default_regression()
+                .skip(Algorithm::ElasticNet)
+                .with_dummy_regressor_settings(
+                    DummyRegressorParameters::default()
+                        .with_strategy(DummyRegressorStrategy::Quantile(0.75)),
+                ),
+        );
+        regressor.train();
+        assert!(regressor
+            .leaderboard()
+            .iter()
+            .any(|entry| entry.algorithm == Algorithm::DummyRegressor));
+        assert!(format!("{regressor}").contains("vs. Baseline"));
+    }
+
+    #[test]
+    fn test_best_passes_over_baseline() {
+        // Features and target are unrelated noise, so the mean beats every model of the features
+        let noise = |i: usize| ((i * 7919 + 13) % 101) as f32 / 101.0;
+        let x: Vec<Vec<f32>> = (0..60)
+            .map(|row| (0..8).map(|col| noise(row * 8 + col + 1000)).collect())
+            .collect();
+        let y: Vec<f32> = (0..60).map(|row| noise(row * 31)).collect();
+        // Leave out the models that shrink to the mean themselves, which can edge out the baseline
+        let mut regressor = SupervisedModel::new(
+            (x.clone(), y),
+            Settings::default_regression()
+                .skip(Algorithm::SVR)
+                .skip(Algorithm::Lasso)
+                .skip(Algorithm::ElasticNet)
+                .with_seed(0),
+        );
+        regressor.train();
+        assert!(regressor.leaderboard()[0].algorithm == Algorithm::DummyRegressor);
+
+        // The final model is the best model of the features, not the constant baseline
+        let predictions = regressor.predict(x[..10].to_vec());
+        assert!(predictions.iter().any(|&p| p != predictions[0]));
+    }
+
+    #[test]
+    fn test_multi_output() {
+        let dataset = load_dataset();
+        let x: Vec<Vec<f32>> = dataset
+            .data
+            .chunks(dataset.num_features)
+            .map(<[f32]>::to_vec)
+            .collect();
+        let y: Vec<Vec<f32>> = x
+            .iter()
+            .zip(&dataset.target)
+            .map(|(row, &target)| vec![target, 100.0 * row[2] + 0.5 * target, row[3]])
+            .collect();
+        let x_test = x[..10].to_vec();
+
+        let mut model = MultiOutputModel::new(
+            (x.clone(), y),
+            Settings::default_regression()
+                .only(Algorithm::Linear)
+                .with_multi_output(MultiOutput::Chain),
+        );
+        model.train();
+        let predictions = model.predict(x_test.clone());
+        assert_eq!(predictions.len(), 10);
+        assert!(predictions.iter().all(|row| row.len() == 3));
+        assert!(format!("{model}").contains("Joint"));
+
+        // The joint linear fit matches a linear regression of each target alone
+        let mut regressor = SupervisedModel::new(
+            (x, dataset.target),
+            Settings::default_regression().only(Algorithm::Linear),
+        );
+        regressor.train();
+        for (joint, single) in predictions.iter().zip(regressor.predict(x_test.clone())) {
+            assert!((joint[0] - single).abs() < 1e-2 * single.abs().max(1.0));
+        }
+
+        // Other algorithms are fit per target or as a chain
+        for multi_output in [MultiOutput::PerTarget, MultiOutput::Chain] {
+            let dataset = load_dataset();
+            let x: Vec<Vec<f32>> = dataset
+                .data
+                .chunks(dataset.num_features)
+                .map(<[f32]>::to_vec)
+                .collect();
+            let y: Vec<Vec<f32>> = dataset
+                .target
+                .iter()
+                .map(|&target| vec![target, -target])
+                .collect();
+            let mut model = MultiOutputModel::new(
+                (x, y),
+                Settings::default_regression()
+                    .only(Algorithm::Ridge)
+                    .with_multi_output(multi_output),
+            );
+            model.train();
+            assert!(format!("{model}").contains(&format!("{multi_output}")));
+
+            // The fitted model survives a save and load
+            model.save("tests/multi_output_model.aml");
+            let loaded = MultiOutputModel::new_from_file("tests/multi_output_model.aml");
+            std::fs::remove_file("tests/multi_output_model.aml").unwrap();
+            assert_eq!(
+                model.predict(x_test.clone()),
+                loaded.predict(x_test.clone())
+            );
+        }
+    }
+
     fn test_from_settings(settings: Settings) {
         // Check training
         let dataset = load_dataset();
@@ -78,4 +726,71 @@ mod regression_tests {
         // Try to predict something
         regressor.predict(vec![vec![5.0_f32; 10]; 10]);
     }
+
+    #[test]
+    fn test_forecast() {
+        // A trend with a weekly season, which a linear model of the lags captures exactly
+        let series =
+            |t: usize| 10.0 + 0.5 * t as f32 + [0.0, 1.0, 4.0, 2.0, -1.0, -3.0, -3.0][t % 7];
+        let y: Vec<f32> = (0..84).map(series).collect();
+        let mut model = ForecastModel::new(
+            y,
+            Settings::default_regression()
+                .only(Algorithm::Linear)
+                .with_number_of_folds(3)
+                .with_forecast_settings(
+                    ForecastParameters::default()
+                        .with_lags(vec![1, 7])
+                        .with_rolling_windows(vec![7])
+                        .with_deltas(1),
+                ),
+        );
+        model.train();
+        assert!(format!("{model}").contains("Linear"));
+
+        // Forecasts of two weeks follow the trend and the season
+        let forecasts = model.forecast(14);
+        assert_eq!(forecasts.len(), 14);
+        for (step, forecast) in forecasts.iter().enumerate() {
+            assert!((forecast - series(84 + step)).abs() < 1e-2);
+        }
+
+        // The model survives a save and load
+        model.save("tests/forecast_model.aml");
+        let loaded = ForecastModel::new_from_file("tests/forecast_model.aml");
+        std::fs::remove_file("tests/forecast_model.aml").unwrap();
+        assert_eq!(forecasts, loaded.forecast(14));
+    }
+
+    #[test]
+    fn test_forecast_with_exogenous() {
+        // Demand that follows its own level and jumps on promotion days
+        let promotion = |t: usize| if t % 5 == 3 { 1.0 } else { 0.0 };
+        let x: Vec<Vec<f32>> = (0..80).map(|t| vec![promotion(t)]).collect();
+        let mut y = vec![20.0_f32];
+        for t in 1..80 {
+            y.push(0.5f32.mul_add(y[t - 1], 10.0) + 8.0 * promotion(t));
+        }
+        let mut model = ForecastModel::new_with_exogenous(
+            (x, y.clone()),
+            Settings::default_regression()
+                .only(Algorithm::Linear)
+                .with_number_of_folds(3)
+                .with_forecast_settings(
+                    ForecastParameters::default()
+                        .with_lags(vec![1])
+                        .with_rolling_windows(vec![])
+                        .with_deltas(0),
+                ),
+        );
+        model.train();
+
+        let future: Vec<Vec<f32>> = (80..86).map(|t| vec![promotion(t)]).collect();
+        let forecasts = model.forecast_with_exogenous(future.clone());
+        let mut last = y[79];
+        for (forecast, row) in forecasts.iter().zip(&future) {
+            last = 0.5f32.mul_add(last, 10.0) + 8.0 * row[0];
+            assert!((forecast - last).abs() < 1e-2);
+        }
+    }
 }